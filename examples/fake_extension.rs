@@ -0,0 +1,66 @@
+//! Extension factice scriptée : lance l'hôte native messaging, rejoue une séquence
+//! d'événements d'onglets et affiche les réponses ainsi que l'état partagé.
+//!
+//! Usage : cargo run --example fake_extension -- <chemin vers sup_mtracker_native_host>
+use std::io::Read;
+use std::process::{Command, Stdio};
+use sup_mtracker::native_messaging::{write_message, BrowserTabStore, ExtensionMessage};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let host_path = std::env::args()
+        .nth(1)
+        .ok_or("Usage: fake_extension <chemin vers sup_mtracker_native_host>")?;
+
+    let mut host = Command::new(host_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    let script = vec![
+        ExtensionMessage::Ping,
+        ExtensionMessage::ActiveTab {
+            tab_id: 1,
+            window_id: Some(1),
+            url: "https://github.com/SUP2Ak/sup_mtracker/issues/12".to_string(),
+            title: "Issue #12 · SUP2Ak/sup_mtracker".to_string(),
+            incognito: false,
+            audible: false,
+        },
+        ExtensionMessage::AudibleChanged {
+            tab_id: 2,
+            audible: true,
+            url: Some("https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string()),
+            title: Some("Never Gonna Give You Up - YouTube".to_string()),
+            incognito: false,
+        },
+        ExtensionMessage::TabClosed { tab_id: 2 },
+    ];
+
+    let mut stdin = host.stdin.take().ok_or("stdin de l'hôte indisponible")?;
+    let mut stdout = host.stdout.take().ok_or("stdout de l'hôte indisponible")?;
+
+    // L'hôte voit ce processus comme le navigateur
+    let store = BrowserTabStore::new();
+    for message in script {
+        write_message(&mut stdin, &message)?;
+
+        let mut length = [0u8; 4];
+        stdout.read_exact(&mut length)?;
+        let mut payload = vec![0u8; u32::from_ne_bytes(length) as usize];
+        stdout.read_exact(&mut payload)?;
+
+        println!("➡️ {:?}", message);
+        println!("⬅️ {}", String::from_utf8_lossy(&payload));
+        if let Some(state) = store.load(std::process::id()) {
+            println!("💾 {}", serde_json::to_string(&state)?);
+        }
+    }
+
+    // Fermer stdin simule la déconnexion de l'extension
+    drop(stdin);
+    let status = host.wait()?;
+    println!("✅ Hôte terminé: {}", status);
+    println!("🧹 État supprimé: {}", store.load(std::process::id()).is_none());
+
+    Ok(())
+}
@@ -0,0 +1,588 @@
+use crate::models::AppIdentity;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Entrée de la table de correspondance : les champs renseignés priment sur ceux
+/// lus dans le binaire ou le fichier .desktop
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IdentityOverride {
+    /// Nom de l'exécutable, sans tenir compte de la casse ni de ".exe" (ex: "code", "chrome.exe")
+    pub executable: String,
+    pub display_name: Option<String>,
+    pub vendor: Option<String>,
+    pub icon: Option<String>,
+}
+
+impl IdentityOverride {
+    pub fn new(executable: &str, display_name: &str, vendor: Option<&str>) -> Self {
+        Self {
+            executable: executable.to_string(),
+            display_name: Some(display_name.to_string()),
+            vendor: vendor.map(str::to_string),
+            icon: None,
+        }
+    }
+}
+
+/// Correspondances par défaut pour les applications courantes dont le binaire
+/// porte un nom peu parlant (ou pas de ressource de version sous Linux)
+pub fn default_overrides() -> Vec<IdentityOverride> {
+    vec![
+        IdentityOverride::new("code", "Visual Studio Code", Some("Microsoft Corporation")),
+        IdentityOverride::new("chrome", "Google Chrome", Some("Google LLC")),
+        IdentityOverride::new("msedge", "Microsoft Edge", Some("Microsoft Corporation")),
+        IdentityOverride::new("firefox", "Firefox", Some("Mozilla Corporation")),
+        IdentityOverride::new("discord", "Discord", Some("Discord Inc.")),
+        IdentityOverride::new("spotify", "Spotify", Some("Spotify AB")),
+        IdentityOverride::new("ms-teams", "Microsoft Teams", Some("Microsoft Corporation")),
+        IdentityOverride::new("slack", "Slack", Some("Slack Technologies")),
+    ]
+}
+
+/// Ce qu'on sait du processus pour l'identifier
+#[derive(Debug, Clone, Default)]
+pub struct IdentityHints<'a> {
+    pub pid: u32,
+    pub process_name: &'a str,
+    pub executable_path: Option<&'a str>,
+    /// Classe de la fenêtre principale (WM_CLASS sous X11)
+    pub window_class: Option<&'a str>,
+}
+
+/// Résolution nom d'affichage / éditeur / icône d'une application
+pub struct AppIdentityResolver {
+    overrides: Vec<IdentityOverride>,
+    cache: Mutex<HashMap<String, AppIdentity>>,
+    #[cfg(target_os = "linux")]
+    desktop_entries: OnceLock<Vec<desktop::DesktopEntry>>,
+}
+
+pub(crate) use platform::executable_path;
+
+static SHARED_RESOLVER: OnceLock<Arc<AppIdentityResolver>> = OnceLock::new();
+
+impl Default for AppIdentityResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AppIdentityResolver {
+    pub fn new() -> Self {
+        Self::with_overrides(Vec::new())
+    }
+
+    /// Les entrées fournies remplacent les correspondances par défaut de même exécutable
+    pub fn with_overrides(overrides: Vec<IdentityOverride>) -> Self {
+        let mut table = default_overrides();
+        for entry in overrides {
+            let key = normalize_executable(&entry.executable);
+            table.retain(|existing| normalize_executable(&existing.executable) != key);
+            table.push(entry);
+        }
+
+        Self {
+            overrides: table,
+            cache: Mutex::new(HashMap::new()),
+            #[cfg(target_os = "linux")]
+            desktop_entries: OnceLock::new(),
+        }
+    }
+
+    /// Résolveur commun, utilisé par défaut par le scanner
+    pub fn shared() -> Arc<Self> {
+        Arc::clone(SHARED_RESOLVER.get_or_init(|| Arc::new(Self::new())))
+    }
+
+    pub fn overrides(&self) -> &[IdentityOverride] {
+        &self.overrides
+    }
+
+    /// Vider le cache (application mise à jour, nouvelle table...)
+    pub fn clear_cache(&self) {
+        self.cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clear();
+    }
+
+    pub fn resolve(&self, hints: &IdentityHints) -> AppIdentity {
+        let executable_path = hints
+            .executable_path
+            .map(str::to_string)
+            .or_else(|| platform::executable_path(hints.pid));
+
+        // Même binaire et même classe de fenêtre : même identité
+        let key = format!(
+            "{}|{}",
+            executable_path.as_deref().unwrap_or(hints.process_name),
+            hints.window_class.unwrap_or_default()
+        );
+        if let Some(identity) = self.cache.lock().ok().and_then(|cache| cache.get(&key).cloned()) {
+            return identity;
+        }
+
+        let identity = self.resolve_uncached(hints, executable_path.as_deref());
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.insert(key, identity.clone());
+        }
+        identity
+    }
+
+    fn resolve_uncached(&self, hints: &IdentityHints, executable_path: Option<&str>) -> AppIdentity {
+        let mut identity = self.platform_identity(hints, executable_path).unwrap_or_else(|| AppIdentity {
+            display_name: fallback_display_name(hints.process_name),
+            vendor: None,
+            icon: None,
+            app_id: None,
+            source: "executable".to_string(),
+        });
+
+        #[cfg(windows)]
+        if identity.app_id.is_none() {
+            identity.app_id = platform::app_user_model_id(hints.pid);
+        }
+
+        // La table de correspondance a le dernier mot
+        let executable_name = executable_path
+            .and_then(|path| path.rsplit(['/', '\\']).next())
+            .unwrap_or(hints.process_name);
+        let entry = self.overrides.iter().find(|entry| {
+            let key = normalize_executable(&entry.executable);
+            key == normalize_executable(hints.process_name) || key == normalize_executable(executable_name)
+        });
+        if let Some(entry) = entry {
+            if let Some(display_name) = &entry.display_name {
+                identity.display_name = display_name.clone();
+                identity.source = "override".to_string();
+            }
+            if entry.vendor.is_some() {
+                identity.vendor = entry.vendor.clone();
+            }
+            if entry.icon.is_some() {
+                identity.icon = entry.icon.clone();
+            }
+        }
+
+        identity
+    }
+
+    /// Ressource de version du binaire PE (FileDescription, ProductName, CompanyName)
+    #[cfg(windows)]
+    fn platform_identity(&self, _hints: &IdentityHints, executable_path: Option<&str>) -> Option<AppIdentity> {
+        use crate::binfmt::PeFile;
+
+        let path = executable_path?;
+        let mut file = std::fs::File::open(path).ok()?;
+        let version = PeFile::parse(&mut file).ok()?.version_info(&mut file).ok()??;
+        // FileDescription est le nom affiché par le gestionnaire des tâches
+        let display_name = version
+            .file_description
+            .clone()
+            .or_else(|| version.product_name.clone())?;
+
+        Some(AppIdentity {
+            display_name,
+            vendor: version.company_name,
+            // L'icône est extraite du binaire lui-même
+            icon: Some(path.to_string()),
+            app_id: None,
+            source: "version_info".to_string(),
+        })
+    }
+
+    /// Fichier .desktop correspondant (StartupWMClass, puis Exec, puis identifiant du fichier)
+    #[cfg(target_os = "linux")]
+    fn platform_identity(&self, hints: &IdentityHints, executable_path: Option<&str>) -> Option<AppIdentity> {
+        let entries = self.desktop_entries.get_or_init(desktop::load_entries);
+        let entry = desktop::find_entry(entries, hints, executable_path)?;
+
+        Some(AppIdentity {
+            display_name: entry.name.clone(),
+            vendor: None,
+            icon: entry.icon.clone(),
+            app_id: Some(entry.id.clone()),
+            source: "desktop_entry".to_string(),
+        })
+    }
+}
+
+/// "Code.exe" -> "code"
+fn normalize_executable(name: &str) -> String {
+    let name = name.to_lowercase();
+    name.strip_suffix(".exe").map(str::to_string).unwrap_or(name)
+}
+
+/// Nom d'exécutable rendu présentable : "firefox-esr" -> "Firefox-esr", "Code.exe" -> "Code"
+fn fallback_display_name(process_name: &str) -> String {
+    let name = process_name.strip_suffix(".exe").or_else(|| process_name.strip_suffix(".EXE")).unwrap_or(process_name);
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::{CloseHandle, ERROR_SUCCESS};
+    use windows::Win32::Storage::Packaging::Appx::GetApplicationUserModelId;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    // APPLICATION_USER_MODEL_ID_MAX_LENGTH
+    const AUMID_MAX_LENGTH: usize = 130;
+
+    pub fn executable_path(pid: u32) -> Option<String> {
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let mut buffer = [0u16; 1024];
+            let mut size = buffer.len() as u32;
+            let result = QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, PWSTR(buffer.as_mut_ptr()), &mut size);
+            let _ = CloseHandle(process);
+            result.ok()?;
+            Some(String::from_utf16_lossy(&buffer[..size as usize]))
+        }
+    }
+
+    /// Identifiant AUMID des applications empaquetées (MSIX/UWP), absent pour les autres
+    pub fn app_user_model_id(pid: u32) -> Option<String> {
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+            let mut buffer = [0u16; AUMID_MAX_LENGTH];
+            let mut length = buffer.len() as u32;
+            let status = GetApplicationUserModelId(process, &mut length, Some(PWSTR(buffer.as_mut_ptr())));
+            let _ = CloseHandle(process);
+            if status != ERROR_SUCCESS {
+                return None;
+            }
+            // La longueur retournée inclut le caractère nul final
+            let end = buffer.iter().position(|word| *word == 0).unwrap_or(length as usize);
+            Some(String::from_utf16_lossy(&buffer[..end])).filter(|id| !id.is_empty())
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    pub fn executable_path(pid: u32) -> Option<String> {
+        crate::linux::procfs::exe_path(pid)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod desktop {
+    use super::IdentityHints;
+    use std::collections::HashSet;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    /// Groupe [Desktop Entry] d'un fichier .desktop
+    #[derive(Debug, Clone)]
+    pub struct DesktopEntry {
+        /// Identifiant freedesktop (nom du fichier sans ".desktop", ex: "org.gnome.Nautilus")
+        pub id: String,
+        pub name: String,
+        pub icon: Option<String>,
+        /// Programme lancé par la ligne Exec (nom de fichier seul), absent pour un shell ou un interpréteur
+        pub program: Option<String>,
+        /// Chemin résolu du programme quand la ligne Exec le donne en absolu
+        pub program_path: Option<String>,
+        pub startup_wm_class: Option<String>,
+    }
+
+    /// Entrées des répertoires applications XDG, les premiers répertoires masquant les suivants
+    pub fn load_entries() -> Vec<DesktopEntry> {
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
+
+        for directory in application_dirs() {
+            let Ok(files) = fs::read_dir(&directory) else {
+                continue;
+            };
+            for file in files.flatten() {
+                let path = file.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some("desktop") {
+                    continue;
+                }
+                let Some(id) = path.file_stem().and_then(|stem| stem.to_str()).map(str::to_string) else {
+                    continue;
+                };
+                if !seen.insert(id.clone()) {
+                    continue;
+                }
+                if let Some(entry) = parse_entry(&path, id) {
+                    entries.push(entry);
+                }
+            }
+        }
+
+        entries
+    }
+
+    pub fn find_entry<'a>(
+        entries: &'a [DesktopEntry],
+        hints: &IdentityHints,
+        executable_path: Option<&str>,
+    ) -> Option<&'a DesktopEntry> {
+        let process_name = hints.process_name.to_lowercase();
+        let executable_name = executable_path
+            .and_then(|path| Path::new(path).file_name())
+            .map(|name| name.to_string_lossy().to_lowercase());
+        let window_class = hints.window_class.map(str::to_lowercase).filter(|class| !class.is_empty());
+
+        // StartupWMClass : correspondance la plus fiable (Electron, Java...)
+        let by_class = window_class.as_ref().and_then(|class| {
+            entries
+                .iter()
+                .find(|entry| entry.startup_wm_class.as_ref().map(|wm| wm.to_lowercase()).as_ref() == Some(class))
+        });
+
+        by_class
+            .or_else(|| {
+                entries.iter().find(|entry| {
+                    // Chemin absolu dans Exec : seul ce binaire correspond, pas un homonyme d'un autre répertoire
+                    if let Some(program_path) = &entry.program_path {
+                        return executable_path == Some(program_path.as_str());
+                    }
+                    entry.program.as_ref().is_some_and(|program| {
+                        let program = program.to_lowercase();
+                        program == process_name || Some(&program) == executable_name.as_ref()
+                    })
+                })
+            })
+            .or_else(|| {
+                // "code.desktop" pour le processus "code", "org.mozilla.firefox" pour la classe "firefox"
+                entries.iter().find(|entry| {
+                    let id = entry.id.to_lowercase();
+                    let last = id.rsplit('.').next().unwrap_or(&id).to_string();
+                    [Some(&process_name), window_class.as_ref()]
+                        .into_iter()
+                        .flatten()
+                        .any(|candidate| *candidate == id || *candidate == last)
+                })
+            })
+    }
+
+    fn application_dirs() -> Vec<PathBuf> {
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        let data_home = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|home| home.join(".local/share")));
+        let data_dirs = std::env::var("XDG_DATA_DIRS")
+            .ok()
+            .filter(|dirs| !dirs.is_empty())
+            .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+
+        let mut dirs: Vec<PathBuf> = data_home.into_iter().collect();
+        dirs.extend(data_dirs.split(':').filter(|dir| !dir.is_empty()).map(PathBuf::from));
+        // Flatpak et Snap exportent leurs lanceurs hors de XDG_DATA_DIRS quand la session ne les déclare pas
+        dirs.push(PathBuf::from("/var/lib/flatpak/exports/share"));
+        dirs.push(PathBuf::from("/var/lib/snapd/desktop"));
+        if let Some(home) = &home {
+            dirs.push(home.join(".local/share/flatpak/exports/share"));
+        }
+
+        let mut seen = HashSet::new();
+        dirs.into_iter()
+            .map(|dir| dir.join("applications"))
+            .filter(|dir| seen.insert(dir.clone()))
+            .collect()
+    }
+
+    fn parse_entry(path: &Path, id: String) -> Option<DesktopEntry> {
+        let content = fs::read_to_string(path).ok()?;
+        let mut in_main_group = false;
+        let mut name = None;
+        let mut icon = None;
+        let mut exec = None;
+        let mut startup_wm_class = None;
+        let mut hidden = false;
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with('[') {
+                in_main_group = line == "[Desktop Entry]";
+                continue;
+            }
+            if !in_main_group || line.starts_with('#') {
+                continue;
+            }
+            // Les clés localisées (Name[fr]=...) ne correspondent pas : on garde la valeur par défaut
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().to_string();
+            match key.trim() {
+                "Name" => name = Some(value),
+                "Icon" => icon = Some(value).filter(|icon| !icon.is_empty()),
+                "Exec" => exec = Some(value),
+                "StartupWMClass" => startup_wm_class = Some(value).filter(|class| !class.is_empty()),
+                "Type" if value != "Application" => return None,
+                "Hidden" => hidden = value == "true",
+                _ => {}
+            }
+        }
+
+        if hidden {
+            return None;
+        }
+        let program = exec.as_deref().and_then(exec_program);
+        // /proc/<pid>/exe donne le chemin réel : les liens de /usr/bin sont résolus de la même façon
+        let program_path = program.as_deref().filter(|program| program.starts_with('/')).map(|program| {
+            fs::canonicalize(program)
+                .map(|path| path.to_string_lossy().to_string())
+                .unwrap_or_else(|_| program.to_string())
+        });
+        let program_name = program
+            .as_deref()
+            .and_then(|program| Path::new(program).file_name())
+            .map(|name| name.to_string_lossy().to_string());
+        Some(DesktopEntry {
+            id,
+            name: name?,
+            icon,
+            program: program_name,
+            program_path,
+            startup_wm_class,
+        })
+    }
+
+    /// Programme d'une ligne Exec : "env VAR=1 /usr/bin/code --new-window %F" -> "/usr/bin/code".
+    /// None pour un shell, un interpréteur ou un lanceur (`sh -c`, `python3 app.py`, `flatpak run`) :
+    /// leur nom désignerait tous les processus de ce programme.
+    fn exec_program(exec: &str) -> Option<String> {
+        let mut tokens = exec.split_whitespace().map(|token| token.trim_matches('"'));
+        let mut program = tokens.next()?;
+        if is_env(program) {
+            program = tokens.find(|token| !token.contains('=') && !token.starts_with('-'))?;
+        }
+        let name = Path::new(program).file_name()?.to_string_lossy().to_lowercase();
+        (!is_launcher(&name)).then(|| program.to_string())
+    }
+
+    fn is_env(program: &str) -> bool {
+        program == "env" || program.ends_with("/env")
+    }
+
+    fn is_launcher(name: &str) -> bool {
+        const LAUNCHERS: [&str; 19] = [
+            "sh", "bash", "dash", "zsh", "ksh", "fish", "env", "java", "javaw", "node", "perl", "ruby", "mono", "wine",
+            "flatpak", "snap", "gtk-launch", "xdg-open", "pkexec",
+        ];
+        LAUNCHERS.contains(&name)
+            || ["python", "pypy"].iter().any(|prefix| name.starts_with(prefix))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn temporary_directory(name: &str) -> PathBuf {
+            let directory = std::env::temp_dir().join(format!("sup_mtracker-desktop-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&directory);
+            fs::create_dir_all(&directory).unwrap();
+            directory
+        }
+
+        fn write_entry(directory: &Path, id: &str, content: &str) -> DesktopEntry {
+            let path = directory.join(format!("{}.desktop", id));
+            fs::write(&path, content).unwrap();
+            parse_entry(&path, id.to_string()).unwrap()
+        }
+
+        fn hints<'a>(process_name: &'a str, window_class: Option<&'a str>) -> IdentityHints<'a> {
+            IdentityHints {
+                pid: 0,
+                process_name,
+                executable_path: None,
+                window_class,
+            }
+        }
+
+        #[test]
+        fn exec_lines_skip_launchers() {
+            assert_eq!(exec_program("/usr/bin/code --new-window %F").as_deref(), Some("/usr/bin/code"));
+            assert_eq!(exec_program("env GDK_BACKEND=x11 \"spotify\" %U").as_deref(), Some("spotify"));
+            assert_eq!(exec_program(r#"sh -c "exec /opt/foo/foo""#), None);
+            assert_eq!(exec_program("/bin/bash /opt/tool/run.sh"), None);
+            assert_eq!(exec_program("python3 /opt/foo/app.py"), None);
+            assert_eq!(exec_program("env PYTHONPATH=/opt /usr/bin/python3.12 -m foo"), None);
+            assert_eq!(exec_program("java -jar /opt/x/x.jar"), None);
+            assert_eq!(exec_program("/usr/bin/flatpak run org.gimp.GIMP"), None);
+            assert_eq!(exec_program(""), None);
+        }
+
+        #[test]
+        fn desktop_files_are_parsed() {
+            let directory = temporary_directory("parse");
+            let entry = write_entry(
+                &directory,
+                "org.example.Editor",
+                "# Commentaire\n[Desktop Entry]\nType=Application\nName=Editor\nName[fr]=Éditeur\nIcon=editor\n\
+                 Exec=env LANG=C editor %F\nStartupWMClass=EditorMain\n\n[Desktop Action new]\nName=New window\nExec=other\n",
+            );
+            assert_eq!(entry.id, "org.example.Editor");
+            assert_eq!(entry.name, "Editor");
+            assert_eq!(entry.icon.as_deref(), Some("editor"));
+            assert_eq!(entry.program.as_deref(), Some("editor"));
+            assert_eq!(entry.program_path, None);
+            assert_eq!(entry.startup_wm_class.as_deref(), Some("EditorMain"));
+
+            let hidden = directory.join("hidden.desktop");
+            fs::write(&hidden, "[Desktop Entry]\nType=Application\nName=Hidden\nHidden=true\n").unwrap();
+            assert!(parse_entry(&hidden, "hidden".to_string()).is_none());
+            let link = directory.join("link.desktop");
+            fs::write(&link, "[Desktop Entry]\nType=Link\nName=Site\nURL=https://example.com\n").unwrap();
+            assert!(parse_entry(&link, "link".to_string()).is_none());
+
+            let _ = fs::remove_dir_all(&directory);
+        }
+
+        #[test]
+        fn launchers_do_not_claim_every_process() {
+            let directory = temporary_directory("launchers");
+            let entries = vec![
+                write_entry(&directory, "shell-app", "[Desktop Entry]\nType=Application\nName=Shell App\nExec=sh -c \"cd /opt/app && ./app\"\n"),
+                write_entry(&directory, "py-app", "[Desktop Entry]\nType=Application\nName=Py App\nExec=python3 /opt/foo/app.py\n"),
+                write_entry(&directory, "jar-app", "[Desktop Entry]\nType=Application\nName=Jar App\nExec=java -jar x.jar\nStartupWMClass=jar-app-main\n"),
+                write_entry(&directory, "gimp", "[Desktop Entry]\nType=Application\nName=GNU Image Manipulation Program\nExec=flatpak run org.gimp.GIMP\n"),
+            ];
+
+            for process_name in ["sh", "bash", "python3", "java", "flatpak"] {
+                assert!(find_entry(&entries, &hints(process_name, None), None).is_none(), "{}", process_name);
+            }
+            // La classe de fenêtre et l'identifiant restent utilisables
+            assert_eq!(find_entry(&entries, &hints("java", Some("jar-app-main")), None).unwrap().name, "Jar App");
+            assert_eq!(find_entry(&entries, &hints("gimp", None), None).unwrap().id, "gimp");
+
+            let _ = fs::remove_dir_all(&directory);
+        }
+
+        #[test]
+        fn absolute_exec_paths_match_the_resolved_binary_only() {
+            let directory = temporary_directory("paths");
+            let binary = directory.join("tool-1.2");
+            fs::write(&binary, "").unwrap();
+            std::os::unix::fs::symlink(&binary, directory.join("tool")).unwrap();
+            let entries = vec![write_entry(
+                &directory,
+                "tool",
+                &format!("[Desktop Entry]\nType=Application\nName=Tool\nExec={}/tool --flag\n", directory.display()),
+            )];
+            let binary = fs::canonicalize(&binary).unwrap().to_string_lossy().to_string();
+            assert_eq!(entries[0].program_path.as_deref(), Some(binary.as_str()));
+
+            // Le lien est résolu comme /proc/<pid>/exe
+            assert_eq!(find_entry(&entries, &hints("tool-1.2", None), Some(&binary)).unwrap().name, "Tool");
+            // Homonyme ailleurs : pas de correspondance par le programme
+            let other = find_entry(&entries, &hints("tool-1.2", None), Some("/usr/local/bin/tool-1.2"));
+            assert!(other.is_none());
+            // Repli sur l'identifiant du fichier
+            assert_eq!(find_entry(&entries, &hints("tool", None), Some("/usr/local/bin/tool")).unwrap().id, "tool");
+
+            let _ = fs::remove_dir_all(&directory);
+        }
+    }
+}
@@ -0,0 +1,25 @@
+//! Hôte native messaging lancé par le navigateur pour l'extension compagnon.
+//!
+//! Le manifeste de l'hôte (`com.sup.mtracker.json`) doit pointer vers cet exécutable ;
+//! l'extension s'y connecte avec `runtime.connectNative("com.sup.mtracker")`.
+use sup_mtracker::native_messaging::{resolve_browser_pid, BrowserTabStore, NativeMessagingHost};
+
+fn main() {
+    // stdout est réservé au protocole : aucune sortie texte ici
+    let browser_pid = match resolve_browser_pid() {
+        Some(pid) => pid,
+        None => {
+            eprintln!("❌ Impossible de déterminer le PID du navigateur");
+            std::process::exit(1);
+        }
+    };
+
+    let mut host = NativeMessagingHost::new(browser_pid, BrowserTabStore::new());
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+
+    if let Err(e) = host.run(&mut stdin.lock(), &mut stdout.lock()) {
+        eprintln!("❌ Erreur de l'hôte native messaging: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,261 @@
+use super::{hex, u16_at, u32_at, u64_at, ReadAt};
+use anyhow::{bail, Result};
+
+// Types de segments (elf.h)
+const PT_LOAD: u32 = 1;
+const PT_DYNAMIC: u32 = 2;
+const PT_NOTE: u32 = 4;
+
+// Entrées de la section dynamique
+const DT_NULL: u64 = 0;
+const DT_STRTAB: u64 = 5;
+const DT_SONAME: u64 = 14;
+
+const NT_GNU_BUILD_ID: u32 = 3;
+
+// Limite de lecture d'un segment de notes ou de la section dynamique
+const MAX_SEGMENT_SIZE: u64 = 1024 * 1024;
+const MAX_STRING_LENGTH: usize = 4096;
+
+struct ProgramHeader {
+    kind: u32,
+    offset: u64,
+    virtual_address: u64,
+    file_size: u64,
+}
+
+/// En-têtes d'un binaire ELF : classe, boutisme, machine et segments
+pub struct ElfFile {
+    is_64_bit: bool,
+    big_endian: bool,
+    machine: u16,
+    segments: Vec<ProgramHeader>,
+}
+
+impl ElfFile {
+    pub fn parse(source: &mut impl ReadAt) -> Result<Self> {
+        let header = source.read_exact_at(0, 64)?;
+        if !header.starts_with(b"\x7fELF") {
+            bail!("Signature ELF absente");
+        }
+        let is_64_bit = match header[4] {
+            1 => false,
+            2 => true,
+            _ => bail!("Classe ELF inconnue"),
+        };
+        let big_endian = header[5] == 2;
+        let machine = u16_at(&header, 0x12, big_endian).unwrap_or(0);
+
+        let (program_offset, entry_size, entry_count) = if is_64_bit {
+            (
+                u64_at(&header, 0x20, big_endian).unwrap_or(0),
+                u16_at(&header, 0x36, big_endian).unwrap_or(0) as usize,
+                u16_at(&header, 0x38, big_endian).unwrap_or(0) as usize,
+            )
+        } else {
+            (
+                u32_at(&header, 0x1C, big_endian).unwrap_or(0) as u64,
+                u16_at(&header, 0x2A, big_endian).unwrap_or(0) as usize,
+                u16_at(&header, 0x2C, big_endian).unwrap_or(0) as usize,
+            )
+        };
+
+        let table = source.read_exact_at(program_offset, entry_size * entry_count)?;
+        let segments = table
+            .chunks_exact(entry_size.max(1))
+            .filter_map(|entry| {
+                Some(if is_64_bit {
+                    ProgramHeader {
+                        kind: u32_at(entry, 0, big_endian)?,
+                        offset: u64_at(entry, 8, big_endian)?,
+                        virtual_address: u64_at(entry, 16, big_endian)?,
+                        file_size: u64_at(entry, 32, big_endian)?,
+                    }
+                } else {
+                    ProgramHeader {
+                        kind: u32_at(entry, 0, big_endian)?,
+                        offset: u32_at(entry, 4, big_endian)? as u64,
+                        virtual_address: u32_at(entry, 8, big_endian)? as u64,
+                        file_size: u32_at(entry, 16, big_endian)? as u64,
+                    }
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            is_64_bit,
+            big_endian,
+            machine,
+            segments,
+        })
+    }
+
+    pub fn architecture(&self) -> Option<&'static str> {
+        match self.machine {
+            3 => Some("x86"),
+            62 => Some("x86_64"),
+            40 => Some("arm"),
+            183 => Some("aarch64"),
+            243 => Some("riscv"),
+            _ => None,
+        }
+    }
+
+    /// Identifiant de build GNU (note NT_GNU_BUILD_ID), en hexadécimal
+    pub fn build_id(&self, source: &mut impl ReadAt) -> Result<Option<String>> {
+        for segment in self.segments.iter().filter(|segment| segment.kind == PT_NOTE) {
+            let notes = source.read_at(segment.offset, segment.file_size.min(MAX_SEGMENT_SIZE) as usize)?;
+            let mut position = 0;
+
+            // Elf_Nhdr : namesz, descsz, type, puis nom et descripteur alignés sur 4 octets
+            while position + 12 <= notes.len() {
+                let name_size = u32_at(&notes, position, self.big_endian).unwrap_or(0) as usize;
+                let desc_size = u32_at(&notes, position + 4, self.big_endian).unwrap_or(0) as usize;
+                let kind = u32_at(&notes, position + 8, self.big_endian).unwrap_or(0);
+                let name_start = position + 12;
+                let desc_start = name_start + align4(name_size);
+
+                if kind == NT_GNU_BUILD_ID && notes.get(name_start..name_start + name_size) == Some(b"GNU\0") {
+                    return Ok(notes.get(desc_start..desc_start + desc_size).map(hex));
+                }
+                position = desc_start + align4(desc_size);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Nom de bibliothèque partagée (DT_SONAME), absent pour un exécutable
+    pub fn soname(&self, source: &mut impl ReadAt) -> Result<Option<String>> {
+        let Some(dynamic) = self.segments.iter().find(|segment| segment.kind == PT_DYNAMIC) else {
+            return Ok(None);
+        };
+        let entries = source.read_at(dynamic.offset, dynamic.file_size.min(MAX_SEGMENT_SIZE) as usize)?;
+        let entry_size = if self.is_64_bit { 16 } else { 8 };
+
+        let mut string_table = None;
+        let mut soname = None;
+        for entry in entries.chunks_exact(entry_size) {
+            let (tag, value) = if self.is_64_bit {
+                (u64_at(entry, 0, self.big_endian), u64_at(entry, 8, self.big_endian))
+            } else {
+                (
+                    u32_at(entry, 0, self.big_endian).map(u64::from),
+                    u32_at(entry, 4, self.big_endian).map(u64::from),
+                )
+            };
+            let (Some(tag), Some(value)) = (tag, value) else {
+                break;
+            };
+            match tag {
+                DT_NULL => break,
+                DT_STRTAB => string_table = Some(value),
+                DT_SONAME => soname = Some(value),
+                _ => {}
+            }
+        }
+
+        // DT_STRTAB est une adresse virtuelle : conversion en position dans le fichier
+        let (Some(string_table), Some(soname)) = (string_table, soname) else {
+            return Ok(None);
+        };
+        let Some(offset) = self.file_offset(string_table) else {
+            return Ok(None);
+        };
+
+        let Some(position) = offset.checked_add(soname) else {
+            return Ok(None);
+        };
+        let bytes = source.read_at(position, MAX_STRING_LENGTH)?;
+        let end = bytes.iter().position(|byte| *byte == 0).unwrap_or(bytes.len());
+        Ok(Some(String::from_utf8_lossy(&bytes[..end]).to_string()).filter(|name| !name.is_empty()))
+    }
+
+    fn file_offset(&self, virtual_address: u64) -> Option<u64> {
+        self.segments
+            .iter()
+            .filter(|segment| segment.kind == PT_LOAD)
+            // En-têtes non fiables : pas d'addition qui puisse déborder
+            .find_map(|segment| {
+                let delta = virtual_address.checked_sub(segment.virtual_address)?;
+                (delta < segment.file_size).then(|| segment.offset.checked_add(delta))?
+            })
+    }
+}
+
+fn align4(value: usize) -> usize {
+    (value + 3) & !3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    // Binaires générés par tests/fixtures/binfmt/generate.py
+    fn fixture(name: &str) -> Cursor<Vec<u8>> {
+        let path = format!("{}/tests/fixtures/binfmt/{}", env!("CARGO_MANIFEST_DIR"), name);
+        Cursor::new(std::fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path, e)))
+    }
+
+    const BUILD_ID: &str = "101112131415161718191a1b1c1d1e1f20212223";
+
+    #[test]
+    fn executable_with_build_id() {
+        let mut source = fixture("elf64_le_build_id.elf");
+        let elf = ElfFile::parse(&mut source).unwrap();
+        assert_eq!(elf.architecture(), Some("x86_64"));
+        assert_eq!(elf.build_id(&mut source).unwrap().as_deref(), Some(BUILD_ID));
+        assert_eq!(elf.soname(&mut source).unwrap(), None);
+    }
+
+    #[test]
+    fn library_with_soname() {
+        let mut source = fixture("elf64_le_soname.elf");
+        let elf = ElfFile::parse(&mut source).unwrap();
+        assert_eq!(elf.architecture(), Some("aarch64"));
+        assert_eq!(elf.build_id(&mut source).unwrap(), None);
+        assert_eq!(elf.soname(&mut source).unwrap().as_deref(), Some("libfixture.so.1"));
+    }
+
+    #[test]
+    fn elf32_little_endian() {
+        let mut source = fixture("elf32_le.elf");
+        let elf = ElfFile::parse(&mut source).unwrap();
+        assert!(!elf.is_64_bit);
+        assert_eq!(elf.architecture(), Some("x86"));
+        assert_eq!(elf.build_id(&mut source).unwrap().as_deref(), Some(BUILD_ID));
+        assert_eq!(elf.soname(&mut source).unwrap().as_deref(), Some("libfixture.so.1"));
+    }
+
+    #[test]
+    fn elf64_big_endian() {
+        let mut source = fixture("elf64_be.elf");
+        let elf = ElfFile::parse(&mut source).unwrap();
+        assert!(elf.big_endian);
+        // PowerPC64 : machine non répertoriée
+        assert_eq!(elf.architecture(), None);
+        assert_eq!(elf.build_id(&mut source).unwrap().as_deref(), Some(BUILD_ID));
+        assert_eq!(elf.soname(&mut source).unwrap().as_deref(), Some("libfixture.so.1"));
+    }
+
+    #[test]
+    fn hostile_addresses_do_not_overflow() {
+        let mut source = fixture("elf64_le_soname.elf");
+        let mut elf = ElfFile::parse(&mut source).unwrap();
+        for segment in &mut elf.segments {
+            if segment.kind == PT_LOAD {
+                segment.virtual_address = u64::MAX - 8;
+                segment.file_size = u64::MAX;
+                segment.offset = u64::MAX;
+            }
+        }
+        assert_eq!(elf.file_offset(u64::MAX - 1), None);
+        assert_eq!(elf.soname(&mut source).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_non_elf_data() {
+        assert!(ElfFile::parse(&mut fixture("pe32_version.exe")).is_err());
+        assert!(ElfFile::parse(&mut Cursor::new(b"\x7fELF".to_vec())).is_err());
+    }
+}
@@ -0,0 +1,171 @@
+pub mod elf;
+pub mod pe;
+
+use crate::models::ExecutableInfo;
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub use elf::ElfFile;
+pub use pe::PeFile;
+
+/// Accès aléatoire au contenu d'un binaire (fichier sur disque ou octets en mémoire)
+pub trait ReadAt {
+    /// Lire au plus `len` octets à partir de `offset` (moins en fin de fichier)
+    fn read_at(&mut self, offset: u64, len: usize) -> io::Result<Vec<u8>>;
+
+    /// Lire exactement `len` octets
+    fn read_exact_at(&mut self, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        let bytes = self.read_at(offset, len)?;
+        if bytes.len() < len {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "binaire tronqué"));
+        }
+        Ok(bytes)
+    }
+}
+
+impl<T: Read + Seek> ReadAt for T {
+    fn read_at(&mut self, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        self.seek(SeekFrom::Start(offset))?;
+        let mut bytes = Vec::with_capacity(len.min(1024 * 1024));
+        self.by_ref().take(len as u64).read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+/// Format reconnu à partir des premiers octets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryFormat {
+    Pe,
+    Elf,
+    Unknown,
+}
+
+impl BinaryFormat {
+    pub fn detect(magic: &[u8]) -> Self {
+        if magic.starts_with(b"MZ") {
+            Self::Pe
+        } else if magic.starts_with(b"\x7fELF") {
+            Self::Elf
+        } else {
+            Self::Unknown
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pe => "pe",
+            Self::Elf => "elf",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// Lire l'identité d'un exécutable : taille, date, SHA-256, version (PE) ou build-id (ELF)
+pub fn read_executable_info(path: &Path) -> Result<ExecutableInfo> {
+    let mut file = File::open(path)
+        .map_err(|e| anyhow::anyhow!("Impossible d'ouvrir {}: {}", path.display(), e))?;
+    let metadata = file.metadata()?;
+
+    let mut info = ExecutableInfo {
+        path: path.to_string_lossy().to_string(),
+        size: metadata.len(),
+        modified: metadata.modified().ok().and_then(millis_since_epoch),
+        sha256: sha256_hex(&mut file)?,
+        format: String::new(),
+        architecture: None,
+        version: None,
+        build_id: None,
+        soname: None,
+    };
+
+    let format = BinaryFormat::detect(&file.read_at(0, 4)?);
+    info.format = format.as_str().to_string();
+
+    // Un en-tête invalide n'empêche pas de publier la taille et le hash
+    match format {
+        BinaryFormat::Pe => {
+            if let Ok(pe) = PeFile::parse(&mut file) {
+                info.architecture = pe.architecture().map(str::to_string);
+                info.version = pe.version_info(&mut file).ok().flatten();
+            }
+        }
+        BinaryFormat::Elf => {
+            if let Ok(elf) = ElfFile::parse(&mut file) {
+                info.architecture = elf.architecture().map(str::to_string);
+                info.build_id = elf.build_id(&mut file).ok().flatten();
+                info.soname = elf.soname(&mut file).ok().flatten();
+            }
+        }
+        BinaryFormat::Unknown => {}
+    }
+
+    Ok(info)
+}
+
+// Le hash d'un gros binaire coûte cher : résultat réutilisé tant que taille et date sont identiques
+static EXECUTABLE_CACHE: OnceLock<Mutex<HashMap<PathBuf, ExecutableInfo>>> = OnceLock::new();
+
+/// `read_executable_info` avec cache par chemin (invalidé si le fichier change)
+pub fn executable_info(path: &Path) -> Result<ExecutableInfo> {
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| anyhow::anyhow!("Impossible de lire {}: {}", path.display(), e))?;
+    let modified = metadata.modified().ok().and_then(millis_since_epoch);
+
+    let cache = EXECUTABLE_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Ok(cache) = cache.lock() {
+        if let Some(info) = cache.get(path) {
+            if info.size == metadata.len() && info.modified == modified {
+                return Ok(info.clone());
+            }
+        }
+    }
+
+    let info = read_executable_info(path)?;
+    if let Ok(mut cache) = cache.lock() {
+        cache.insert(path.to_path_buf(), info.clone());
+    }
+    Ok(info)
+}
+
+fn sha256_hex(file: &mut File) -> Result<String> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn millis_since_epoch(time: SystemTime) -> Option<u64> {
+    time.duration_since(UNIX_EPOCH).ok().map(|d| d.as_millis() as u64)
+}
+
+pub(crate) fn u16_at(bytes: &[u8], offset: usize, big_endian: bool) -> Option<u16> {
+    let raw: [u8; 2] = bytes.get(offset..offset + 2)?.try_into().ok()?;
+    Some(if big_endian { u16::from_be_bytes(raw) } else { u16::from_le_bytes(raw) })
+}
+
+pub(crate) fn u32_at(bytes: &[u8], offset: usize, big_endian: bool) -> Option<u32> {
+    let raw: [u8; 4] = bytes.get(offset..offset + 4)?.try_into().ok()?;
+    Some(if big_endian { u32::from_be_bytes(raw) } else { u32::from_le_bytes(raw) })
+}
+
+pub(crate) fn u64_at(bytes: &[u8], offset: usize, big_endian: bool) -> Option<u64> {
+    let raw: [u8; 8] = bytes.get(offset..offset + 8)?.try_into().ok()?;
+    Some(if big_endian { u64::from_be_bytes(raw) } else { u64::from_le_bytes(raw) })
+}
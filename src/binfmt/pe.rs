@@ -0,0 +1,424 @@
+use super::{u16_at, u32_at, ReadAt};
+use crate::models::VersionInfo;
+use anyhow::{bail, Result};
+
+// Types de ressources (winuser.h)
+pub const RT_ICON: u16 = 3;
+pub const RT_GROUP_ICON: u16 = 14;
+pub const RT_VERSION: u16 = 16;
+
+// Limite de lecture de la section de ressources (les icônes haute résolution pèsent quelques Mo)
+const MAX_RESOURCE_SECTION: usize = 64 * 1024 * 1024;
+// Profondeur maximale d'un VS_VERSIONINFO (VS_VERSION_INFO > StringFileInfo > StringTable > String)
+const MAX_VERSION_DEPTH: usize = 4;
+
+struct Section {
+    virtual_address: u32,
+    virtual_size: u32,
+    raw_size: u32,
+    raw_offset: u32,
+}
+
+/// Ressource extraite de la section .rsrc
+#[derive(Debug, Clone)]
+pub struct Resource {
+    /// Identifiant numérique (None pour une ressource nommée)
+    pub id: Option<u16>,
+    pub name: Option<String>,
+    pub language: u16,
+    pub data: Vec<u8>,
+}
+
+/// En-têtes d'un binaire PE (exe/dll) : machine, sections et répertoire de ressources
+pub struct PeFile {
+    machine: u16,
+    sections: Vec<Section>,
+    resource_rva: u32,
+    resource_size: u32,
+}
+
+impl PeFile {
+    pub fn parse(source: &mut impl ReadAt) -> Result<Self> {
+        let dos = source.read_exact_at(0, 64)?;
+        if !dos.starts_with(b"MZ") {
+            bail!("Signature MZ absente");
+        }
+        let pe_offset = u32_at(&dos, 0x3C, false).unwrap_or(0) as u64;
+
+        // Signature "PE\0\0" + IMAGE_FILE_HEADER (20 octets)
+        let header = source.read_exact_at(pe_offset, 24)?;
+        if &header[..4] != b"PE\0\0" {
+            bail!("Signature PE absente");
+        }
+        let machine = u16_at(&header, 4, false).unwrap_or(0);
+        let section_count = u16_at(&header, 6, false).unwrap_or(0) as usize;
+        let optional_size = u16_at(&header, 20, false).unwrap_or(0) as usize;
+
+        let optional = source.read_exact_at(pe_offset + 24, optional_size)?;
+        // PE32 (0x10B) ou PE32+ (0x20B) : les répertoires de données ne sont pas au même endroit
+        let directories = match u16_at(&optional, 0, false) {
+            Some(0x10B) => 96,
+            Some(0x20B) => 112,
+            _ => bail!("En-tête optionnel PE inconnu"),
+        };
+        // Répertoire n°2 : ressources (RVA, taille)
+        let resource_rva = u32_at(&optional, directories + 2 * 8, false).unwrap_or(0);
+        let resource_size = u32_at(&optional, directories + 2 * 8 + 4, false).unwrap_or(0);
+
+        let table = source.read_exact_at(pe_offset + 24 + optional_size as u64, section_count * 40)?;
+        let sections = table
+            .chunks_exact(40)
+            .map(|entry| Section {
+                virtual_size: u32_at(entry, 8, false).unwrap_or(0),
+                virtual_address: u32_at(entry, 12, false).unwrap_or(0),
+                raw_size: u32_at(entry, 16, false).unwrap_or(0),
+                raw_offset: u32_at(entry, 20, false).unwrap_or(0),
+            })
+            .collect();
+
+        Ok(Self {
+            machine,
+            sections,
+            resource_rva,
+            resource_size,
+        })
+    }
+
+    pub fn architecture(&self) -> Option<&'static str> {
+        match self.machine {
+            0x014C => Some("x86"),
+            0x8664 => Some("x86_64"),
+            0xAA64 => Some("aarch64"),
+            0x01C4 => Some("arm"),
+            _ => None,
+        }
+    }
+
+    /// Ressources d'un type donné (première langue de chaque entrée)
+    pub fn resources(&self, source: &mut impl ReadAt, resource_type: u16) -> Result<Vec<Resource>> {
+        let Some(rsrc) = self.read_resource_section(source)? else {
+            return Ok(Vec::new());
+        };
+
+        let mut resources = Vec::new();
+        for (type_id, _, type_offset) in directory_entries(&rsrc, 0) {
+            if type_id != Some(resource_type) {
+                continue;
+            }
+            let Some(names) = type_offset.subdirectory() else {
+                continue;
+            };
+
+            for (id, name, name_offset) in directory_entries(&rsrc, names) {
+                let Some(languages) = name_offset.subdirectory() else {
+                    continue;
+                };
+                let Some((language, _, data_offset)) = directory_entries(&rsrc, languages).into_iter().next() else {
+                    continue;
+                };
+                if data_offset.subdirectory().is_some() {
+                    continue;
+                }
+                if let Some(data) = self.data_entry(&rsrc, data_offset.0 as usize) {
+                    resources.push(Resource {
+                        id,
+                        name,
+                        language: language.unwrap_or(0),
+                        data,
+                    });
+                }
+            }
+        }
+        Ok(resources)
+    }
+
+    /// Ressource VS_VERSIONINFO (None si le binaire n'en a pas)
+    pub fn version_info(&self, source: &mut impl ReadAt) -> Result<Option<VersionInfo>> {
+        Ok(self
+            .resources(source, RT_VERSION)?
+            .first()
+            .and_then(|resource| parse_version_info(&resource.data)))
+    }
+
+    /// Section .rsrc lue à partir du répertoire de ressources
+    fn read_resource_section(&self, source: &mut impl ReadAt) -> Result<Option<Vec<u8>>> {
+        if self.resource_rva == 0 || self.resource_size == 0 {
+            return Ok(None);
+        }
+        let Some(section) = self.section_for(self.resource_rva) else {
+            return Ok(None);
+        };
+
+        // Les données des ressources suivent le répertoire, jusqu'à la fin de la section
+        let start = self.resource_rva - section.virtual_address;
+        let length = (section.raw_size.max(section.virtual_size).saturating_sub(start) as usize)
+            .max(self.resource_size as usize)
+            .min(MAX_RESOURCE_SECTION);
+        let offset = section.raw_offset as u64 + start as u64;
+        Ok(Some(source.read_at(offset, length)?))
+    }
+
+    fn section_for(&self, rva: u32) -> Option<&Section> {
+        self.sections.iter().find(|section| {
+            let size = section.virtual_size.max(section.raw_size);
+            rva >= section.virtual_address && rva < section.virtual_address.saturating_add(size)
+        })
+    }
+
+    /// IMAGE_RESOURCE_DATA_ENTRY : RVA et taille des données
+    fn data_entry(&self, rsrc: &[u8], offset: usize) -> Option<Vec<u8>> {
+        let rva = u32_at(rsrc, offset, false)?;
+        let size = u32_at(rsrc, offset + 4, false)? as usize;
+        let start = rva.checked_sub(self.resource_rva)? as usize;
+        rsrc.get(start..start.checked_add(size)?).map(<[u8]>::to_vec)
+    }
+}
+
+/// Décalage d'une entrée de répertoire : bit de poids fort = sous-répertoire
+#[derive(Clone, Copy)]
+struct EntryOffset(u32);
+
+impl EntryOffset {
+    fn subdirectory(self) -> Option<usize> {
+        (self.0 & 0x8000_0000 != 0).then_some((self.0 & 0x7FFF_FFFF) as usize)
+    }
+}
+
+/// Entrées d'un IMAGE_RESOURCE_DIRECTORY : (identifiant, nom, décalage)
+fn directory_entries(rsrc: &[u8], offset: usize) -> Vec<(Option<u16>, Option<String>, EntryOffset)> {
+    let named = u16_at(rsrc, offset + 12, false).unwrap_or(0) as usize;
+    let ids = u16_at(rsrc, offset + 14, false).unwrap_or(0) as usize;
+
+    (0..named + ids)
+        .filter_map(|index| {
+            let entry = offset + 16 + index * 8;
+            let name = u32_at(rsrc, entry, false)?;
+            let target = u32_at(rsrc, entry + 4, false)?;
+            if name & 0x8000_0000 != 0 {
+                // Nom : IMAGE_RESOURCE_DIR_STRING_U (longueur en caractères + UTF-16)
+                let string = (name & 0x7FFF_FFFF) as usize;
+                let length = u16_at(rsrc, string, false)? as usize;
+                let words = utf16_words(rsrc.get(string + 2..string + 2 + length * 2)?);
+                Some((None, Some(String::from_utf16_lossy(&words)), EntryOffset(target)))
+            } else {
+                Some((Some(name as u16), None, EntryOffset(target)))
+            }
+        })
+        .collect()
+}
+
+/// Bloc d'un VS_VERSIONINFO : wLength, wValueLength, wType, szKey, valeur puis enfants
+struct VersionBlock<'a> {
+    key: String,
+    value: &'a [u8],
+    is_text: bool,
+    children: Vec<VersionBlock<'a>>,
+}
+
+/// Décoder une ressource RT_VERSION
+pub fn parse_version_info(data: &[u8]) -> Option<VersionInfo> {
+    let (root, _) = parse_version_block(data, 0)?;
+    if root.key != "VS_VERSION_INFO" {
+        return None;
+    }
+
+    let mut info = VersionInfo {
+        fixed_file_version: fixed_file_version(root.value),
+        ..VersionInfo::default()
+    };
+
+    // Table de chaînes : anglais (0409) de préférence, sinon la première
+    let tables: Vec<&VersionBlock> = root
+        .children
+        .iter()
+        .filter(|child| child.key == "StringFileInfo")
+        .flat_map(|child| child.children.iter())
+        .collect();
+    let Some(table) = tables
+        .iter()
+        .find(|table| table.key.to_uppercase().starts_with("0409"))
+        .or_else(|| tables.first())
+    else {
+        return Some(info);
+    };
+
+    for string in &table.children {
+        let value = if string.is_text {
+            let words: Vec<u16> = utf16_words(string.value).into_iter().take_while(|word| *word != 0).collect();
+            String::from_utf16_lossy(&words)
+        } else {
+            String::from_utf8_lossy(string.value).trim_end_matches('\0').to_string()
+        };
+        let value = value.trim().to_string();
+        if value.is_empty() {
+            continue;
+        }
+
+        match string.key.as_str() {
+            "ProductName" => info.product_name = Some(value),
+            "CompanyName" => info.company_name = Some(value),
+            "FileDescription" => info.file_description = Some(value),
+            "FileVersion" => info.file_version = Some(value),
+            "ProductVersion" => info.product_version = Some(value),
+            "OriginalFilename" => info.original_filename = Some(value),
+            _ => {}
+        }
+    }
+
+    Some(info)
+}
+
+fn parse_version_block(data: &[u8], depth: usize) -> Option<(VersionBlock<'_>, usize)> {
+    let length = (u16_at(data, 0, false)? as usize).min(data.len());
+    let value_length = u16_at(data, 2, false)? as usize;
+    let is_text = u16_at(data, 4, false)? == 1;
+    if length < 6 {
+        return None;
+    }
+    let block = &data[..length];
+
+    // Clé UTF-16 terminée par un caractère nul
+    let key_words: Vec<u16> = block[6..]
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|word| *word != 0)
+        .collect();
+    let key = String::from_utf16_lossy(&key_words);
+    let mut position = align4(6 + (key_words.len() + 1) * 2);
+
+    // Valeur texte : wValueLength est en caractères mais parfois faux, on lit jusqu'à la fin
+    // du bloc (une chaîne n'a pas d'enfants) et le nul final sert de délimiteur
+    let value_end = match (is_text, value_length) {
+        (_, 0) => position,
+        (true, _) => length,
+        (false, size) => (position + size).min(length),
+    };
+    let value = block.get(position..value_end).unwrap_or(&[]);
+    position = align4(value_end.max(position));
+
+    let mut children = Vec::new();
+    if depth < MAX_VERSION_DEPTH {
+        while position + 6 <= length {
+            let Some((child, child_length)) = parse_version_block(&block[position..], depth + 1) else {
+                break;
+            };
+            children.push(child);
+            position = align4(position + child_length);
+        }
+    }
+
+    Some((
+        VersionBlock {
+            key,
+            value,
+            is_text,
+            children,
+        },
+        length,
+    ))
+}
+
+/// VS_FIXEDFILEINFO : signature 0xFEEF04BD puis dwFileVersionMS / dwFileVersionLS
+fn fixed_file_version(value: &[u8]) -> Option<String> {
+    if u32_at(value, 0, false)? != 0xFEEF_04BD {
+        return None;
+    }
+    let most = u32_at(value, 8, false)?;
+    let least = u32_at(value, 12, false)?;
+    Some(format!("{}.{}.{}.{}", most >> 16, most & 0xFFFF, least >> 16, least & 0xFFFF))
+}
+
+fn align4(value: usize) -> usize {
+    (value + 3) & !3
+}
+
+fn utf16_words(bytes: &[u8]) -> Vec<u16> {
+    bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    // Binaires générés par tests/fixtures/binfmt/generate.py
+    fn fixture(name: &str) -> Cursor<Vec<u8>> {
+        let path = format!("{}/tests/fixtures/binfmt/{}", env!("CARGO_MANIFEST_DIR"), name);
+        Cursor::new(std::fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path, e)))
+    }
+
+    fn expected_version() -> VersionInfo {
+        VersionInfo {
+            product_name: Some("Fixture".to_string()),
+            company_name: Some("Fixture Corp".to_string()),
+            file_description: Some("Fixture Application".to_string()),
+            file_version: Some("1.2.3".to_string()),
+            product_version: Some("1.2".to_string()),
+            original_filename: Some("fixture.exe".to_string()),
+            fixed_file_version: Some("1.2.3.4".to_string()),
+        }
+    }
+
+    #[test]
+    fn pe32_with_version_info() {
+        let mut source = fixture("pe32_version.exe");
+        let pe = PeFile::parse(&mut source).unwrap();
+        assert_eq!(pe.architecture(), Some("x86"));
+        assert_eq!(pe.version_info(&mut source).unwrap(), Some(expected_version()));
+    }
+
+    #[test]
+    fn pe32_without_version_info() {
+        let mut source = fixture("pe32_no_version.dll");
+        let pe = PeFile::parse(&mut source).unwrap();
+        assert_eq!(pe.version_info(&mut source).unwrap(), None);
+
+        let icons = pe.resources(&mut source, RT_ICON).unwrap();
+        assert_eq!(icons.len(), 1);
+        assert_eq!(icons[0].id, Some(1));
+        assert_eq!(icons[0].language, 0x0409);
+        assert_eq!(icons[0].data.len(), 40);
+    }
+
+    #[test]
+    fn pe32_plus_with_several_resource_types() {
+        let mut source = fixture("pe32plus_version.exe");
+        let pe = PeFile::parse(&mut source).unwrap();
+        assert_eq!(pe.architecture(), Some("x86_64"));
+        assert_eq!(pe.resources(&mut source, RT_ICON).unwrap().len(), 1);
+        assert_eq!(pe.resources(&mut source, RT_GROUP_ICON).unwrap().len(), 0);
+        assert_eq!(pe.version_info(&mut source).unwrap(), Some(expected_version()));
+    }
+
+    #[test]
+    fn version_info_prefers_the_english_table() {
+        let data = fixture("version_info.bin").into_inner();
+        // La table française vient en premier dans la ressource
+        assert_eq!(parse_version_info(&data), Some(expected_version()));
+    }
+
+    #[test]
+    fn malformed_version_info() {
+        let data = fixture("version_info.bin").into_inner();
+        // Tronquée : les blocs incomplets sont ignorés, sans panique
+        for length in 0..data.len() {
+            let _ = parse_version_info(&data[..length]);
+        }
+        assert_eq!(parse_version_info(&data[..6]), None);
+
+        let mut wrong_key = data.clone();
+        wrong_key[6] = b'X';
+        assert_eq!(parse_version_info(&wrong_key), None);
+    }
+
+    #[test]
+    fn rejects_non_pe_data() {
+        assert!(PeFile::parse(&mut fixture("elf32_le.elf")).is_err());
+        let mut truncated = fixture("pe32_version.exe").into_inner();
+        truncated.truncate(100);
+        assert!(PeFile::parse(&mut Cursor::new(truncated)).is_err());
+    }
+}
@@ -0,0 +1,358 @@
+use crate::metadata::SiteCategories;
+use crate::models::{MetadataOptions, ProcessSelectionPolicy};
+use crate::process_query::ProcessQuery;
+use crate::realtime_monitor::MonitorConfig;
+use crate::redaction::{RedactionConfig, Redactor};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Fichier de configuration (TOML ou JSON) : cibles à surveiller, sorties et masquage
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrackerConfig {
+    pub targets: Vec<TargetConfig>,
+    pub outputs: Vec<OutputConfig>,
+    /// Masquage des données personnelles, appliqué à toutes les cibles (désactivé si absent)
+    pub redaction: Option<RedactionConfig>,
+}
+
+/// Une cible surveillée : règle de correspondance, intervalles et métadonnées à collecter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TargetConfig {
+    /// Nom de la cible dans les sorties
+    pub name: String,
+    /// Nom de l'exécutable (ex: "chrome.exe")
+    pub executable: Option<String>,
+    /// Requête de sélection, prioritaire sur `executable` (ex: "name~\"chrome\" and has_window")
+    pub query: Option<String>,
+    pub selection_policy: ProcessSelectionPolicy,
+    /// Intervalles (mêmes unités que `MonitorConfig`)
+    pub check_interval: u64,
+    pub adaptive_polling: bool,
+    pub fast_check_interval_ms: u64,
+    pub idle_check_interval: u64,
+    pub max_backoff_interval: u64,
+    pub jitter_ratio: f64,
+    pub watch_foreground: bool,
+    pub process_events: bool,
+    pub metadata: MetadataOptions,
+    /// Catégories d'onglets propres à la cible (sinon celles par défaut)
+    pub site_categories: Option<SiteCategories>,
+}
+
+impl Default for TargetConfig {
+    fn default() -> Self {
+        let monitor = MonitorConfig::default();
+        Self {
+            name: String::new(),
+            executable: None,
+            query: None,
+            selection_policy: monitor.selection_policy,
+            check_interval: monitor.check_interval,
+            adaptive_polling: monitor.adaptive_polling,
+            fast_check_interval_ms: monitor.fast_check_interval_ms,
+            idle_check_interval: monitor.idle_check_interval,
+            max_backoff_interval: monitor.max_backoff_interval,
+            jitter_ratio: monitor.jitter_ratio,
+            watch_foreground: monitor.watch_foreground,
+            process_events: monitor.process_events,
+            metadata: monitor.metadata_options,
+            site_categories: None,
+        }
+    }
+}
+
+/// Destination des états publiés par le mode surveillance (une ligne JSON par changement)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum OutputConfig {
+    Stdout,
+    /// Fichier JSON Lines, ouvert en ajout
+    File { path: PathBuf },
+}
+
+/// Format déduit de l'extension du fichier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_lowercase()).as_deref() {
+            Some("toml") => Ok(Self::Toml),
+            Some("json") => Ok(Self::Json),
+            _ => bail!(
+                "Format de configuration inconnu pour {} (extensions acceptées: .toml, .json)",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl TrackerConfig {
+    /// Charger et valider un fichier de configuration
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Impossible de lire {}: {}", path.display(), e))?;
+        Self::parse(&text, ConfigFormat::from_path(path)?)
+            .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+    }
+
+    pub fn parse(text: &str, format: ConfigFormat) -> Result<Self> {
+        // serde_path_to_error : l'erreur indique la clé fautive (ex: targets[1].metadata.cpu_info)
+        let config: Self = match format {
+            ConfigFormat::Toml => serde_path_to_error::deserialize(toml::Deserializer::new(text))
+                .map_err(|e| anyhow::anyhow!("clé `{}`: {}", e.path(), e.inner().to_string().trim()))?,
+            ConfigFormat::Json => {
+                let mut deserializer = serde_json::Deserializer::from_str(text);
+                serde_path_to_error::deserialize(&mut deserializer)
+                    .map_err(|e| anyhow::anyhow!("clé `{}`: {}", e.path(), e.inner()))?
+            }
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Vérifications sémantiques (ce que la désérialisation ne peut pas détecter)
+    pub fn validate(&self) -> Result<()> {
+        if self.targets.is_empty() {
+            bail!("clé `targets`: au moins une cible est requise");
+        }
+
+        let mut names = HashSet::new();
+        for (index, target) in self.targets.iter().enumerate() {
+            let key = |field: &str| format!("targets[{}].{}", index, field);
+
+            if target.name.trim().is_empty() {
+                bail!("clé `{}`: le nom de la cible est requis", key("name"));
+            }
+            if !names.insert(target.name.as_str()) {
+                bail!("clé `{}`: cible '{}' déjà définie", key("name"), target.name);
+            }
+
+            match (&target.executable, &target.query) {
+                (None, None) => bail!(
+                    "clé `{}`: `executable` ou `query` est requis pour la cible '{}'",
+                    key("executable"),
+                    target.name
+                ),
+                (Some(executable), _) if executable.trim().is_empty() => {
+                    bail!("clé `{}`: nom d'exécutable vide", key("executable"))
+                }
+                _ => {}
+            }
+            if let Some(query) = &target.query {
+                ProcessQuery::parse(query).map_err(|e| anyhow::anyhow!("clé `{}`: {}", key("query"), e))?;
+            }
+
+            if target.check_interval == 0 {
+                bail!("clé `{}`: doit être supérieur à 0", key("check_interval"));
+            }
+            if target.fast_check_interval_ms == 0 {
+                bail!("clé `{}`: doit être supérieur à 0", key("fast_check_interval_ms"));
+            }
+            if target.idle_check_interval < target.check_interval {
+                bail!(
+                    "clé `{}`: doit être supérieur ou égal à check_interval ({})",
+                    key("idle_check_interval"),
+                    target.check_interval
+                );
+            }
+            if target.max_backoff_interval < target.check_interval {
+                bail!(
+                    "clé `{}`: doit être supérieur ou égal à check_interval ({})",
+                    key("max_backoff_interval"),
+                    target.check_interval
+                );
+            }
+            if !(0.0..=1.0).contains(&target.jitter_ratio) {
+                bail!("clé `{}`: doit être compris entre 0 et 1", key("jitter_ratio"));
+            }
+        }
+
+        for (index, output) in self.outputs.iter().enumerate() {
+            if let OutputConfig::File { path } = output {
+                if path.as_os_str().is_empty() {
+                    bail!("clé `outputs[{}].path`: chemin vide", index);
+                }
+            }
+        }
+
+        if let Some(redaction) = &self.redaction {
+            Redactor::new(redaction.clone()).map_err(|e| anyhow::anyhow!("redaction: {}", e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Pipeline de masquage partagé par les moniteurs des cibles
+    pub fn redactor(&self) -> Result<Option<Arc<Redactor>>> {
+        self.redaction
+            .clone()
+            .map(|redaction| Redactor::new(redaction).map(Arc::new))
+            .transpose()
+    }
+
+    /// Sorties effectives (stdout si aucune n'est configurée)
+    pub fn effective_outputs(&self) -> Vec<OutputConfig> {
+        if self.outputs.is_empty() {
+            vec![OutputConfig::Stdout]
+        } else {
+            self.outputs.clone()
+        }
+    }
+}
+
+impl TargetConfig {
+    /// Construire la configuration du moniteur correspondant à la cible
+    pub fn monitor_config(&self, redactor: Option<Arc<Redactor>>) -> Result<MonitorConfig> {
+        let target_query = self.query.as_deref().map(ProcessQuery::parse).transpose()?;
+
+        Ok(MonitorConfig {
+            executable_name: self.executable.clone().unwrap_or_else(|| self.name.clone()),
+            target_query,
+            selection_policy: self.selection_policy,
+            check_interval: self.check_interval,
+            adaptive_polling: self.adaptive_polling,
+            fast_check_interval_ms: self.fast_check_interval_ms,
+            idle_check_interval: self.idle_check_interval,
+            max_backoff_interval: self.max_backoff_interval,
+            jitter_ratio: self.jitter_ratio,
+            metadata_options: self.metadata.clone(),
+            site_categories: self.site_categories.clone().unwrap_or_default(),
+            watch_foreground: self.watch_foreground,
+            process_events: self.process_events,
+            redactor,
+            on_data_change: None,
+        })
+    }
+}
+
+impl OutputConfig {
+    /// Publier une ligne (JSON Lines)
+    pub fn write_line(&self, line: &str) -> Result<()> {
+        match self {
+            Self::Stdout => println!("{}", line),
+            Self::File { path } => {
+                let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+                writeln!(file, "{}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rechargement à chaud : relit le fichier quand sa date de modification change
+pub struct ConfigWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    config: TrackerConfig,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let modified = modified_time(&path);
+        let config = TrackerConfig::load(&path)?;
+        Ok(Self { path, modified, config })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn config(&self) -> &TrackerConfig {
+        &self.config
+    }
+
+    /// Nouvelle configuration si le fichier a changé. Une configuration invalide
+    /// est signalée sans remplacer la précédente (et n'est pas relue avant la prochaine modification)
+    pub fn reload_if_changed(&mut self) -> Result<Option<&TrackerConfig>> {
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return Ok(None);
+        }
+        self.modified = modified;
+
+        self.config = TrackerConfig::load(&self.path)?;
+        Ok(Some(&self.config))
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(text: &str, format: ConfigFormat) -> String {
+        TrackerConfig::parse(text, format).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn minimal_toml_config() {
+        let config = TrackerConfig::parse(
+            r#"
+            [[targets]]
+            name = "chrome"
+            executable = "chrome.exe"
+            metadata = { cpu_info = true }
+            "#,
+            ConfigFormat::Toml,
+        )
+        .unwrap();
+        assert!(config.targets[0].metadata.cpu_info);
+        // Les options absentes gardent leur valeur par défaut
+        assert!(config.targets[0].metadata.memory_info);
+    }
+
+    #[test]
+    fn unknown_metadata_option_points_at_the_key() {
+        let error = parse_error(
+            r#"
+            [[targets]]
+            name = "chrome"
+            executable = "chrome.exe"
+            metadata = { cpu_infoo = true }
+            "#,
+            ConfigFormat::Toml,
+        );
+        assert!(error.contains("targets[0].metadata"), "{}", error);
+        assert!(error.contains("cpu_infoo"), "{}", error);
+    }
+
+    #[test]
+    fn unknown_site_category_key_points_at_the_key() {
+        let error = parse_error(
+            r#"{"targets": [{"name": "web", "query": "name~\"firefox\"",
+                "site_categories": {"categories": [{"name": "work", "keyword": ["jira"]}]}}]}"#,
+            ConfigFormat::Json,
+        );
+        assert!(error.contains("targets[0].site_categories.categories[0]"), "{}", error);
+        assert!(error.contains("keyword"), "{}", error);
+    }
+
+    #[test]
+    fn semantic_errors_name_the_target() {
+        let error = parse_error("[[targets]]\nname = \"a\"\n", ConfigFormat::Toml);
+        assert!(error.contains("targets[0]"), "{}", error);
+
+        let error = parse_error(
+            "[[targets]]\nname = \"a\"\nquery = \"nmae~\\\"a\\\"\"\n",
+            ConfigFormat::Toml,
+        );
+        assert!(error.contains("targets[0].query"), "{}", error);
+    }
+}
@@ -0,0 +1,373 @@
+use crate::models::WindowInfo;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// Type d'événement de premier plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ForegroundEventKind {
+    /// Une autre fenêtre est passée au premier plan
+    FocusChanged,
+    /// La fenêtre au premier plan a changé de titre (changement d'onglet, navigation...)
+    TitleChanged,
+}
+
+/// Événement émis par le watcher, horodaté à sa réception
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForegroundEvent {
+    pub kind: ForegroundEventKind,
+    pub window: WindowInfo,
+    pub process_name: String,
+    pub timestamp: u64, // millisecondes depuis l'epoch
+}
+
+/// Surveillance événementielle de la fenêtre au premier plan
+/// (SetWinEventHook sous Windows, PropertyNotify sur _NET_ACTIVE_WINDOW sous X11)
+pub struct ForegroundWatcher {
+    stop_requested: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    #[cfg(windows)]
+    thread_id: u32,
+}
+
+impl ForegroundWatcher {
+    /// Démarrer le watcher sur un thread dédié ; les événements arrivent sur le receiver
+    pub fn start() -> Result<(Self, UnboundedReceiver<ForegroundEvent>)> {
+        let (sender, receiver) = unbounded_channel();
+        let stop_requested = Arc::new(AtomicBool::new(false));
+
+        #[cfg(windows)]
+        {
+            // Attendre que le thread ait créé sa file de messages : sans elle, WM_QUIT serait perdu
+            // et stop() resterait bloqué sur join()
+            let (ready_sender, ready_receiver) = std::sync::mpsc::channel();
+            let thread = std::thread::Builder::new()
+                .name("sup_mtracker-foreground".to_string())
+                .spawn(move || windows_hook::run(sender, ready_sender))?;
+            let thread_id = match ready_receiver.recv() {
+                Ok(thread_id) => thread_id,
+                Err(_) => {
+                    let _ = thread.join();
+                    return Err(anyhow::anyhow!("Le thread du hook de premier plan s'est arrêté au démarrage"));
+                }
+            };
+
+            Ok((
+                Self {
+                    stop_requested,
+                    thread: Some(thread),
+                    thread_id,
+                },
+                receiver,
+            ))
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            // Connexion dédiée : la boucle d'événements ne doit pas partager celle des requêtes
+            let backend = crate::linux::x11::X11WindowBackend::connect()?;
+            let stop_clone = Arc::clone(&stop_requested);
+            let thread = std::thread::Builder::new()
+                .name("sup_mtracker-foreground".to_string())
+                .spawn(move || x11_events::run(backend, sender, stop_clone))?;
+
+            Ok((
+                Self {
+                    stop_requested,
+                    thread: Some(thread),
+                },
+                receiver,
+            ))
+        }
+
+        #[cfg(not(any(windows, target_os = "linux")))]
+        {
+            let _ = (sender, receiver, stop_requested);
+            Err(anyhow::anyhow!("Surveillance du premier plan non prise en charge sur cette plateforme"))
+        }
+    }
+
+    /// Arrêter le watcher et attendre la fin de son thread
+    pub fn stop(&mut self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+
+        #[cfg(windows)]
+        unsafe {
+            // Débloquer GetMessageW (la file existe depuis start())
+            if self.thread.is_some() {
+                winapi::um::winuser::PostThreadMessageW(self.thread_id, winapi::um::winuser::WM_QUIT, 0, 0);
+            }
+        }
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for ForegroundWatcher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(any(windows, target_os = "linux"))]
+fn emit(sender: &UnboundedSender<ForegroundEvent>, kind: ForegroundEventKind, window: WindowInfo) -> bool {
+    let process_name = crate::ProcessMetadataCollector::new().get_process_name_by_pid(window.process_id);
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+
+    sender
+        .send(ForegroundEvent {
+            kind,
+            window,
+            process_name,
+            timestamp,
+        })
+        .is_ok()
+}
+
+#[cfg(windows)]
+mod windows_hook {
+    use super::{emit, ForegroundEvent, ForegroundEventKind};
+    use crate::metadata::process_metadata::window_info_from_hwnd;
+    use std::cell::RefCell;
+    use std::mem;
+    use std::ptr::null_mut;
+    use std::sync::mpsc::Sender;
+    use tokio::sync::mpsc::UnboundedSender;
+    use winapi::shared::minwindef::DWORD;
+    use winapi::shared::ntdef::LONG;
+    use winapi::shared::windef::{HWINEVENTHOOK, HWND};
+    use winapi::um::processthreadsapi::GetCurrentThreadId;
+    use winapi::um::winuser::{
+        DispatchMessageW, GetForegroundWindow, GetMessageW, PeekMessageW, SetWinEventHook, TranslateMessage,
+        UnhookWinEvent, EVENT_OBJECT_NAMECHANGE, EVENT_SYSTEM_FOREGROUND, MSG, OBJID_WINDOW, PM_NOREMOVE,
+        WINEVENT_OUTOFCONTEXT, WINEVENT_SKIPOWNPROCESS, WM_USER,
+    };
+
+    thread_local! {
+        // Le callback WinEvent n'a pas de paramètre utilisateur : l'émetteur vit sur le thread du hook
+        static SENDER: RefCell<Option<UnboundedSender<ForegroundEvent>>> = RefCell::new(None);
+    }
+
+    pub fn run(sender: UnboundedSender<ForegroundEvent>, ready: Sender<u32>) {
+        SENDER.with(|cell| *cell.borrow_mut() = Some(sender));
+
+        unsafe {
+            // Le premier appel à PeekMessageW crée la file de messages du thread
+            let mut msg: MSG = mem::zeroed();
+            PeekMessageW(&mut msg, null_mut(), WM_USER, WM_USER, PM_NOREMOVE);
+            let _ = ready.send(GetCurrentThreadId());
+
+            let flags = WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS;
+            let foreground_hook = SetWinEventHook(
+                EVENT_SYSTEM_FOREGROUND,
+                EVENT_SYSTEM_FOREGROUND,
+                null_mut(),
+                Some(win_event_proc),
+                0,
+                0,
+                flags,
+            );
+            let name_hook = SetWinEventHook(
+                EVENT_OBJECT_NAMECHANGE,
+                EVENT_OBJECT_NAMECHANGE,
+                null_mut(),
+                Some(win_event_proc),
+                0,
+                0,
+                flags,
+            );
+
+            // Les hooks hors contexte sont délivrés via la file de messages de ce thread
+            while GetMessageW(&mut msg, null_mut(), 0, 0) > 0 {
+                TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+
+            if !foreground_hook.is_null() {
+                UnhookWinEvent(foreground_hook);
+            }
+            if !name_hook.is_null() {
+                UnhookWinEvent(name_hook);
+            }
+        }
+
+        SENDER.with(|cell| *cell.borrow_mut() = None);
+    }
+
+    unsafe extern "system" fn win_event_proc(
+        _hook: HWINEVENTHOOK,
+        event: DWORD,
+        hwnd: HWND,
+        id_object: LONG,
+        _id_child: LONG,
+        _event_thread: DWORD,
+        _event_time: DWORD,
+    ) {
+        if hwnd.is_null() || id_object != OBJID_WINDOW {
+            return;
+        }
+
+        let kind = match event {
+            EVENT_SYSTEM_FOREGROUND => ForegroundEventKind::FocusChanged,
+            // On ne garde que les changements de titre de la fenêtre au premier plan
+            EVENT_OBJECT_NAMECHANGE if hwnd == GetForegroundWindow() => ForegroundEventKind::TitleChanged,
+            _ => return,
+        };
+
+        let window = window_info_from_hwnd(hwnd);
+        SENDER.with(|cell| {
+            if let Some(sender) = cell.borrow().as_ref() {
+                emit(sender, kind, window);
+            }
+        });
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod x11_events {
+    use super::{emit, ForegroundEvent, ForegroundEventKind};
+    use crate::linux::x11::X11WindowBackend;
+    use anyhow::Result;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use sup_common::debug_eprintln;
+    use tokio::sync::mpsc::UnboundedSender;
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{ChangeWindowAttributesAux, ConnectionExt, EventMask, Window};
+    use x11rb::protocol::Event;
+
+    // Pas de réveil possible d'un wait_for_event bloquant : on interroge la file périodiquement
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    // Attente entre deux vérifications du retour du serveur X (shared() espace lui-même ses tentatives)
+    const RECONNECT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+    /// Boucle d'événements ; une erreur de connexion est journalisée et la surveillance reprend
+    /// sur une nouvelle connexion dès que le serveur X répond à nouveau
+    pub fn run(mut backend: X11WindowBackend, sender: UnboundedSender<ForegroundEvent>, stop_requested: Arc<AtomicBool>) {
+        let mut reconnected = false;
+        loop {
+            match watch(&backend, &sender, &stop_requested, reconnected) {
+                Ok(()) => return,
+                Err(e) => debug_eprintln!("❌ Erreur du watcher de premier plan X11, reconnexion: {}", e),
+            }
+            match reconnect(&stop_requested) {
+                Some(fresh) => backend = fresh,
+                None => return,
+            }
+            reconnected = true;
+        }
+    }
+
+    /// Ok(()) à l'arrêt demandé ou quand plus personne n'écoute, Err si la connexion est perdue
+    fn watch(
+        backend: &X11WindowBackend,
+        sender: &UnboundedSender<ForegroundEvent>,
+        stop_requested: &AtomicBool,
+        announce_active: bool,
+    ) -> Result<()> {
+        let conn = backend.connection();
+        let atoms = *backend.atoms();
+
+        conn.change_window_attributes(
+            backend.root(),
+            &ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+        )?;
+
+        let mut active_window = backend.active_window()?;
+        watch_window(backend, None, active_window)?;
+        conn.flush()?;
+
+        // Après une reconnexion, le focus a pu changer sans qu'on le voie
+        if announce_active {
+            if let Some(window) = active_window.and_then(|window| backend.window_info(window).ok()) {
+                if !emit(sender, ForegroundEventKind::FocusChanged, window) {
+                    return Ok(());
+                }
+            }
+        }
+
+        while !stop_requested.load(Ordering::SeqCst) {
+            let event = match conn.poll_for_event()? {
+                Some(event) => event,
+                None => {
+                    std::thread::sleep(POLL_INTERVAL);
+                    continue;
+                }
+            };
+
+            let Event::PropertyNotify(notify) = event else {
+                continue;
+            };
+
+            if notify.window == backend.root() && notify.atom == atoms._NET_ACTIVE_WINDOW {
+                let new_active = backend.active_window()?;
+                if new_active == active_window {
+                    continue;
+                }
+                watch_window(backend, active_window, new_active)?;
+                conn.flush()?;
+                active_window = new_active;
+
+                if let Some(window) = new_active.and_then(|window| backend.window_info(window).ok()) {
+                    if !emit(sender, ForegroundEventKind::FocusChanged, window) {
+                        break;
+                    }
+                }
+            } else if Some(notify.window) == active_window && notify.atom == atoms._NET_WM_NAME {
+                if let Ok(window) = backend.window_info(notify.window) {
+                    if !emit(sender, ForegroundEventKind::TitleChanged, window) {
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Nouvelle connexion dédiée quand le serveur X est de nouveau joignable. `shared()` sert de
+    /// sonde (avec son délai entre tentatives) ; la boucle garde sa propre connexion, car
+    /// `shared()` vide la file d'événements de la connexion commune.
+    fn reconnect(stop_requested: &AtomicBool) -> Option<X11WindowBackend> {
+        while !stop_requested.load(Ordering::SeqCst) {
+            if X11WindowBackend::shared().is_some() {
+                match X11WindowBackend::connect() {
+                    Ok(backend) => return Some(backend),
+                    Err(e) => debug_eprintln!("❌ Reconnexion X11 du watcher de premier plan impossible: {}", e),
+                }
+            }
+            std::thread::sleep(RECONNECT_POLL_INTERVAL);
+        }
+        None
+    }
+
+    // Suivre les changements de titre de la fenêtre active uniquement
+    fn watch_window(backend: &X11WindowBackend, previous: Option<Window>, current: Option<Window>) -> Result<()> {
+        let conn = backend.connection();
+        if let Some(previous) = previous {
+            // La fenêtre peut déjà avoir été détruite : erreur ignorée
+            let _ = conn.change_window_attributes(
+                previous,
+                &ChangeWindowAttributesAux::new().event_mask(EventMask::NO_EVENT),
+            );
+        }
+        if let Some(current) = current {
+            conn.change_window_attributes(
+                current,
+                &ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+//! Icônes Windows : groupes RT_GROUP_ICON des ressources PE, fichiers .ico et images DIB
+
+use super::{png, IconImage};
+use crate::binfmt::pe::{RT_GROUP_ICON, RT_ICON};
+use crate::binfmt::{u16_at, u32_at, PeFile, ReadAt};
+use anyhow::{bail, Result};
+
+// BITMAPINFOHEADER
+const BITMAP_HEADER_SIZE: usize = 40;
+const BI_RGB: u32 = 0;
+const BI_BITFIELDS: u32 = 3;
+
+/// Images du premier groupe d'icônes d'un binaire PE (l'icône affichée par l'Explorateur)
+pub fn pe_icons(source: &mut impl ReadAt) -> Result<Vec<IconImage>> {
+    let pe = PeFile::parse(source)?;
+    let groups = pe.resources(source, RT_GROUP_ICON)?;
+    let Some(group) = groups.first() else {
+        return Ok(Vec::new());
+    };
+    let icons = pe.resources(source, RT_ICON)?;
+
+    // GRPICONDIR : reserved, type, count puis GRPICONDIRENTRY de 14 octets (nID en dernier)
+    let count = u16_at(&group.data, 4, false).unwrap_or(0) as usize;
+    let mut images = Vec::new();
+    for index in 0..count {
+        let Some(id) = u16_at(&group.data, 6 + index * 14 + 12, false) else {
+            break;
+        };
+        let Some(icon) = icons.iter().find(|icon| icon.id == Some(id)) else {
+            continue;
+        };
+        if let Ok(image) = decode_entry(&icon.data) {
+            images.push(image);
+        }
+    }
+    Ok(images)
+}
+
+/// Images d'un fichier .ico (ICONDIRENTRY de 16 octets avec position dans le fichier)
+pub fn ico_file(data: &[u8]) -> Result<Vec<IconImage>> {
+    if u16_at(data, 0, false) != Some(0) || u16_at(data, 2, false) != Some(1) {
+        bail!("En-tête ICO invalide");
+    }
+    let count = u16_at(data, 4, false).unwrap_or(0) as usize;
+    let mut images = Vec::new();
+    for index in 0..count {
+        let entry = 6 + index * 16;
+        let (Some(size), Some(offset)) = (u32_at(data, entry + 8, false), u32_at(data, entry + 12, false)) else {
+            break;
+        };
+        let Some(bytes) = data.get(offset as usize..offset as usize + size as usize) else {
+            continue;
+        };
+        if let Ok(image) = decode_entry(bytes) {
+            images.push(image);
+        }
+    }
+    Ok(images)
+}
+
+/// Une entrée d'icône est soit un PNG complet (256x256 depuis Vista), soit un DIB
+fn decode_entry(data: &[u8]) -> Result<IconImage> {
+    if png::is_png(data) {
+        png::decode(data)
+    } else {
+        decode_dib(data)
+    }
+}
+
+/// DIB d'icône : hauteur doublée, lignes XOR de bas en haut puis masque AND 1 bit
+fn decode_dib(data: &[u8]) -> Result<IconImage> {
+    let header_size = u32_at(data, 0, false).unwrap_or(0) as usize;
+    if header_size < BITMAP_HEADER_SIZE {
+        bail!("En-tête DIB invalide");
+    }
+    let width = u32_at(data, 4, false).unwrap_or(0) as i32;
+    let height = u32_at(data, 8, false).unwrap_or(0) as i32 / 2;
+    let bit_count = u16_at(data, 14, false).unwrap_or(0) as usize;
+    let compression = u32_at(data, 16, false).unwrap_or(0);
+    let used_colors = u32_at(data, 32, false).unwrap_or(0) as usize;
+
+    if width <= 0 || height <= 0 || width > 1024 || height > 1024 {
+        bail!("Dimensions DIB non supportées");
+    }
+    if !matches!(compression, BI_RGB | BI_BITFIELDS) || !matches!(bit_count, 1 | 4 | 8 | 24 | 32) {
+        bail!("Format DIB non supporté ({} bits, compression {})", bit_count, compression);
+    }
+    let (width, height) = (width as usize, height as usize);
+
+    // Palette RGBQUAD (B, G, R, réservé) pour les profondeurs <= 8
+    let mut position = header_size;
+    if compression == BI_BITFIELDS {
+        position += 12;
+    }
+    let palette_size = if bit_count <= 8 {
+        if used_colors > 0 { used_colors } else { 1 << bit_count }
+    } else {
+        0
+    };
+    let palette: Vec<[u8; 3]> = data
+        .get(position..position + palette_size * 4)
+        .unwrap_or_default()
+        .chunks_exact(4)
+        .map(|quad| [quad[2], quad[1], quad[0]])
+        .collect();
+    position += palette_size * 4;
+
+    let stride = (width * bit_count).div_ceil(32) * 4;
+    let mask_stride = width.div_ceil(32) * 4;
+    let Some(pixels) = data.get(position..position + stride * height) else {
+        bail!("Données DIB tronquées");
+    };
+    let mask = data.get(position + stride * height..position + (stride + mask_stride) * height);
+
+    let mut rgba = vec![0u8; width * height * 4];
+    let mut has_alpha = false;
+    for y in 0..height {
+        // Lignes stockées de bas en haut
+        let row = &pixels[(height - 1 - y) * stride..(height - y) * stride];
+        for x in 0..width {
+            let pixel = match bit_count {
+                32 => [row[x * 4 + 2], row[x * 4 + 1], row[x * 4], row[x * 4 + 3]],
+                24 => [row[x * 3 + 2], row[x * 3 + 1], row[x * 3], 255],
+                _ => {
+                    let bit = x * bit_count;
+                    let index = (row[bit / 8] >> (8 - bit_count - bit % 8)) & ((1u16 << bit_count) - 1) as u8;
+                    let [r, g, b] = palette.get(index as usize).copied().unwrap_or_default();
+                    [r, g, b, 255]
+                }
+            };
+            has_alpha |= bit_count == 32 && pixel[3] != 0;
+            rgba[(y * width + x) * 4..(y * width + x + 1) * 4].copy_from_slice(&pixel);
+        }
+    }
+
+    // Sans canal alpha exploitable, la transparence vient du masque AND
+    if !has_alpha {
+        if let Some(mask) = mask {
+            for y in 0..height {
+                let row = &mask[(height - 1 - y) * mask_stride..(height - y) * mask_stride];
+                for x in 0..width {
+                    let transparent = row[x / 8] & (0x80 >> (x % 8)) != 0;
+                    rgba[(y * width + x) * 4 + 3] = if transparent { 0 } else { 255 };
+                }
+            }
+        } else {
+            rgba.chunks_exact_mut(4).for_each(|pixel| pixel[3] = 255);
+        }
+    }
+
+    Ok(IconImage {
+        width: width as u32,
+        height: height as u32,
+        rgba,
+    })
+}
@@ -0,0 +1,380 @@
+//! Extraction des icônes d'applications et de fenêtres, livrées en PNG à la taille demandée
+
+pub mod ico;
+pub mod png;
+#[cfg(target_os = "linux")]
+pub mod theme;
+
+use crate::app_identity::executable_path;
+use crate::binfmt::{executable_info, hex, BinaryFormat, ReadAt};
+use crate::models::{AppIdentity, ApplicationInfo, WindowInfo};
+use anyhow::{bail, Result};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use sup_common::debug_println;
+
+// Au-delà, l'icône n'a plus de sens pour un lanceur
+const MAX_ICON_SIZE: u32 = 1024;
+
+/// Image décodée en RGBA 8 bits, lignes de haut en bas
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IconImage {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl IconImage {
+    /// Redimensionner en carré `size` x `size` (moyenne des pixels couverts, alpha prémultiplié)
+    pub fn resize(&self, size: u32) -> IconImage {
+        if self.width == size && self.height == size {
+            return self.clone();
+        }
+
+        let scale_x = self.width as f64 / size as f64;
+        let scale_y = self.height as f64 / size as f64;
+        let mut rgba = Vec::with_capacity((size * size * 4) as usize);
+
+        for y in 0..size {
+            let (top, bottom) = (y as f64 * scale_y, (y + 1) as f64 * scale_y);
+            for x in 0..size {
+                let (left, right) = (x as f64 * scale_x, (x + 1) as f64 * scale_x);
+                let mut sum = [0f64; 4];
+                let mut total = 0f64;
+
+                for source_y in top.floor() as u32..(bottom.ceil() as u32).min(self.height) {
+                    let weight_y = (bottom.min(source_y as f64 + 1.0) - top.max(source_y as f64)).max(0.0);
+                    for source_x in left.floor() as u32..(right.ceil() as u32).min(self.width) {
+                        let weight_x = (right.min(source_x as f64 + 1.0) - left.max(source_x as f64)).max(0.0);
+                        let weight = weight_x * weight_y;
+                        let index = ((source_y * self.width + source_x) * 4) as usize;
+                        let alpha = self.rgba[index + 3] as f64 / 255.0;
+                        for (channel, value) in sum.iter_mut().take(3).enumerate() {
+                            *value += self.rgba[index + channel] as f64 * alpha * weight;
+                        }
+                        sum[3] += alpha * weight;
+                        total += weight;
+                    }
+                }
+
+                let alpha = if total > 0.0 { sum[3] / total } else { 0.0 };
+                for channel in sum.iter().take(3) {
+                    let value = if sum[3] > 0.0 { channel / sum[3] } else { 0.0 };
+                    rgba.push(value.round().clamp(0.0, 255.0) as u8);
+                }
+                rgba.push((alpha * 255.0).round().clamp(0.0, 255.0) as u8);
+            }
+        }
+
+        IconImage {
+            width: size,
+            height: size,
+            rgba,
+        }
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        png::encode(self)
+    }
+}
+
+/// Plus petite image couvrant la taille demandée, sinon la plus grande, ramenée à `size`
+pub fn best_image(images: &[IconImage], size: u32) -> Option<IconImage> {
+    let side = |image: &&IconImage| image.width.min(image.height);
+    let larger = images.iter().filter(|image| side(image) >= size).min_by_key(side);
+    larger
+        .or_else(|| images.iter().max_by_key(side))
+        .map(|image| image.resize(size))
+}
+
+/// Extraction d'icônes avec cache disque des PNG produits
+/// (`<sha256 de l'exécutable>_<taille>.png`, ou empreinte du fichier d'icône du thème)
+pub struct IconExtractor {
+    cache_dir: Option<PathBuf>,
+}
+
+static SHARED_EXTRACTOR: OnceLock<Arc<IconExtractor>> = OnceLock::new();
+
+// Suffixe des fichiers temporaires du cache (plusieurs extractions concurrentes dans un processus)
+static TEMPORARY_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+impl Default for IconExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IconExtractor {
+    /// Cache dans le répertoire utilisateur par défaut
+    pub fn new() -> Self {
+        Self::with_cache_dir(default_cache_dir())
+    }
+
+    /// `None` désactive le cache disque
+    pub fn with_cache_dir(cache_dir: Option<PathBuf>) -> Self {
+        Self { cache_dir }
+    }
+
+    pub fn shared() -> Arc<Self> {
+        Arc::clone(SHARED_EXTRACTOR.get_or_init(|| Arc::new(Self::new())))
+    }
+
+    pub fn cache_dir(&self) -> Option<&Path> {
+        self.cache_dir.as_deref()
+    }
+
+    /// Supprimer les PNG en cache (et les fichiers temporaires d'une écriture interrompue)
+    pub fn clear_cache(&self) -> Result<()> {
+        let Some(directory) = &self.cache_dir else {
+            return Ok(());
+        };
+        let Ok(entries) = fs::read_dir(directory) else {
+            return Ok(());
+        };
+        for entry in entries.flatten() {
+            if matches!(entry.path().extension().and_then(|ext| ext.to_str()), Some("png" | "tmp")) {
+                fs::remove_file(entry.path())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Icône embarquée dans un binaire PE (premier RT_GROUP_ICON), None si le binaire n'en a pas
+    pub fn executable_icon(&self, path: &Path, size: u32) -> Result<Option<Vec<u8>>> {
+        check_size(size)?;
+        let info = executable_info(path)?;
+        if info.format != BinaryFormat::Pe.as_str() {
+            // Les binaires ELF ne portent pas d'icône
+            return Ok(None);
+        }
+
+        let key = format!("{}_{}", info.sha256, size);
+        self.cached(&key, || {
+            let mut file = File::open(path)?;
+            Ok(best_image(&ico::pe_icons(&mut file)?, size))
+        })
+    }
+
+    /// Icône désignée par une identité : nom du thème ou chemin (`Icon=` d'un .desktop),
+    /// binaire PE ou fichier .ico / .png
+    pub fn named_icon(&self, icon: &str, size: u32) -> Result<Option<Vec<u8>>> {
+        check_size(size)?;
+
+        #[cfg(target_os = "linux")]
+        let path = theme::lookup(icon, size);
+        #[cfg(not(target_os = "linux"))]
+        let path = Some(PathBuf::from(icon)).filter(|path| path.is_file());
+
+        let Some(path) = path else {
+            return Ok(None);
+        };
+        if is_icon_file(&path) {
+            self.icon_file(&path, size)
+        } else {
+            self.executable_icon(&path, size)
+        }
+    }
+
+    /// Icône publiée par la fenêtre (_NET_WM_ICON sous X11), sinon celle de l'exécutable du processus.
+    /// Les icônes de fenêtre peuvent changer à tout moment : elles ne passent pas par le cache.
+    pub fn window_icon(&self, window: &WindowInfo, size: u32) -> Result<Option<Vec<u8>>> {
+        check_size(size)?;
+
+        #[cfg(target_os = "linux")]
+        if let Some(backend) = crate::linux::x11::X11WindowBackend::shared() {
+            match backend.window_icons(window.hwnd as u32) {
+                Ok(images) => {
+                    if let Some(image) = best_image(&images, size) {
+                        return Ok(Some(image.to_png()));
+                    }
+                }
+                Err(e) => debug_println!("_NET_WM_ICON illisible pour 0x{:x}: {}", window.hwnd, e),
+            }
+        }
+
+        // Windows : l'icône de classe est celle de l'exécutable dans l'immense majorité des cas
+        match executable_path(window.process_id) {
+            Some(path) => self.executable_icon(Path::new(&path), size),
+            None => Ok(None),
+        }
+    }
+
+    /// Icône d'une application retournée par `scan_applications` :
+    /// identité résolue, puis fenêtre principale, puis exécutable
+    pub fn application_icon(&self, application: &ApplicationInfo, size: u32) -> Result<Option<Vec<u8>>> {
+        check_size(size)?;
+        let process = &application.main_process;
+
+        if let Some(icon) = application.identity.as_ref().and_then(|identity| identity.icon.as_deref()) {
+            match self.named_icon(icon, size) {
+                Ok(Some(png)) => return Ok(Some(png)),
+                Ok(None) => {}
+                Err(e) => debug_println!("Icône '{}' inutilisable: {}", icon, e),
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        if let Some(backend) = crate::linux::x11::X11WindowBackend::shared() {
+            for window in backend.windows_for_process(process.pid).unwrap_or_default() {
+                if let Ok(Some(png)) = self.window_icon(&window, size) {
+                    return Ok(Some(png));
+                }
+            }
+        }
+
+        let path = process.executable_path.clone().or_else(|| executable_path(process.pid));
+        match path {
+            Some(path) => self.executable_icon(Path::new(&path), size),
+            None => Ok(None),
+        }
+    }
+
+    /// Raccourci pour une identité seule (sans processus associé)
+    pub fn identity_icon(&self, identity: &AppIdentity, size: u32) -> Result<Option<Vec<u8>>> {
+        match &identity.icon {
+            Some(icon) => self.named_icon(icon, size),
+            None => Ok(None),
+        }
+    }
+
+    fn icon_file(&self, path: &Path, size: u32) -> Result<Option<Vec<u8>>> {
+        let metadata = fs::metadata(path)?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        // Le fichier du thème peut être remplacé : chemin, taille et date dans l'empreinte
+        let fingerprint = Sha256::digest(format!("{}|{}|{}", path.display(), metadata.len(), modified));
+        let key = format!("{}_{}", hex(&fingerprint), size);
+
+        self.cached(&key, || {
+            let data = fs::read(path)?;
+            let images = if png::is_png(&data) {
+                vec![png::decode(&data)?]
+            } else {
+                ico::ico_file(&data)?
+            };
+            Ok(best_image(&images, size))
+        })
+    }
+
+    fn cached(&self, key: &str, extract: impl FnOnce() -> Result<Option<IconImage>>) -> Result<Option<Vec<u8>>> {
+        let cache_path = self.cache_dir.as_ref().map(|directory| directory.join(format!("{}.png", key)));
+        if let Some(png) = cache_path.as_ref().and_then(|path| fs::read(path).ok()) {
+            return Ok(Some(png));
+        }
+
+        let Some(image) = extract()? else {
+            return Ok(None);
+        };
+        let png = image.to_png();
+
+        // Un cache inaccessible ne doit pas priver l'appelant de l'icône
+        if let (Some(directory), Some(path)) = (&self.cache_dir, &cache_path) {
+            if let Err(e) = fs::create_dir_all(directory).and_then(|_| write_atomic(path, &png)) {
+                debug_println!("Cache d'icônes inaccessible ({}): {}", path.display(), e);
+            }
+        }
+        Ok(Some(png))
+    }
+}
+
+/// Écrire dans un fichier temporaire puis renommer : un lecteur concurrent (autre thread
+/// ou autre processus) ne voit jamais de PNG partiel
+fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let temporary = path.with_file_name(format!(
+        ".{}.{}.{}.tmp",
+        name,
+        std::process::id(),
+        TEMPORARY_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = fs::write(&temporary, data).and_then(|_| fs::rename(&temporary, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    result
+}
+
+fn check_size(size: u32) -> Result<()> {
+    if size == 0 || size > MAX_ICON_SIZE {
+        bail!("Taille d'icône invalide: {} (1 à {})", size, MAX_ICON_SIZE);
+    }
+    Ok(())
+}
+
+/// .png ou .ico reconnus à leurs premiers octets (un PE commence par "MZ")
+fn is_icon_file(path: &Path) -> bool {
+    let Ok(mut file) = File::open(path) else {
+        return false;
+    };
+    let magic = file.read_at(0, 8).unwrap_or_default();
+    png::is_png(&magic) || magic.starts_with(&[0, 0, 1, 0])
+}
+
+/// $XDG_CACHE_HOME/sup_mtracker/icons (Linux), %LOCALAPPDATA%\sup_mtracker\icons (Windows)
+fn default_cache_dir() -> Option<PathBuf> {
+    #[cfg(windows)]
+    let base = std::env::var_os("LOCALAPPDATA").map(PathBuf::from);
+    #[cfg(not(windows))]
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")));
+
+    base.map(|base| base.join("sup_mtracker").join("icons"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temporary_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("sup_mtracker-icons-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    #[test]
+    fn icon_files_are_cached_atomically() {
+        let directory = temporary_directory("cache");
+        let icon = format!("{}/tests/fixtures/png/rgba8.png", env!("CARGO_MANIFEST_DIR"));
+        let extractor = IconExtractor::with_cache_dir(Some(directory.clone()));
+
+        let first = extractor.icon_file(Path::new(&icon), 16).unwrap().unwrap();
+        assert_eq!(png::decode(&first).unwrap().width, 16);
+
+        // Un seul PNG dans le cache, aucun fichier temporaire résiduel
+        let names: Vec<String> = fs::read_dir(&directory)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names.len(), 1, "{:?}", names);
+        assert!(names[0].ends_with("_16.png"));
+
+        // Deuxième appel servi par le cache
+        assert_eq!(extractor.icon_file(Path::new(&icon), 16).unwrap().unwrap(), first);
+
+        extractor.clear_cache().unwrap();
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 0);
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn atomic_write_replaces_existing_file() {
+        let directory = temporary_directory("write");
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("icon.png");
+        write_atomic(&path, b"old").unwrap();
+        write_atomic(&path, b"new").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 1);
+        let _ = fs::remove_dir_all(&directory);
+    }
+}
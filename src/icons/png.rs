@@ -0,0 +1,734 @@
+//! Encodage et décodage PNG minimal (8 bits RGBA en sortie, sans entrelacement),
+//! suffisant pour les icônes des thèmes, des ressources PE et de _NET_WM_ICON
+
+use super::IconImage;
+use anyhow::{bail, Result};
+
+const SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+// Taille maximale acceptée pour une icône (évite d'allouer pour une image corrompue)
+const MAX_DIMENSION: u32 = 4096;
+
+pub fn is_png(data: &[u8]) -> bool {
+    data.starts_with(SIGNATURE)
+}
+
+/// Encoder une image RGBA en PNG (deflate à codes fixes)
+pub fn encode(image: &IconImage) -> Vec<u8> {
+    let row_size = image.width as usize * 4;
+    // Filtre "Sub" sur chaque ligne : les aplats d'une icône deviennent des suites de zéros
+    let mut raw = Vec::with_capacity((row_size + 1) * image.height as usize);
+    for row in image.rgba.chunks_exact(row_size) {
+        raw.push(1);
+        for (index, byte) in row.iter().enumerate() {
+            let left = if index >= 4 { row[index - 4] } else { 0 };
+            raw.push(byte.wrapping_sub(left));
+        }
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&image.width.to_be_bytes());
+    header.extend_from_slice(&image.height.to_be_bytes());
+    // Profondeur 8, RGBA, compression/filtre standard, pas d'entrelacement
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib_compress(&raw));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// Décoder un PNG vers une image RGBA 8 bits
+pub fn decode(data: &[u8]) -> Result<IconImage> {
+    if !is_png(data) {
+        bail!("Signature PNG absente");
+    }
+
+    let mut position = SIGNATURE.len();
+    let mut header = None;
+    let mut palette: Vec<[u8; 3]> = Vec::new();
+    let mut transparency: Vec<u8> = Vec::new();
+    let mut compressed = Vec::new();
+
+    while position + 8 <= data.len() {
+        let length = u32::from_be_bytes(data[position..position + 4].try_into()?) as usize;
+        let kind = &data[position + 4..position + 8];
+        let Some(body) = data.get(position + 8..position + 8 + length) else {
+            bail!("Bloc PNG tronqué");
+        };
+        match kind {
+            b"IHDR" => header = Some(PngHeader::parse(body)?),
+            b"PLTE" => palette = body.chunks_exact(3).map(|rgb| [rgb[0], rgb[1], rgb[2]]).collect(),
+            b"tRNS" => transparency = body.to_vec(),
+            b"IDAT" => compressed.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+        // Longueur + type + données + CRC
+        position += 12 + length;
+    }
+
+    let Some(header) = header else {
+        bail!("En-tête PNG absent");
+    };
+    // Pas plus que ce qu'annonce l'en-tête : un flux gonflé artificiellement est rejeté
+    let raw = zlib_decompress(&compressed, header.filtered_size())?;
+    let pixels = unfilter(&header, &raw)?;
+    Ok(header.to_rgba(&pixels, &palette, &transparency))
+}
+
+struct PngHeader {
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    color_type: u8,
+}
+
+impl PngHeader {
+    fn parse(body: &[u8]) -> Result<Self> {
+        if body.len() < 13 {
+            bail!("En-tête PNG invalide");
+        }
+        let header = Self {
+            width: u32::from_be_bytes(body[0..4].try_into()?),
+            height: u32::from_be_bytes(body[4..8].try_into()?),
+            bit_depth: body[8],
+            color_type: body[9],
+        };
+        if header.width == 0 || header.height == 0 || header.width > MAX_DIMENSION || header.height > MAX_DIMENSION {
+            bail!("Dimensions PNG non supportées ({}x{})", header.width, header.height);
+        }
+        if body[12] != 0 {
+            bail!("PNG entrelacé non supporté");
+        }
+        // Combinaisons autorisées par la spécification (PNG 11.2.2)
+        let supported = match header.color_type {
+            0 => matches!(header.bit_depth, 1 | 2 | 4 | 8 | 16),
+            3 => matches!(header.bit_depth, 1 | 2 | 4 | 8),
+            2 | 4 | 6 => matches!(header.bit_depth, 8 | 16),
+            _ => false,
+        };
+        if !supported {
+            bail!("Format PNG non supporté (type {}, profondeur {})", header.color_type, header.bit_depth);
+        }
+        Ok(header)
+    }
+
+    fn channels(&self) -> usize {
+        match self.color_type {
+            0 | 3 => 1,
+            2 => 3,
+            4 => 2,
+            6 => 4,
+            _ => 0,
+        }
+    }
+
+    fn bits_per_pixel(&self) -> usize {
+        self.channels() * self.bit_depth as usize
+    }
+
+    fn row_size(&self) -> usize {
+        (self.width as usize * self.bits_per_pixel()).div_ceil(8)
+    }
+
+    /// Taille des données décompressées : un octet de filtre par ligne
+    fn filtered_size(&self) -> usize {
+        (self.row_size() + 1) * self.height as usize
+    }
+
+    /// Convertir les lignes défiltrées en RGBA 8 bits
+    fn to_rgba(&self, pixels: &[u8], palette: &[[u8; 3]], transparency: &[u8]) -> IconImage {
+        let width = self.width as usize;
+        let row_size = self.row_size();
+        let mut rgba = Vec::with_capacity(width * self.height as usize * 4);
+
+        for row in pixels.chunks_exact(row_size) {
+            for x in 0..width {
+                let pixel = match (self.color_type, self.bit_depth) {
+                    // Niveaux de gris ou palette en profondeur < 8 : plusieurs pixels par octet
+                    (0 | 3, depth) if depth < 8 => {
+                        let depth = depth as usize;
+                        let bit = x * depth;
+                        let value = (row[bit / 8] >> (8 - depth - bit % 8)) & ((1 << depth) - 1) as u8;
+                        if self.color_type == 3 {
+                            palette_color(value, palette, transparency)
+                        } else {
+                            let gray = (value as u32 * 255 / ((1 << depth) - 1)) as u8;
+                            [gray, gray, gray, 255]
+                        }
+                    }
+                    (color_type, depth) => {
+                        // 16 bits : octet de poids fort de chaque composante
+                        let step = depth as usize / 8;
+                        let sample = |channel: usize| row[(x * self.channels() + channel) * step];
+                        match color_type {
+                            0 => [sample(0), sample(0), sample(0), 255],
+                            2 => [sample(0), sample(1), sample(2), 255],
+                            3 => palette_color(sample(0), palette, transparency),
+                            4 => [sample(0), sample(0), sample(0), sample(1)],
+                            _ => [sample(0), sample(1), sample(2), sample(3)],
+                        }
+                    }
+                };
+                rgba.extend_from_slice(&pixel);
+            }
+        }
+
+        IconImage {
+            width: self.width,
+            height: self.height,
+            rgba,
+        }
+    }
+}
+
+fn palette_color(index: u8, palette: &[[u8; 3]], transparency: &[u8]) -> [u8; 4] {
+    let [r, g, b] = palette.get(index as usize).copied().unwrap_or_default();
+    [r, g, b, transparency.get(index as usize).copied().unwrap_or(255)]
+}
+
+/// Annuler les filtres de ligne (None, Sub, Up, Average, Paeth)
+fn unfilter(header: &PngHeader, raw: &[u8]) -> Result<Vec<u8>> {
+    let row_size = header.row_size();
+    let bpp = header.bits_per_pixel().div_ceil(8);
+    let height = header.height as usize;
+    if raw.len() < (row_size + 1) * height {
+        bail!("Données PNG tronquées");
+    }
+
+    let mut pixels = vec![0u8; row_size * height];
+    for y in 0..height {
+        let filter = raw[y * (row_size + 1)];
+        let source = &raw[y * (row_size + 1) + 1..(y + 1) * (row_size + 1)];
+        let (previous, current) = pixels.split_at_mut(y * row_size);
+        let above = if y > 0 { &previous[(y - 1) * row_size..] } else { &[][..] };
+        let current = &mut current[..row_size];
+
+        for x in 0..row_size {
+            let left = if x >= bpp { current[x - bpp] } else { 0 };
+            let up = above.get(x).copied().unwrap_or(0);
+            let up_left = if x >= bpp { above.get(x - bpp).copied().unwrap_or(0) } else { 0 };
+            let predictor = match filter {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                4 => paeth(left, up, up_left),
+                _ => bail!("Filtre PNG inconnu ({})", filter),
+            };
+            current[x] = source[x].wrapping_add(predictor);
+        }
+    }
+    Ok(pixels)
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - up_left as i16;
+    let distance_left = (estimate - left as i16).abs();
+    let distance_up = (estimate - up as i16).abs();
+    let distance_up_left = (estimate - up_left as i16).abs();
+    if distance_left <= distance_up && distance_left <= distance_up_left {
+        left
+    } else if distance_up <= distance_up_left {
+        up
+    } else {
+        up_left
+    }
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], body: &[u8]) {
+    png.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(body);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+// --- Deflate ---
+
+// Longueurs et distances de base (RFC 1951, 3.2.5)
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+// Ordre des longueurs de codes dans un bloc à codes dynamiques
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+const WINDOW_SIZE: usize = 32 * 1024;
+const MAX_MATCH: usize = 258;
+const MIN_MATCH: usize = 3;
+const HASH_SIZE: usize = 1 << 15;
+
+/// zlib : en-tête, un bloc deflate à codes fixes (LZ77 simple), Adler-32
+fn zlib_compress(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::default();
+    writer.bits(1, 1); // dernier bloc
+    writer.bits(1, 2); // codes fixes
+
+    let mut head = vec![usize::MAX; HASH_SIZE];
+    let hash = |at: usize| {
+        let value = (data[at] as usize) << 16 | (data[at + 1] as usize) << 8 | data[at + 2] as usize;
+        (value.wrapping_mul(2654435761)) >> 17 & (HASH_SIZE - 1)
+    };
+
+    let mut position = 0;
+    while position < data.len() {
+        let mut best = (0, 0);
+        if position + MIN_MATCH <= data.len() {
+            let key = hash(position);
+            let candidate = head[key];
+            head[key] = position;
+            if candidate != usize::MAX && position - candidate <= WINDOW_SIZE {
+                let limit = (data.len() - position).min(MAX_MATCH);
+                let length = (0..limit)
+                    .take_while(|offset| data[candidate + offset] == data[position + offset])
+                    .count();
+                if length >= MIN_MATCH {
+                    best = (length, position - candidate);
+                }
+            }
+        }
+
+        if best.0 >= MIN_MATCH {
+            writer.length_distance(best.0, best.1);
+            position += best.0;
+        } else {
+            writer.literal(data[position] as u16);
+            position += 1;
+        }
+    }
+    writer.literal(256);
+
+    let mut zlib = vec![0x78, 0x01];
+    zlib.extend_from_slice(&writer.finish());
+    zlib.extend_from_slice(&adler32(data).to_be_bytes());
+    zlib
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn bits(&mut self, value: u32, count: u32) {
+        self.buffer |= value << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Les codes de Huffman s'écrivent bit de poids fort en premier
+    fn code(&mut self, code: u32, length: u32) {
+        let reversed = code.reverse_bits() >> (32 - length);
+        self.bits(reversed, length);
+    }
+
+    /// Table des codes fixes (RFC 1951, 3.2.6)
+    fn literal(&mut self, symbol: u16) {
+        match symbol {
+            0..=143 => self.code(0x30 + symbol as u32, 8),
+            144..=255 => self.code(0x190 + symbol as u32 - 144, 9),
+            256..=279 => self.code(symbol as u32 - 256, 7),
+            _ => self.code(0xC0 + symbol as u32 - 280, 8),
+        }
+    }
+
+    fn length_distance(&mut self, length: usize, distance: usize) {
+        let index = LENGTH_BASE.iter().rposition(|base| *base as usize <= length).unwrap_or(0);
+        self.literal(257 + index as u16);
+        self.bits((length - LENGTH_BASE[index] as usize) as u32, LENGTH_EXTRA[index] as u32);
+
+        let index = DISTANCE_BASE.iter().rposition(|base| *base as usize <= distance).unwrap_or(0);
+        self.code(index as u32, 5);
+        self.bits((distance - DISTANCE_BASE[index] as usize) as u32, DISTANCE_EXTRA[index] as u32);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    buffer: u64,
+    count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn bits(&mut self, count: u32) -> Result<u32> {
+        while self.count < count {
+            let Some(byte) = self.data.get(self.position) else {
+                bail!("Flux deflate tronqué");
+            };
+            self.buffer |= (*byte as u64) << self.count;
+            self.position += 1;
+            self.count += 8;
+        }
+        let value = (self.buffer & ((1u64 << count) - 1)) as u32;
+        self.buffer >>= count;
+        self.count -= count;
+        Ok(value)
+    }
+
+    fn align_to_byte(&mut self) {
+        let skip = self.count % 8;
+        self.buffer >>= skip;
+        self.count -= skip;
+    }
+}
+
+/// Table de décodage canonique : nombre de codes par longueur et symboles triés
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; 16];
+        for length in lengths {
+            counts[*length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut offsets = [0u16; 16];
+        for length in 1..16 {
+            offsets[length] = offsets[length - 1] + counts[length - 1];
+        }
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, length) in lengths.iter().enumerate() {
+            if *length != 0 {
+                symbols[offsets[*length as usize] as usize] = symbol as u16;
+                offsets[*length as usize] += 1;
+            }
+        }
+        Self { counts, symbols }
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for length in 1..16 {
+            code |= reader.bits(1)? as i32;
+            let count = self.counts[length] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        bail!("Code de Huffman invalide")
+    }
+}
+
+fn zlib_decompress(data: &[u8], max_size: usize) -> Result<Vec<u8>> {
+    // CM = 8 (deflate), contrôle FCHECK, pas de dictionnaire prédéfini (FDICT)
+    if data.len() < 2 || data[0] & 0x0F != 8 || !u16::from_be_bytes([data[0], data[1]]).is_multiple_of(31) || data[1] & 0x20 != 0 {
+        bail!("Flux zlib invalide");
+    }
+    inflate(&data[2..], max_size)
+}
+
+fn inflate(data: &[u8], max_size: usize) -> Result<Vec<u8>> {
+    let mut reader = BitReader::new(data);
+    let mut output = Vec::with_capacity(max_size);
+
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => {
+                reader.align_to_byte();
+                let length = reader.bits(16)?;
+                if reader.bits(16)? != !length & 0xFFFF {
+                    bail!("Longueur de bloc stocké invalide");
+                }
+                let length = length as usize;
+                if output.len() + length > max_size {
+                    bail!("Données deflate plus longues qu'annoncé");
+                }
+                for _ in 0..length {
+                    output.push(reader.bits(8)? as u8);
+                }
+            }
+            1 => {
+                let mut lengths = [0u8; 288];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..].fill(8);
+                let literals = Huffman::new(&lengths);
+                let distances = Huffman::new(&[5u8; 30]);
+                inflate_block(&mut reader, &mut output, max_size, &literals, &distances)?;
+            }
+            2 => {
+                let (literals, distances) = dynamic_tables(&mut reader)?;
+                inflate_block(&mut reader, &mut output, max_size, &literals, &distances)?;
+            }
+            _ => bail!("Type de bloc deflate invalide"),
+        }
+        if last {
+            return Ok(output);
+        }
+    }
+}
+
+fn dynamic_tables(reader: &mut BitReader) -> Result<(Huffman, Huffman)> {
+    let literal_count = reader.bits(5)? as usize + 257;
+    let distance_count = reader.bits(5)? as usize + 1;
+    let code_count = reader.bits(4)? as usize + 4;
+
+    let mut code_lengths = [0u8; 19];
+    for index in CODE_LENGTH_ORDER.iter().take(code_count) {
+        code_lengths[*index] = reader.bits(3)? as u8;
+    }
+    let code_table = Huffman::new(&code_lengths);
+
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let (value, repeat) = match code_table.decode(reader)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => {
+                let Some(previous) = lengths.last().copied() else {
+                    bail!("Répétition sans longueur précédente");
+                };
+                (previous, 3 + reader.bits(2)? as usize)
+            }
+            17 => (0, 3 + reader.bits(3)? as usize),
+            _ => (0, 11 + reader.bits(7)? as usize),
+        };
+        lengths.extend(std::iter::repeat_n(value, repeat));
+    }
+    if lengths.len() > literal_count + distance_count {
+        bail!("Longueurs de codes deflate invalides");
+    }
+
+    Ok((
+        Huffman::new(&lengths[..literal_count]),
+        Huffman::new(&lengths[literal_count..]),
+    ))
+}
+
+fn inflate_block(
+    reader: &mut BitReader,
+    output: &mut Vec<u8>,
+    max_size: usize,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> Result<()> {
+    loop {
+        let symbol = literals.decode(reader)? as usize;
+        if symbol != 256 && output.len() >= max_size {
+            bail!("Données deflate plus longues qu'annoncé");
+        }
+        match symbol {
+            0..=255 => output.push(symbol as u8),
+            256 => return Ok(()),
+            _ => {
+                let index = symbol - 257;
+                if index >= LENGTH_BASE.len() {
+                    bail!("Longueur deflate invalide");
+                }
+                let length = LENGTH_BASE[index] as usize + reader.bits(LENGTH_EXTRA[index] as u32)? as usize;
+
+                let index = distances.decode(reader)? as usize;
+                if index >= DISTANCE_BASE.len() {
+                    bail!("Distance deflate invalide");
+                }
+                let distance = DISTANCE_BASE[index] as usize + reader.bits(DISTANCE_EXTRA[index] as u32)? as usize;
+                if distance > output.len() {
+                    bail!("Distance deflate hors de la fenêtre");
+                }
+                if output.len() + length > max_size {
+                    bail!("Données deflate plus longues qu'annoncé");
+                }
+
+                // Copie octet par octet : la source peut chevaucher la destination
+                let start = output.len() - distance;
+                for offset in 0..length {
+                    output.push(output[start + offset]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // PNG produits par zlib (tests/fixtures/png/generate.py) et pixels RGBA attendus
+    const FIXTURES: &[&str] = &[
+        "gray1", "gray2", "gray4", "gray8", "gray16", "rgb8", "rgb16", "palette1", "palette2", "palette4", "palette8",
+        "graya8", "graya16", "rgba8", "rgba16", "rgba8_stored", "rgba8_fixed", "rgba8_split_idat",
+    ];
+
+    fn fixture(name: &str) -> Vec<u8> {
+        let path = format!("{}/tests/fixtures/png/{}", env!("CARGO_MANIFEST_DIR"), name);
+        std::fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+    }
+
+    fn image(width: u32, height: u32, pixel: impl Fn(u32, u32) -> [u8; 4]) -> IconImage {
+        let mut rgba = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                rgba.extend_from_slice(&pixel(x, y));
+            }
+        }
+        IconImage { width, height, rgba }
+    }
+
+    #[test]
+    fn decodes_reference_images() {
+        for name in FIXTURES {
+            let decoded = decode(&fixture(&format!("{}.png", name))).unwrap_or_else(|e| panic!("{}: {}", name, e));
+            assert_eq!((decoded.width, decoded.height), (5, 6), "{}", name);
+            assert_eq!(decoded.rgba, fixture(&format!("{}.rgba", name)), "{}", name);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let images = [
+            // Aplat : longues répétitions (correspondances de 258 octets)
+            image(64, 64, |_, _| [0x20, 0x40, 0x80, 0xFF]),
+            // Dégradé et transparence
+            image(17, 9, |x, y| [(x * 15) as u8, (y * 28) as u8, (x * y) as u8, (255 - x * 10) as u8]),
+            // Bruit : presque uniquement des littéraux
+            image(33, 31, |x, y| {
+                let value = x.wrapping_mul(2654435761).wrapping_add(y * 40503).rotate_left(x % 7) as u8;
+                [value, value.wrapping_mul(3), value ^ 0x5A, value.wrapping_add(y as u8)]
+            }),
+            image(1, 1, |_, _| [1, 2, 3, 4]),
+        ];
+        for image in &images {
+            let decoded = decode(&encode(image)).unwrap();
+            assert_eq!((decoded.width, decoded.height), (image.width, image.height));
+            assert_eq!(decoded.rgba, image.rgba);
+        }
+    }
+
+    #[test]
+    fn encoded_chunks_have_valid_checksums() {
+        let png = encode(&image(8, 8, |x, y| [x as u8, y as u8, 0, 255]));
+        let mut position = SIGNATURE.len();
+        let mut kinds = Vec::new();
+        while position < png.len() {
+            let length = u32::from_be_bytes(png[position..position + 4].try_into().unwrap()) as usize;
+            let end = position + 8 + length;
+            let crc = u32::from_be_bytes(png[end..end + 4].try_into().unwrap());
+            assert_eq!(crc, crc32(&png[position + 4..end]));
+            kinds.push(png[position + 4..position + 8].to_vec());
+            position = end + 4;
+        }
+        assert_eq!(kinds, [b"IHDR".to_vec(), b"IDAT".to_vec(), b"IEND".to_vec()]);
+        // Valeur de référence de la spécification (PNG, annexe D)
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn inflated_size_is_capped_by_the_header() {
+        // En-tête 1x1 (5 octets attendus) mais 1 Mo de données compressées
+        let mut png = SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &[0, 0, 0, 1, 0, 0, 0, 1, 8, 6, 0, 0, 0]);
+        write_chunk(&mut png, b"IDAT", &zlib_compress(&vec![0; 1024 * 1024]));
+        write_chunk(&mut png, b"IEND", &[]);
+        assert!(decode(&png).unwrap_err().to_string().contains("plus longues"));
+
+        // Même limite pour les blocs stockés
+        let stored = fixture("rgba8_stored.png");
+        let compressed = &stored[stored.windows(4).position(|w| w == b"IDAT").unwrap() + 4..];
+        let length = 5 * 6 * 4 + 6;
+        assert!(zlib_decompress(compressed, length - 1).is_err());
+        assert_eq!(zlib_decompress(compressed, length).unwrap().len(), length);
+    }
+
+    #[test]
+    fn rejects_invalid_headers() {
+        let png = encode(&image(2, 2, |_, _| [0; 4]));
+        let ihdr = SIGNATURE.len() + 8;
+        let with_header = |offset: usize, value: u8| {
+            let mut png = png.clone();
+            png[ihdr + offset] = value;
+            decode(&png)
+        };
+        // RGB en 4 bits et palette en 16 bits n'existent pas
+        assert!(with_header(8, 4).is_err());
+        let mut palette16 = png.clone();
+        palette16[ihdr + 8] = 16;
+        palette16[ihdr + 9] = 3;
+        assert!(decode(&palette16).is_err());
+        // Type de couleur inconnu, entrelacement, dimensions hors limites
+        assert!(with_header(9, 5).is_err());
+        assert!(with_header(12, 1).is_err());
+        assert!(with_header(2, 0xFF).is_err());
+        assert!(decode(&png[..ihdr]).is_err());
+        assert!(decode(b"not a png").is_err());
+    }
+
+    #[test]
+    fn truncated_streams_fail_cleanly() {
+        let png = fixture("rgba8.png");
+        for length in 0..png.len() {
+            let _ = decode(&png[..length]);
+        }
+        let mut corrupted = png.clone();
+        let idat = png.windows(4).position(|w| w == b"IDAT").unwrap() + 6;
+        for byte in &mut corrupted[idat..idat + 8] {
+            *byte ^= 0xA5;
+        }
+        let _ = decode(&corrupted);
+    }
+}
@@ -0,0 +1,175 @@
+//! Recherche d'icônes dans les thèmes freedesktop (index.theme, héritage, hicolor)
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FALLBACK_THEME: &str = "hicolor";
+
+/// Fichier d'icône candidat et sa taille nominale
+#[derive(Debug, Clone)]
+pub struct ThemeIcon {
+    pub path: PathBuf,
+    pub size: u32,
+}
+
+/// Résoudre la valeur `Icon=` d'un fichier .desktop : chemin absolu ou nom d'icône du thème.
+/// Seuls les PNG sont retenus (pas de rendu SVG/XPM).
+pub fn lookup(icon: &str, size: u32) -> Option<PathBuf> {
+    let path = Path::new(icon);
+    if path.is_absolute() {
+        return Some(path.to_path_buf()).filter(|path| is_png_file(path));
+    }
+
+    let bases = base_dirs();
+    let mut visited = HashSet::new();
+    let mut themes = vec![current_theme().unwrap_or_else(|| FALLBACK_THEME.to_string())];
+
+    // Parcours du thème puis de ceux dont il hérite, hicolor en dernier recours
+    while let Some(theme) = themes.pop() {
+        if !visited.insert(theme.clone()) {
+            continue;
+        }
+        let candidates = theme_candidates(&bases, &theme, icon);
+        if let Some(best) = best_candidate(candidates, size) {
+            return Some(best.path);
+        }
+        let mut parents = theme_parents(&bases, &theme);
+        if parents.is_empty() && theme != FALLBACK_THEME {
+            parents.push(FALLBACK_THEME.to_string());
+        }
+        // Pile : le premier parent déclaré est exploré en premier
+        themes.extend(parents.into_iter().rev());
+    }
+
+    bases
+        .iter()
+        .chain(std::iter::once(&PathBuf::from("/usr/share/pixmaps")))
+        .map(|base| base.join(format!("{}.png", icon)))
+        .find(|path| path.is_file())
+}
+
+/// Plus petite icône couvrant la taille demandée, sinon la plus grande disponible
+fn best_candidate(candidates: Vec<ThemeIcon>, size: u32) -> Option<ThemeIcon> {
+    let larger = candidates
+        .iter()
+        .filter(|candidate| candidate.size >= size)
+        .min_by_key(|candidate| candidate.size)
+        .cloned();
+    larger.or_else(|| candidates.into_iter().max_by_key(|candidate| candidate.size))
+}
+
+/// Fichiers `<base>/<thème>/<répertoire>/<nom>.png` des répertoires déclarés dans index.theme
+fn theme_candidates(bases: &[PathBuf], theme: &str, icon: &str) -> Vec<ThemeIcon> {
+    let mut candidates = Vec::new();
+    for base in bases {
+        let root = base.join(theme);
+        let Ok(index) = fs::read_to_string(root.join("index.theme")) else {
+            continue;
+        };
+        for (directory, size) in theme_directories(&index) {
+            let path = root.join(&directory).join(format!("{}.png", icon));
+            if path.is_file() {
+                candidates.push(ThemeIcon { path, size });
+            }
+        }
+    }
+    candidates
+}
+
+/// Répertoires de l'index (clé Directories, et ScaledDirectories) avec leur taille en pixels
+fn theme_directories(index: &str) -> Vec<(String, u32)> {
+    let directories: Vec<String> = ["Directories", "ScaledDirectories"]
+        .iter()
+        .filter_map(|key| index_value(index, "Icon Theme", key))
+        .flat_map(|value| value.split(',').map(|dir| dir.trim().to_string()).collect::<Vec<_>>())
+        .filter(|dir| !dir.is_empty())
+        .collect();
+
+    directories
+        .into_iter()
+        .filter_map(|directory| {
+            let size: u32 = index_value(index, &directory, "Size")?.parse().ok()?;
+            let scale: u32 = index_value(index, &directory, "Scale")
+                .and_then(|scale| scale.parse().ok())
+                .unwrap_or(1);
+            Some((directory, size * scale))
+        })
+        .collect()
+}
+
+fn theme_parents(bases: &[PathBuf], theme: &str) -> Vec<String> {
+    bases
+        .iter()
+        .find_map(|base| fs::read_to_string(base.join(theme).join("index.theme")).ok())
+        .and_then(|index| index_value(&index, "Icon Theme", "Inherits"))
+        .map(|value| {
+            value
+                .split(',')
+                .map(|parent| parent.trim().to_string())
+                .filter(|parent| !parent.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Valeur d'une clé dans un groupe d'un fichier de type .ini
+fn index_value(content: &str, group: &str, key: &str) -> Option<String> {
+    let mut in_group = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            in_group = name == group;
+            continue;
+        }
+        if !in_group {
+            continue;
+        }
+        if let Some((name, value)) = line.split_once('=') {
+            if name.trim() == key {
+                return Some(value.trim().to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Thème configuré pour GTK (settings.ini), faute de mieux sans session de bureau
+fn current_theme() -> Option<String> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    ["gtk-4.0", "gtk-3.0"].iter().find_map(|version| {
+        let content = fs::read_to_string(config_home.join(version).join("settings.ini")).ok()?;
+        index_value(&content, "Settings", "gtk-icon-theme-name").filter(|theme| !theme.is_empty())
+    })
+}
+
+/// $HOME/.icons puis <XDG_DATA_DIRS>/icons, dans l'ordre de priorité de la spécification
+fn base_dirs() -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|home| home.join(".local/share")));
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+
+    let mut dirs: Vec<PathBuf> = home.iter().map(|home| home.join(".icons")).collect();
+    dirs.extend(data_home.map(|dir| dir.join("icons")));
+    dirs.extend(
+        data_dirs
+            .split(':')
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| PathBuf::from(dir).join("icons")),
+    );
+    dirs.push(PathBuf::from("/var/lib/flatpak/exports/share/icons"));
+
+    let mut seen = HashSet::new();
+    dirs.into_iter().filter(|dir| seen.insert(dir.clone())).collect()
+}
+
+fn is_png_file(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| ext.eq_ignore_ascii_case("png")) && path.is_file()
+}
@@ -2,16 +2,46 @@ pub mod process_scanner;
 pub mod models;
 pub mod metadata;
 pub mod realtime_monitor;
+pub mod native_messaging;
+pub mod foreground_watcher;
+pub mod process_events;
+pub mod process_query;
+pub mod system_snapshot;
+pub mod config;
+pub mod redaction;
+pub mod binfmt;
+pub mod app_identity;
+pub mod icons;
+#[cfg(target_os = "linux")]
+pub mod linux;
 
 pub use process_scanner::ProcessScanner;
-pub use metadata::{ProcessMetadataCollector, MediaControlCollector};
-pub use realtime_monitor::{RealtimeProcessMonitor, MonitorConfig, ProcessMonitorState, create_simple_monitor};
+pub use metadata::{
+    ProcessMetadataCollector, BrowserTitleParser, BrowserFamily, SiteCategories, SiteCategory,
+};
+#[cfg(windows)]
+pub use metadata::{MediaControlCollector, MediaWorker};
+pub use foreground_watcher::{ForegroundWatcher, ForegroundEvent, ForegroundEventKind};
+pub use process_events::{ProcessEvent, ProcessEventSource, ProcessEventBackend};
+pub use process_query::{ProcessQuery, QuerySubject, QueryValue};
+pub use system_snapshot::{SystemSnapshot, SnapshotCache};
+pub use redaction::{RedactionConfig, RedactionRule, RedactionAudit, Redactor};
+pub use app_identity::{AppIdentityResolver, IdentityHints, IdentityOverride};
+pub use icons::{IconExtractor, IconImage};
+pub use config::{TrackerConfig, TargetConfig, OutputConfig, ConfigFormat, ConfigWatcher};
+pub use realtime_monitor::{
+    RealtimeProcessMonitor, MonitorConfig, ProcessMonitorState, ProcessExitInfo, MonitorHealth, IoRates, create_simple_monitor,
+};
 
 // Réexporter SEULEMENT les types publics nécessaires
 pub use models::{
     MetadataOptions,
     ProcessInfo,
     ApplicationInfo,
+    ApplicationGrouping,
+    AppIdentity,
+    ProcessRole,
+    ProcessRoleSummary,
     ScanResult,
     ProcessMetadata,
     WindowInfo,
@@ -19,7 +49,22 @@ pub use models::{
     ThreadInfo,
     ModuleInfo,
     MediaSessionInfo,
+    MediaPlaybackStatus,
     HandleInfo,
+    NetworkConnection,
     MemoryInfo,
+    MemoryMap,
+    MemoryUsage,
+    MemoryRegion,
+    MemoryCategory,
     CpuInfo,
+    IoInfo,
+    ContainerInfo,
+    OwnerInfo,
+    ExecutableInfo,
+    VersionInfo,
+    ParsedTabTitle,
+    BrowserTabInfo,
+    ProcessEntry,
+    ProcessSelectionPolicy,
 };
\ No newline at end of file
@@ -0,0 +1,438 @@
+use crate::models::ContainerInfo;
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::path::Path;
+
+// Identifiants de conteneur : 64 caractères hexadécimaux (12 minimum pour les formes abrégées)
+const MIN_CONTAINER_ID_LENGTH: usize = 12;
+
+/// Cgroup, unité systemd, conteneur et namespaces d'un processus
+pub fn container_info(pid: u32) -> Result<ContainerInfo> {
+    let content = fs::read_to_string(format!("/proc/{}/cgroup", pid))
+        .map_err(|e| anyhow!("Impossible de lire /proc/{}/cgroup: {}", pid, e))?;
+    let mut info = parse_cgroup(&content);
+
+    // Namespaces : lisibles seulement pour nos propres processus (ou root)
+    info.pid_namespace = namespace_inode(pid, "pid");
+    info.mount_namespace = namespace_inode(pid, "mnt");
+
+    // Vu depuis l'intérieur d'un conteneur, le cgroup est souvent "/" : indices du système de fichiers
+    if info.runtime.is_none() {
+        let root = format!("/proc/{}/root", pid);
+        if Path::new(&root).join(".dockerenv").exists() {
+            info.runtime = Some("docker".to_string());
+        } else if Path::new(&root).join("run/.containerenv").exists() {
+            info.runtime = Some("podman".to_string());
+        }
+        if info.runtime.is_some() && info.container_id.is_none() {
+            info.container_id = mountinfo_container_id(pid);
+        }
+    }
+
+    Ok(info)
+}
+
+/// Analyser /proc/<pid>/cgroup : "0::/chemin" en v2, "N:contrôleurs:/chemin" en v1
+pub fn parse_cgroup(content: &str) -> ContainerInfo {
+    let mut unified = None;
+    let mut systemd = None;
+    let mut controllers = Vec::new();
+
+    for line in content.lines() {
+        let mut fields = line.splitn(3, ':');
+        let (Some(id), Some(names), Some(path)) = (fields.next(), fields.next(), fields.next()) else {
+            continue;
+        };
+        if id == "0" && names.is_empty() {
+            unified = Some(path.to_string());
+        } else if names == "name=systemd" {
+            systemd = Some(path.to_string());
+        } else {
+            controllers.push(path.to_string());
+        }
+    }
+
+    // v2 pur : une seule ligne "0::". En hiérarchie hybride, les contrôleurs v1 décrivent le conteneur
+    // (le pilote cgroupfs de Docker ne touche pas à la hiérarchie unifiée, restée sur containerd.service)
+    let is_v2 = systemd.is_none() && controllers.is_empty();
+    let cgroup_path = if is_v2 {
+        unified.clone().unwrap_or_else(|| "/".to_string())
+    } else {
+        systemd
+            .clone()
+            .filter(|path| path != "/")
+            .or_else(|| unified.clone().filter(|path| path != "/"))
+            .or_else(|| controllers.iter().find(|path| *path != "/").cloned())
+            .unwrap_or_else(|| "/".to_string())
+    };
+
+    let mut info = ContainerInfo {
+        cgroup_version: if is_v2 { 2 } else { 1 },
+        ..Default::default()
+    };
+
+    // Les contrôleurs v1 peuvent placer le conteneur ailleurs que name=systemd
+    let mut paths = vec![cgroup_path.as_str()];
+    if !is_v2 {
+        paths.extend(unified.as_deref());
+        paths.extend(controllers.iter().map(String::as_str));
+    }
+
+    for path in paths {
+        // Slice et unité : la dernière rencontrée est la plus proche du processus
+        let is_main_path = path == cgroup_path;
+        for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+            if segment.ends_with(".slice") {
+                if is_main_path {
+                    info.systemd_slice = Some(segment.to_string());
+                }
+                if let Some(pod) = kubernetes_pod(segment) {
+                    info.kubernetes_pod = Some(pod);
+                }
+            } else if is_main_path && (segment.ends_with(".service") || segment.ends_with(".scope")) {
+                info.systemd_unit = Some(segment.to_string());
+            } else if let Some(pod) = segment.strip_prefix("pod").filter(|_| path.starts_with("/kubepods/")) {
+                info.kubernetes_pod = Some(pod.to_string());
+            }
+
+            if info.container_id.is_none() {
+                if let Some((runtime, id)) = container_segment(segment) {
+                    info.runtime = Some(runtime.to_string());
+                    info.container_id = Some(id);
+                }
+            }
+        }
+        // Forme cgroupfs (sans systemd) : /docker/<id>, /lxc/<nom>, /kubepods/<qos>/pod<uid>/<id>
+        if info.container_id.is_none() {
+            if let Some((runtime, id)) = cgroupfs_container(path) {
+                info.runtime = Some(runtime.to_string());
+                info.container_id = Some(id);
+            }
+        }
+    }
+
+    info.cgroup_path = cgroup_path;
+    info
+}
+
+/// Unités de conteneur créées par systemd : "docker-<id>.scope", "libpod-<id>.scope",
+/// "cri-containerd-<id>.scope", "crio-<id>.scope"
+fn container_segment(segment: &str) -> Option<(&'static str, String)> {
+    let name = segment.strip_suffix(".scope").unwrap_or(segment);
+    let prefixes = [
+        ("docker-", "docker"),
+        ("libpod-conmon-", "podman"),
+        ("libpod-", "podman"),
+        ("cri-containerd-", "containerd"),
+        ("crio-conmon-", "cri-o"),
+        ("crio-", "cri-o"),
+    ];
+    prefixes.iter().find_map(|(prefix, runtime)| {
+        let id = name.strip_prefix(prefix)?;
+        is_container_id(id).then(|| (*runtime, id.to_string()))
+    })
+}
+
+fn cgroupfs_container(path: &str) -> Option<(&'static str, String)> {
+    let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+    let last = *segments.last()?;
+
+    match segments.first().copied()? {
+        "docker" if is_container_id(last) => Some(("docker", last.to_string())),
+        "libpod_parent" => container_segment(last),
+        "lxc" | "lxc.payload" => segments.get(1).map(|name| ("lxc", name.to_string())),
+        first if first.starts_with("lxc.payload.") => Some(("lxc", first["lxc.payload.".len()..].to_string())),
+        "kubepods" if is_container_id(last) => Some(("containerd", last.to_string())),
+        _ => None,
+    }
+}
+
+/// "kubepods-burstable-pod1234_5678.slice" -> "1234-5678"
+fn kubernetes_pod(segment: &str) -> Option<String> {
+    let name = segment.strip_suffix(".slice")?;
+    if !name.starts_with("kubepods") {
+        return None;
+    }
+    let pod = name.rsplit('-').next()?.strip_prefix("pod")?;
+    Some(pod.replace('_', "-"))
+}
+
+fn is_container_id(value: &str) -> bool {
+    value.len() >= MIN_CONTAINER_ID_LENGTH && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// "pid:[4026531836]" -> 4026531836
+fn namespace_inode(pid: u32, namespace: &str) -> Option<u64> {
+    let link = fs::read_link(format!("/proc/{}/ns/{}", pid, namespace)).ok()?;
+    let link = link.to_string_lossy();
+    link.split_once('[')?.1.strip_suffix(']')?.parse().ok()
+}
+
+/// Docker et Podman montent hostname/resolv.conf depuis un répertoire nommé par l'identifiant
+fn mountinfo_container_id(pid: u32) -> Option<String> {
+    let content = fs::read_to_string(format!("/proc/{}/mountinfo", pid)).ok()?;
+    content.lines().find_map(|line| {
+        let root = line.split_whitespace().nth(3)?;
+        let mut segments = root.split('/');
+        segments.find(|segment| *segment == "containers" || *segment == "overlay-containers")?;
+        segments.next().filter(|id| is_container_id(id)).map(str::to_string)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "4f3c2b1a0d9e8f7a6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a";
+
+    struct Expected {
+        version: u8,
+        path: &'static str,
+        unit: Option<&'static str>,
+        slice: Option<&'static str>,
+        runtime: Option<&'static str>,
+        container_id: Option<String>,
+        pod: Option<&'static str>,
+    }
+
+    fn v1_lines(path: &str) -> String {
+        let controllers = [
+            "12:pids", "11:memory", "10:cpu,cpuacct", "9:blkio", "8:devices", "7:freezer", "6:net_cls,net_prio",
+            "5:perf_event", "4:hugetlb", "3:cpuset", "2:rdma", "1:name=systemd",
+        ];
+        controllers.iter().map(|controller| format!("{}:{}\n", controller, path)).collect()
+    }
+
+    #[test]
+    fn real_cgroup_samples() {
+        let cases: Vec<(&str, String, Expected)> = vec![
+            (
+                "v2, session graphique",
+                "0::/user.slice/user-1000.slice/user@1000.service/app.slice/app-gnome-org.gnome.Terminal-4242.scope\n"
+                    .to_string(),
+                Expected {
+                    version: 2,
+                    path: "/user.slice/user-1000.slice/user@1000.service/app.slice/app-gnome-org.gnome.Terminal-4242.scope",
+                    unit: Some("app-gnome-org.gnome.Terminal-4242.scope"),
+                    slice: Some("app.slice"),
+                    runtime: None,
+                    container_id: None,
+                    pod: None,
+                },
+            ),
+            (
+                "v2, service système",
+                "0::/system.slice/sshd.service\n".to_string(),
+                Expected {
+                    version: 2,
+                    path: "/system.slice/sshd.service",
+                    unit: Some("sshd.service"),
+                    slice: Some("system.slice"),
+                    runtime: None,
+                    container_id: None,
+                    pod: None,
+                },
+            ),
+            (
+                "v2, docker (pilote systemd)",
+                format!("0::/system.slice/docker-{}.scope\n", ID),
+                Expected {
+                    version: 2,
+                    path: "",
+                    unit: None,
+                    slice: Some("system.slice"),
+                    runtime: Some("docker"),
+                    container_id: Some(ID.to_string()),
+                    pod: None,
+                },
+            ),
+            (
+                "v2, podman sans privilèges",
+                format!("0::/user.slice/user-1000.slice/user@1000.service/user.slice/libpod-{}.scope/container\n", ID),
+                Expected {
+                    version: 2,
+                    path: "",
+                    unit: None,
+                    slice: Some("user.slice"),
+                    runtime: Some("podman"),
+                    container_id: Some(ID.to_string()),
+                    pod: None,
+                },
+            ),
+            (
+                "v2, kubepods (containerd, pilote systemd)",
+                format!(
+                    "0::/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod0f1e2d3c_4b5a_6978_8796_a5b4c3d2e1f0.slice/cri-containerd-{}.scope\n",
+                    ID
+                ),
+                Expected {
+                    version: 2,
+                    path: "",
+                    unit: None,
+                    slice: Some("kubepods-burstable-pod0f1e2d3c_4b5a_6978_8796_a5b4c3d2e1f0.slice"),
+                    runtime: Some("containerd"),
+                    container_id: Some(ID.to_string()),
+                    pod: Some("0f1e2d3c-4b5a-6978-8796-a5b4c3d2e1f0"),
+                },
+            ),
+            (
+                "v2, kubepods (cri-o)",
+                format!("0::/kubepods.slice/kubepods-besteffort.slice/kubepods-besteffort-pod1a2b.slice/crio-{}.scope\n", ID),
+                Expected {
+                    version: 2,
+                    path: "",
+                    unit: None,
+                    slice: Some("kubepods-besteffort-pod1a2b.slice"),
+                    runtime: Some("cri-o"),
+                    container_id: Some(ID.to_string()),
+                    pod: Some("1a2b"),
+                },
+            ),
+            (
+                "v2, lxc",
+                "0::/lxc.payload.web01/system.slice/nginx.service\n".to_string(),
+                Expected {
+                    version: 2,
+                    path: "/lxc.payload.web01/system.slice/nginx.service",
+                    unit: Some("nginx.service"),
+                    slice: Some("system.slice"),
+                    runtime: Some("lxc"),
+                    container_id: Some("web01".to_string()),
+                    pod: None,
+                },
+            ),
+            (
+                "v2, vu de l'intérieur d'un conteneur",
+                "0::/\n".to_string(),
+                Expected {
+                    version: 2,
+                    path: "/",
+                    unit: None,
+                    slice: None,
+                    runtime: None,
+                    container_id: None,
+                    pod: None,
+                },
+            ),
+            (
+                "v1, docker (pilote cgroupfs)",
+                v1_lines(&format!("/docker/{}", ID)),
+                Expected {
+                    version: 1,
+                    path: "",
+                    unit: None,
+                    slice: None,
+                    runtime: Some("docker"),
+                    container_id: Some(ID.to_string()),
+                    pod: None,
+                },
+            ),
+            (
+                "v1, kubepods (pilote cgroupfs)",
+                v1_lines(&format!("/kubepods/burstable/pod0f1e2d3c-4b5a-6978-8796-a5b4c3d2e1f0/{}", ID)),
+                Expected {
+                    version: 1,
+                    path: "",
+                    unit: None,
+                    slice: None,
+                    runtime: Some("containerd"),
+                    container_id: Some(ID.to_string()),
+                    pod: Some("0f1e2d3c-4b5a-6978-8796-a5b4c3d2e1f0"),
+                },
+            ),
+            (
+                "v1, lxc",
+                v1_lines("/lxc/web01"),
+                Expected {
+                    version: 1,
+                    path: "/lxc/web01",
+                    unit: None,
+                    slice: None,
+                    runtime: Some("lxc"),
+                    container_id: Some("web01".to_string()),
+                    pod: None,
+                },
+            ),
+            (
+                "hybride, session utilisateur",
+                format!(
+                    "{}0::/user.slice/user-1000.slice/session-2.scope\n",
+                    v1_lines("/user.slice/user-1000.slice/session-2.scope").replace("7:freezer:/user.slice/user-1000.slice/session-2.scope", "7:freezer:/")
+                ),
+                Expected {
+                    version: 1,
+                    path: "/user.slice/user-1000.slice/session-2.scope",
+                    unit: Some("session-2.scope"),
+                    slice: Some("user-1000.slice"),
+                    runtime: None,
+                    container_id: None,
+                    pod: None,
+                },
+            ),
+            (
+                "hybride, docker (pilote cgroupfs, hiérarchie unifiée restée sur containerd)",
+                format!("{}0::/system.slice/containerd.service\n", v1_lines(&format!("/docker/{}", ID))),
+                Expected {
+                    version: 1,
+                    path: "",
+                    unit: None,
+                    slice: None,
+                    runtime: Some("docker"),
+                    container_id: Some(ID.to_string()),
+                    pod: None,
+                },
+            ),
+            (
+                "hybride, docker (pilote systemd)",
+                format!(
+                    "{}0::/system.slice/docker-{}.scope\n",
+                    v1_lines(&format!("/system.slice/docker-{}.scope", ID)),
+                    ID
+                ),
+                Expected {
+                    version: 1,
+                    path: "",
+                    unit: None,
+                    slice: Some("system.slice"),
+                    runtime: Some("docker"),
+                    container_id: Some(ID.to_string()),
+                    pod: None,
+                },
+            ),
+        ];
+
+        for (name, content, expected) in cases {
+            let info = parse_cgroup(&content);
+            assert_eq!(info.cgroup_version, expected.version, "{}", name);
+            // Chemin vide : seul le conteneur importe (le chemin contient l'identifiant)
+            if !expected.path.is_empty() {
+                assert_eq!(info.cgroup_path, expected.path, "{}", name);
+                assert_eq!(info.systemd_unit.as_deref(), expected.unit, "{}", name);
+            }
+            assert_eq!(info.systemd_slice.as_deref(), expected.slice, "{}", name);
+            assert_eq!(info.runtime.as_deref(), expected.runtime, "{}", name);
+            assert_eq!(info.container_id, expected.container_id, "{}", name);
+            assert_eq!(info.kubernetes_pod.as_deref(), expected.pod, "{}", name);
+        }
+    }
+
+    #[test]
+    fn short_ids_and_malformed_lines() {
+        // Identifiant trop court pour être un conteneur
+        let info = parse_cgroup("0::/system.slice/docker-1234.scope\n");
+        assert_eq!(info.container_id, None);
+        assert_eq!(info.systemd_unit.as_deref(), Some("docker-1234.scope"));
+
+        let info = parse_cgroup("garbage\n\n0::/system.slice/cron.service\n");
+        assert_eq!(info.cgroup_version, 2);
+        assert_eq!(info.systemd_unit.as_deref(), Some("cron.service"));
+        assert_eq!(parse_cgroup("").cgroup_path, "/");
+    }
+
+    #[test]
+    fn own_process() {
+        let info = container_info(std::process::id()).unwrap();
+        assert!(info.cgroup_path.starts_with('/'));
+        assert!(info.pid_namespace.is_some());
+    }
+}
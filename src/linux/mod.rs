@@ -0,0 +1,3 @@
+pub mod cgroup;
+pub mod procfs;
+pub mod x11;
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Champs utiles de /proc/<pid>/stat
+#[derive(Debug, Clone)]
+pub struct ProcStat {
+    pub pid: u32,
+    pub ppid: u32,
+    pub comm: String,
+    pub state: char,
+    pub utime_ticks: u64,
+    pub stime_ticks: u64,
+    pub priority: i32,
+    pub nice: i32,
+    pub num_threads: u32,
+    pub start_time_ticks: u64,
+}
+
+// USER_HZ : fixé à 100 par l'ABI Linux sur toutes les architectures courantes
+const CLOCK_TICKS_PER_SECOND: u64 = 100;
+
+pub fn list_pids() -> Vec<u32> {
+    let mut pids = Vec::new();
+    if let Ok(entries) = fs::read_dir("/proc") {
+        for entry in entries.flatten() {
+            if let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) {
+                pids.push(pid);
+            }
+        }
+    }
+    pids.sort_unstable();
+    pids
+}
+
+pub fn read_stat(pid: u32) -> Option<ProcStat> {
+    let content = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    parse_stat(&content)
+}
+
+/// "1234 (nom avec espaces) S 1 ..." : le nom peut contenir des parenthèses, on coupe sur la dernière
+pub fn parse_stat(content: &str) -> Option<ProcStat> {
+    let open = content.find('(')?;
+    let close = content.rfind(')')?;
+    let pid = content[..open].trim().parse().ok()?;
+    let comm = content[open + 1..close].to_string();
+
+    // Champs à partir de "state" (champ 3 dans proc(5))
+    let fields: Vec<&str> = content[close + 1..].split_whitespace().collect();
+    Some(ProcStat {
+        pid,
+        ppid: fields.get(1)?.parse().ok()?,
+        comm,
+        state: fields.first()?.chars().next()?,
+        utime_ticks: fields.get(11)?.parse().ok()?,
+        stime_ticks: fields.get(12)?.parse().ok()?,
+        priority: fields.get(15)?.parse().ok()?,
+        nice: fields.get(16)?.parse().ok()?,
+        num_threads: fields.get(17)?.parse().ok()?,
+        start_time_ticks: fields.get(19)?.parse().ok()?,
+    })
+}
+
+pub fn read_task_stat(pid: u32, tid: u32) -> Option<ProcStat> {
+    let content = fs::read_to_string(format!("/proc/{}/task/{}/stat", pid, tid)).ok()?;
+    parse_stat(&content)
+}
+
+/// Ticks d'horloge -> unités de 100 ns (même unité que les FILETIME Windows)
+pub fn ticks_to_100ns(ticks: u64) -> u64 {
+    ticks * (10_000_000 / CLOCK_TICKS_PER_SECOND)
+}
+
+pub fn ticks_to_millis(ticks: u64) -> u64 {
+    ticks * 1000 / CLOCK_TICKS_PER_SECOND
+}
+
+/// Heure de démarrage du système (ligne "btime" de /proc/stat) en millisecondes depuis l'epoch
+pub fn boot_time_millis() -> Option<u64> {
+    let content = fs::read_to_string("/proc/stat").ok()?;
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("btime "))
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(|seconds| seconds * 1000)
+}
+
+/// Nom de l'exécutable (basename de /proc/<pid>/exe, sinon comm qui est tronqué à 15 caractères)
+pub fn process_name(stat: &ProcStat) -> String {
+    exe_path(stat.pid)
+        .as_deref()
+        .and_then(|path| Path::new(path).file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| stat.comm.clone())
+}
+
+pub fn exe_path(pid: u32) -> Option<String> {
+    let path = fs::read_link(format!("/proc/{}/exe", pid)).ok()?;
+    let path = path.to_string_lossy();
+    // Le noyau ajoute " (deleted)" quand le binaire a été remplacé sur le disque
+    Some(path.trim_end_matches(" (deleted)").to_string())
+}
+
+/// Lire /proc/<pid>/status sous forme clé -> valeur
+pub fn read_status(pid: u32) -> Option<HashMap<String, String>> {
+    let content = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    Some(
+        content
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect(),
+    )
+}
+
+/// Lire /proc/<pid>/io (rchar, wchar, syscr, syscw, read_bytes, write_bytes...).
+/// Réservé au propriétaire du processus (ou root)
+pub fn read_io(pid: u32) -> Option<HashMap<String, u64>> {
+    let content = fs::read_to_string(format!("/proc/{}/io", pid)).ok()?;
+    Some(
+        content
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .filter_map(|(key, value)| Some((key.trim().to_string(), value.trim().parse().ok()?)))
+            .collect(),
+    )
+}
+
+/// "1234 kB" -> 1263616
+pub fn parse_kb(value: &str) -> Option<u64> {
+    let number = value.split_whitespace().next()?.parse::<u64>().ok()?;
+    Some(number * 1024)
+}
+
+pub fn thread_ids(pid: u32) -> Vec<u32> {
+    let mut tids = Vec::new();
+    if let Ok(entries) = fs::read_dir(format!("/proc/{}/task", pid)) {
+        for entry in entries.flatten() {
+            if let Some(tid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) {
+                tids.push(tid);
+            }
+        }
+    }
+    tids.sort_unstable();
+    tids
+}
+
+/// Variables d'environnement initiales du processus (/proc/<pid>/environ, "NOM=valeur\0...")
+pub fn read_environ(pid: u32) -> std::io::Result<Vec<(String, String)>> {
+    let content = fs::read(format!("/proc/{}/environ", pid))?;
+    Ok(split_nul(&content)
+        .filter_map(|entry| {
+            let (name, value) = entry.split_once('=')?;
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect())
+}
+
+/// Découper un contenu séparé par des octets nuls (environ, cmdline)
+pub fn split_nul(content: &[u8]) -> impl Iterator<Item = String> + '_ {
+    content
+        .split(|byte| *byte == 0)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| String::from_utf8_lossy(entry).to_string())
+}
+
+/// Arguments du processus (/proc/<pid>/cmdline), vide pour les threads noyau
+pub fn read_cmdline(pid: u32) -> Option<Vec<String>> {
+    let content = fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    let args: Vec<String> = split_nul(&content).collect();
+
+    // Les processus qui réécrivent leur titre (Chromium, setproctitle) n'ont plus qu'une chaîne
+    // où les arguments sont séparés par des espaces
+    if args.len() == 1 && content.iter().filter(|byte| **byte == 0).count() <= 1 && args[0].contains(' ') {
+        return Some(args[0].split_whitespace().map(|arg| arg.to_string()).collect());
+    }
+    Some(args)
+}
+
+pub fn cwd(pid: u32) -> Option<String> {
+    let path = fs::read_link(format!("/proc/{}/cwd", pid)).ok()?;
+    Some(path.to_string_lossy().trim_end_matches(" (deleted)").to_string())
+}
@@ -0,0 +1,344 @@
+use crate::icons::IconImage;
+use crate::models::{WindowInfo, WindowRect};
+use anyhow::Result;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use sup_common::debug_println;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt, MapState, Window};
+use x11rb::rust_connection::RustConnection;
+
+x11rb::atom_manager! {
+    pub Atoms: AtomsCookie {
+        _NET_CLIENT_LIST,
+        _NET_ACTIVE_WINDOW,
+        _NET_WM_PID,
+        _NET_WM_NAME,
+        _NET_WM_ICON,
+        UTF8_STRING,
+    }
+}
+
+/// Énumération des fenêtres via les propriétés EWMH du gestionnaire de fenêtres
+pub struct X11WindowBackend {
+    conn: RustConnection,
+    root: Window,
+    atoms: Atoms,
+}
+
+// Délai entre deux tentatives de connexion (DISPLAY pas encore défini, serveur X redémarré)
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+struct SharedBackend {
+    backend: Option<Arc<X11WindowBackend>>,
+    last_attempt: Option<Instant>,
+}
+
+static SHARED_BACKEND: OnceLock<Mutex<SharedBackend>> = OnceLock::new();
+
+impl X11WindowBackend {
+    /// Se connecter au serveur X désigné par $DISPLAY
+    pub fn connect() -> Result<Self> {
+        let (conn, screen_num) = x11rb::connect(None)?;
+        let root = conn.setup().roots[screen_num].root;
+        let atoms = Atoms::new(&conn)?.reply()?;
+        Ok(Self { conn, root, atoms })
+    }
+
+    /// Connexion partagée par le processus (None hors session X11).
+    /// Une connexion rompue est remplacée, un échec est retenté après `RECONNECT_INTERVAL`.
+    pub fn shared() -> Option<Arc<Self>> {
+        let mut shared = SHARED_BACKEND
+            .get_or_init(|| Mutex::new(SharedBackend::default()))
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(backend) = &shared.backend {
+            if backend.is_alive() {
+                return Some(Arc::clone(backend));
+            }
+            debug_println!("⚠️ Connexion X11 perdue, reconnexion");
+            shared.backend = None;
+            shared.last_attempt = None;
+        }
+
+        if shared.last_attempt.is_some_and(|attempt| attempt.elapsed() < RECONNECT_INTERVAL) {
+            return None;
+        }
+        shared.last_attempt = Some(Instant::now());
+        match Self::connect() {
+            Ok(backend) => {
+                let backend = Arc::new(backend);
+                shared.backend = Some(Arc::clone(&backend));
+                Some(backend)
+            }
+            Err(e) => {
+                debug_println!("Connexion X11 impossible: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Le serveur ferme la socket quand il s'arrête : la lecture non bloquante échoue alors.
+    /// La connexion partagée ne sélectionne aucun événement, il n'y a rien à perdre à les vider.
+    fn is_alive(&self) -> bool {
+        loop {
+            match self.conn.poll_for_event() {
+                Ok(Some(_)) => continue,
+                Ok(None) => return true,
+                Err(_) => return false,
+            }
+        }
+    }
+
+    pub fn connection(&self) -> &RustConnection {
+        &self.conn
+    }
+
+    pub fn root(&self) -> Window {
+        self.root
+    }
+
+    pub fn atoms(&self) -> &Atoms {
+        &self.atoms
+    }
+
+    /// Fenêtres gérées (_NET_CLIENT_LIST)
+    pub fn client_windows(&self) -> Result<Vec<Window>> {
+        let reply = self
+            .conn
+            .get_property(false, self.root, self.atoms._NET_CLIENT_LIST, AtomEnum::WINDOW, 0, u32::MAX)?
+            .reply()?;
+        Ok(reply.value32().map(|values| values.collect()).unwrap_or_default())
+    }
+
+    pub fn list_windows(&self) -> Result<Vec<WindowInfo>> {
+        let mut windows = Vec::new();
+        for window in self.client_windows()? {
+            // Une fenêtre peut disparaître entre la liste et la requête : on l'ignore
+            if let Ok(info) = self.window_info(window) {
+                windows.push(info);
+            }
+        }
+        Ok(windows)
+    }
+
+    pub fn windows_for_process(&self, pid: u32) -> Result<Vec<WindowInfo>> {
+        Ok(self
+            .list_windows()?
+            .into_iter()
+            .filter(|window| window.process_id == pid)
+            .collect())
+    }
+
+    /// Fenêtre active (_NET_ACTIVE_WINDOW)
+    pub fn active_window(&self) -> Result<Option<Window>> {
+        let reply = self
+            .conn
+            .get_property(false, self.root, self.atoms._NET_ACTIVE_WINDOW, AtomEnum::WINDOW, 0, 1)?
+            .reply()?;
+        Ok(reply.value32().and_then(|mut values| values.next()).filter(|window| *window != 0))
+    }
+
+    pub fn window_pid(&self, window: Window) -> Result<u32> {
+        let reply = self
+            .conn
+            .get_property(false, window, self.atoms._NET_WM_PID, AtomEnum::CARDINAL, 0, 1)?
+            .reply()?;
+        Ok(reply.value32().and_then(|mut values| values.next()).unwrap_or(0))
+    }
+
+    /// Titre de la fenêtre (_NET_WM_NAME en UTF-8, sinon WM_NAME)
+    pub fn window_title(&self, window: Window) -> Result<String> {
+        let reply = self
+            .conn
+            .get_property(false, window, self.atoms._NET_WM_NAME, self.atoms.UTF8_STRING, 0, u32::MAX)?
+            .reply()?;
+        if !reply.value.is_empty() {
+            return Ok(String::from_utf8_lossy(&reply.value).to_string());
+        }
+
+        let reply = self
+            .conn
+            .get_property(false, window, AtomEnum::WM_NAME, AtomEnum::STRING, 0, u32::MAX)?
+            .reply()?;
+        Ok(String::from_utf8_lossy(&reply.value).to_string())
+    }
+
+    pub fn window_info(&self, window: Window) -> Result<WindowInfo> {
+        let attributes = self.conn.get_window_attributes(window)?.reply()?;
+        let geometry = self.conn.get_geometry(window)?.reply()?;
+        let position = self.conn.translate_coordinates(window, self.root, 0, 0)?.reply()?;
+
+        Ok(WindowInfo {
+            hwnd: window as u64,
+            class_name: self.window_class(window)?,
+            window_title: self.window_title(window)?,
+            process_id: self.window_pid(window)?,
+            thread_id: 0, // Pas d'équivalent sous X11
+            is_visible: attributes.map_state == MapState::VIEWABLE,
+            window_rect: Some(WindowRect {
+                left: position.dst_x as i32,
+                top: position.dst_y as i32,
+                right: position.dst_x as i32 + geometry.width as i32,
+                bottom: position.dst_y as i32 + geometry.height as i32,
+            }),
+            browser_tab: None,
+        })
+    }
+
+    /// Icônes publiées par la fenêtre (_NET_WM_ICON : largeur, hauteur puis pixels ARGB, répétés)
+    pub fn window_icons(&self, window: Window) -> Result<Vec<IconImage>> {
+        let reply = self
+            .conn
+            .get_property(false, window, self.atoms._NET_WM_ICON, AtomEnum::CARDINAL, 0, u32::MAX)?
+            .reply()?;
+        let values: Vec<u32> = reply.value32().map(|values| values.collect()).unwrap_or_default();
+
+        let mut icons = Vec::new();
+        let mut position = 0;
+        while position + 2 <= values.len() {
+            let (width, height) = (values[position], values[position + 1]);
+            let count = width as usize * height as usize;
+            let Some(pixels) = values.get(position + 2..position + 2 + count) else {
+                break;
+            };
+            if count > 0 {
+                let rgba = pixels
+                    .iter()
+                    .flat_map(|argb| {
+                        let [a, r, g, b] = argb.to_be_bytes();
+                        [r, g, b, a]
+                    })
+                    .collect();
+                icons.push(IconImage { width, height, rgba });
+            }
+            position += 2 + count;
+        }
+        Ok(icons)
+    }
+
+    /// Classe de WM_CLASS ("instance\0Classe\0") : on garde la classe
+    fn window_class(&self, window: Window) -> Result<String> {
+        let reply = self
+            .conn
+            .get_property(false, window, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, u32::MAX)?
+            .reply()?;
+        let mut parts = reply.value.split(|&b| b == 0).filter(|part| !part.is_empty());
+        let instance = parts.next();
+        let class = parts.next().or(instance).unwrap_or_default();
+        Ok(String::from_utf8_lossy(class).to_string())
+    }
+}
+
+// Tests à lancer sous Xvfb (`xvfb-run cargo test`) : ignorés sans serveur X
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use x11rb::protocol::xproto::{CreateWindowAux, PropMode, WindowClass};
+    use x11rb::wrapper::ConnectionExt as _;
+
+    fn backend() -> Option<X11WindowBackend> {
+        std::env::var_os("DISPLAY")?;
+        X11WindowBackend::connect().ok()
+    }
+
+    /// Fenêtre de test déclarée dans _NET_CLIENT_LIST comme le ferait un gestionnaire de fenêtres
+    fn create_window(backend: &X11WindowBackend, title: &str) -> Window {
+        let conn = backend.connection();
+        let atoms = backend.atoms();
+        let window = conn.generate_id().unwrap();
+        conn.create_window(
+            x11rb::COPY_DEPTH_FROM_PARENT,
+            window,
+            backend.root(),
+            10,
+            20,
+            300,
+            200,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            x11rb::COPY_FROM_PARENT,
+            &CreateWindowAux::new(),
+        )
+        .unwrap();
+        conn.change_property8(PropMode::REPLACE, window, atoms._NET_WM_NAME, atoms.UTF8_STRING, title.as_bytes())
+            .unwrap();
+        conn.change_property8(PropMode::REPLACE, window, AtomEnum::WM_CLASS, AtomEnum::STRING, b"test\0TestClass\0")
+            .unwrap();
+        conn.change_property32(PropMode::REPLACE, window, atoms._NET_WM_PID, AtomEnum::CARDINAL, &[std::process::id()])
+            .unwrap();
+        // Deux icônes : 2x1 puis 1x1
+        conn.change_property32(
+            PropMode::REPLACE,
+            window,
+            atoms._NET_WM_ICON,
+            AtomEnum::CARDINAL,
+            &[2, 1, 0xFF102030, 0x80405060, 1, 1, 0x00000000],
+        )
+        .unwrap();
+        conn.change_property32(PropMode::APPEND, backend.root(), atoms._NET_CLIENT_LIST, AtomEnum::WINDOW, &[window])
+            .unwrap();
+        conn.sync().unwrap();
+        window
+    }
+
+    #[test]
+    fn reads_ewmh_properties() {
+        let Some(backend) = backend() else {
+            return;
+        };
+        let window = create_window(&backend, "Fenêtre de test — ünïcödé");
+
+        let info = backend.window_info(window).unwrap();
+        assert_eq!(info.window_title, "Fenêtre de test — ünïcödé");
+        assert_eq!(info.class_name, "TestClass");
+        assert_eq!(info.process_id, std::process::id());
+
+        let windows = backend.windows_for_process(std::process::id()).unwrap();
+        assert!(windows.iter().any(|info| info.hwnd == window as u64));
+
+        let icons = backend.window_icons(window).unwrap();
+        assert_eq!(icons.len(), 2);
+        assert_eq!((icons[0].width, icons[0].height), (2, 1));
+        assert_eq!(&icons[0].rgba[..8], &[0x10, 0x20, 0x30, 0xFF, 0x40, 0x50, 0x60, 0x80]);
+
+        backend.connection().destroy_window(window).unwrap();
+        backend.connection().sync().unwrap();
+        // Fenêtre détruite mais encore listée : ignorée
+        assert!(!backend.list_windows().unwrap().iter().any(|info| info.hwnd == window as u64));
+    }
+
+    #[test]
+    fn active_window() {
+        let Some(backend) = backend() else {
+            return;
+        };
+        let window = create_window(&backend, "Active");
+        backend
+            .connection()
+            .change_property32(
+                PropMode::REPLACE,
+                backend.root(),
+                backend.atoms()._NET_ACTIVE_WINDOW,
+                AtomEnum::WINDOW,
+                &[window],
+            )
+            .unwrap();
+        backend.connection().sync().unwrap();
+
+        assert_eq!(backend.active_window().unwrap(), Some(window));
+        assert_eq!(backend.window_pid(window).unwrap(), std::process::id());
+    }
+
+    #[test]
+    fn shared_backend_is_reused() {
+        if backend().is_none() {
+            return;
+        }
+        let first = X11WindowBackend::shared().unwrap();
+        let second = X11WindowBackend::shared().unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+}
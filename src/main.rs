@@ -1,24 +1,172 @@
-use sup_mtracker::ProcessScanner;
+use sup_mtracker::{
+    BrowserTitleParser, ConfigWatcher, OutputConfig, ProcessQuery, ProcessScanner, RealtimeProcessMonitor, Redactor,
+    TrackerConfig,
+};
+use std::sync::Arc;
 use std::fs;
 use std::env;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Cible du mode surveillance : moniteur démarré et dernier état publié
+struct WatchedTarget {
+    name: String,
+    monitor: RealtimeProcessMonitor,
+    outputs: Vec<OutputConfig>,
+    redactor: Option<Arc<Redactor>>,
+    last_published: Option<Instant>,
+}
+
+async fn start_monitors(config: &TrackerConfig) -> anyhow::Result<Vec<WatchedTarget>> {
+    let redactor = config.redactor()?;
+    let mut targets = Vec::new();
+    for target in &config.targets {
+        // Échec sur une cible : celles déjà démarrées sont arrêtées, l'ensemble est refusé
+        let started = match target.monitor_config(redactor.clone()) {
+            Ok(monitor_config) => {
+                let monitor = RealtimeProcessMonitor::new(monitor_config);
+                monitor.start().await.map(|_| monitor)
+            }
+            Err(e) => Err(e),
+        };
+        let monitor = match started {
+            Ok(monitor) => monitor,
+            Err(e) => {
+                stop_monitors(targets).await;
+                return Err(anyhow::anyhow!("cible '{}': {}", target.name, e));
+            }
+        };
+        println!("▶️ Cible '{}' surveillée", target.name);
+        targets.push(WatchedTarget {
+            name: target.name.clone(),
+            monitor,
+            outputs: config.effective_outputs(),
+            redactor: redactor.clone(),
+            last_published: None,
+        });
+    }
+    Ok(targets)
+}
+
+async fn stop_monitors(targets: Vec<WatchedTarget>) {
+    for target in targets {
+        target.monitor.stop().await;
+    }
+}
+
+/// Publier l'état des cibles qui ont changé depuis la dernière publication
+fn publish_changes(targets: &mut [WatchedTarget]) {
+    for target in targets {
+        let state = target.monitor.get_state();
+        if state.last_update.is_none() || state.last_update == target.last_published {
+            continue;
+        }
+        target.last_published = state.last_update;
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        // L'état est déjà masqué par le moniteur : seul le bilan du masquage est ajouté
+        let mut line = serde_json::json!({
+            "target": target.name,
+            "timestamp": timestamp,
+            "state": state,
+        });
+        if let Some(redactor) = &target.redactor {
+            line["redaction"] = serde_json::json!(redactor.audit());
+        }
+        let line = line.to_string();
+
+        for output in &target.outputs {
+            if let Err(e) = output.write_line(&line) {
+                eprintln!("❌ Sortie {:?} indisponible: {}", output, e);
+            }
+        }
+    }
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+
+    // --redact <config.toml> : masquer les sorties du scan, des requêtes et des métadonnées
+    // avec la section [redaction] du fichier de surveillance (règles par défaut si absente)
+    let mut redactor = None;
+    if let Some(index) = args.iter().position(|arg| arg == "--redact") {
+        let Some(path) = args.get(index + 1).cloned() else {
+            println!("Usage: {} --redact <config.toml|config.json> [commande]", args[0]);
+            return Ok(());
+        };
+        args.drain(index..=index + 1);
+        let config = TrackerConfig::load(std::path::Path::new(&path))?;
+        redactor = Some(Arc::new(Redactor::new(config.redaction.unwrap_or_default())?));
+    }
     
     if args.len() > 1 {
-        let scanner = ProcessScanner::new();
+        let scanner = ProcessScanner::new().with_redactor(redactor.clone());
         
-        // Mode spécial : trouver un processus par nom d'exécutable
-        if args.len() > 2 && args[2] == "find-by-name" {
+        // Mode spécial : rechercher des processus par requête (ex: query "name~\"chrome\" and mem>500MB")
+        if args[1] == "query" {
+            let Some(expression) = args.get(2) else {
+                println!("Usage: {} query \"<requête>\"", args[0]);
+                return Ok(());
+            };
+            let query = ProcessQuery::parse(expression)?;
+            println!("🔍 Requête: {}", query);
+
+            let processes = scanner.find_processes(&query)?;
+            for process in &processes {
+                match &process.window_title {
+                    Some(title) => println!("  {} {} - {}", process.pid, process.name, title),
+                    None => println!("  {} {}", process.pid, process.name),
+                }
+            }
+            println!("✅ {} processus trouvé(s)", processes.len());
+        } else if args[1] == "watch" {
+            // Mode surveillance continue : cibles et sorties décrites dans un fichier TOML/JSON, rechargé à chaud
+            let Some(path) = args.get(2) else {
+                println!("Usage: {} watch <config.toml|config.json>", args[0]);
+                return Ok(());
+            };
+            let mut watcher = ConfigWatcher::new(path)?;
+            println!("👀 Surveillance selon {}", watcher.path().display());
+            let mut targets = start_monitors(watcher.config()).await?;
+
+            loop {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+
+                match watcher.reload_if_changed() {
+                    Ok(Some(config)) => {
+                        let config = config.clone();
+                        // Les anciennes cibles tournent jusqu'au démarrage complet des nouvelles
+                        match start_monitors(&config).await {
+                            Ok(new_targets) => {
+                                stop_monitors(std::mem::replace(&mut targets, new_targets)).await;
+                                println!("🔄 Configuration rechargée");
+                            }
+                            Err(e) => eprintln!("❌ Configuration non appliquée, cibles précédentes conservées: {}", e),
+                        }
+                    }
+                    Ok(None) => {}
+                    // Configuration invalide : les moniteurs en cours sont conservés
+                    Err(e) => eprintln!("❌ Configuration ignorée: {}", e),
+                }
+
+                publish_changes(&mut targets);
+            }
+        } else if args.len() > 2 && args[2] == "find-by-name" {
             let executable_name = &args[1];
             println!("🔍 Recherche du processus: {}", executable_name);
             
-            if let Some(found_pid) = scanner.find_pid_by_executable_name(executable_name)? {
-                println!("✅ Processus trouvé ! PID: {}", found_pid);
+            let instances = scanner.find_pids(executable_name)?;
+            if !instances.is_empty() {
+                println!("✅ {} instance(s) trouvée(s) !", instances.len());
+                for instance in &instances {
+                    println!("  PID: {} (parent: {}, démarré: {:?})", instance.pid, instance.parent_pid, instance.start_time);
+                }
                 
                 // Récupérer les métadonnées
-                if let Some(metadata) = scanner.monitor_process_by_name(executable_name, None)? {
+                if let Some(metadata) = scanner.monitor_process_by_name(executable_name, None).await? {
                     println!("📊 Métadonnées:");
                     println!("  PID: {}", metadata.pid);
                     println!("  Nom: {}", metadata.name);
@@ -50,12 +198,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     if let Some(rect) = &active_tab.window_rect {
                         println!("  📐 Position: ({}, {}) - ({}, {})", rect.left, rect.top, rect.right, rect.bottom);
                     }
+                    if let Some(parsed) = BrowserTitleParser::new().parse_window(&active_tab) {
+                        println!("  📄 Page: {}", parsed.page_title);
+                        if let Some(site) = &parsed.site_name {
+                            println!("  🌐 Site: {}", site);
+                        }
+                        if let Some(category) = &parsed.category {
+                            println!("  🏷️ Catégorie: {}", category);
+                        }
+                    }
                 } else {
                     println!("❌ Aucun onglet actif détecté");
                 }
             } else {
                 println!("🔍 Récupération des métadonnées pour PID: {}", pid);
-                let metadata = scanner.get_process_metadata(pid, None)?;
+                let metadata = scanner.get_process_metadata(pid, None).await?;
             
                 // Convertir en JSON
                 let json_output = serde_json::to_string_pretty(&metadata)?;
@@ -87,8 +244,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         // Mode scan des applications (comme avant)
         println!("🔍 Démarrage du scan des applications...");
         
-        let scanner = ProcessScanner::new();
-        let result = scanner.scan_applications()?;
+        let scanner = ProcessScanner::new().with_redactor(redactor);
+        let result = scanner.scan_applications().await?;
         
         // Convertir en JSON
         let json_output = serde_json::to_string_pretty(&result)?;
@@ -103,8 +260,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         
         // Afficher un résumé
         for app in &result.applications {
+            let display_name = app
+                .identity
+                .as_ref()
+                .map(|identity| identity.display_name.as_str())
+                .unwrap_or(&app.main_process.name);
             println!(
-                "  📱 {} (PID: {}) - {} processus",
+                "  📱 {} [{}] (PID: {}) - {} processus",
+                display_name,
                 app.main_process.name,
                 app.main_process.pid,
                 app.total_processes
@@ -112,10 +275,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             if let Some(title) = &app.main_process.window_title {
                 println!("     Titre: {}", title);
             }
+            if !app.roles.is_empty() {
+                let roles: Vec<String> = app
+                    .roles
+                    .iter()
+                    .map(|summary| format!("{:?} x{}", summary.role, summary.process_count))
+                    .collect();
+                println!("     Rôles: {}", roles.join(", "));
+            }
         }
         
         println!("\n💡 Pour récupérer les métadonnées détaillées d'un processus:");
         println!("   {} <PID>", args[0]);
+        println!("💡 Pour rechercher des processus par requête:");
+        println!("   {} query \"name~\\\"chrome\\\" and mem>500MB\"", args[0]);
+        println!("💡 Pour masquer les données personnelles des résultats (section [redaction]):");
+        println!("   {} --redact config.toml", args[0]);
+        println!("💡 Pour surveiller les cibles d'un fichier de configuration (rechargé à chaud):");
+        println!("   {} watch config.toml", args[0]);
     }
     
     Ok(())
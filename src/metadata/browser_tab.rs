@@ -0,0 +1,313 @@
+use crate::models::{ParsedTabTitle, WindowInfo};
+use serde::{Deserialize, Serialize};
+
+/// Famille de navigateur déduite de la classe de fenêtre
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrowserFamily {
+    Firefox,
+    Chromium,
+    EdgeUwp,
+}
+
+impl BrowserFamily {
+    /// Déduire la famille depuis la classe de fenêtre (mêmes classes que la détection d'onglet)
+    pub fn from_class_name(class_name: &str) -> Option<Self> {
+        match class_name {
+            "MozillaWindowClass" | "MozillaDropShadowWindowClass" => Some(Self::Firefox),
+            "Chrome_WidgetWin_1" | "Chrome_WidgetWin_0" => Some(Self::Chromium),
+            "EdgeUiInputTopWndClass" | "ApplicationFrameWindow" => Some(Self::EdgeUwp),
+            // Classes WM_CLASS sous X11
+            "firefox" | "Firefox" | "firefox-esr" | "Firefox-esr" => Some(Self::Firefox),
+            "Google-chrome" | "Chromium" | "Chromium-browser" | "Brave-browser" | "Microsoft-edge"
+            | "Vivaldi-stable" | "Opera" => Some(Self::Chromium),
+            _ => None,
+        }
+    }
+
+    /// Suffixes ajoutés par le navigateur au titre de la page (du plus spécifique au plus générique)
+    fn title_suffixes(&self) -> &'static [&'static str] {
+        match self {
+            Self::Firefox => &[
+                "Mozilla Firefox Private Browsing",
+                "Navigation privée de Mozilla Firefox",
+                "Firefox Developer Edition",
+                "Firefox Nightly",
+                "Mozilla Firefox",
+            ],
+            Self::Chromium => &[
+                "Google Chrome",
+                "Chromium",
+                "Microsoft\u{200b} Edge",
+                "Microsoft Edge",
+                "Brave",
+                "Opera",
+                "Vivaldi",
+            ],
+            Self::EdgeUwp => &["Microsoft\u{200b} Edge", "Microsoft Edge"],
+        }
+    }
+}
+
+/// Catégorie de site configurable (ex: "work" -> ["github", "jira"])
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SiteCategory {
+    pub name: String,
+    /// Mots-clés recherchés (insensible à la casse) dans le nom du site puis dans le titre
+    pub keywords: Vec<String>,
+}
+
+/// Ensemble ordonné de catégories : la première qui correspond l'emporte
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SiteCategories {
+    pub categories: Vec<SiteCategory>,
+}
+
+impl Default for SiteCategories {
+    fn default() -> Self {
+        let category = |name: &str, keywords: &[&str]| SiteCategory {
+            name: name.to_string(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+        };
+
+        Self {
+            categories: vec![
+                category("video", &["youtube", "twitch", "netflix", "prime video", "disney+", "vimeo", "crunchyroll"]),
+                category("social", &["twitter", "x.com", "facebook", "instagram", "reddit", "linkedin", "discord", "mastodon", "tiktok"]),
+                category("work", &["github", "gitlab", "bitbucket", "jira", "confluence", "notion", "slack", "stack overflow", "google docs", "outlook", "teams"]),
+            ],
+        }
+    }
+}
+
+impl SiteCategories {
+    fn classify(&self, site_name: Option<&str>, page_title: &str) -> Option<String> {
+        let site = site_name.map(|s| s.to_lowercase());
+        let page = page_title.to_lowercase();
+
+        // Le nom du site est plus fiable que le titre : on le teste en premier pour toutes les catégories
+        if let Some(site) = &site {
+            for category in &self.categories {
+                if category.keywords.iter().any(|k| site.contains(&k.to_lowercase())) {
+                    return Some(category.name.clone());
+                }
+            }
+        }
+
+        for category in &self.categories {
+            if category.keywords.iter().any(|k| page.contains(&k.to_lowercase())) {
+                return Some(category.name.clone());
+            }
+        }
+
+        None
+    }
+}
+
+/// Analyseur des titres de fenêtres de navigateur
+#[derive(Default)]
+pub struct BrowserTitleParser {
+    categories: SiteCategories,
+}
+
+// Séparateurs utilisés par les sites entre le titre de page et le nom du site
+const SITE_SEPARATORS: [&str; 5] = [" - ", " — ", " – ", " | ", " · "];
+
+// Au-delà, la partie droite ressemble plus à un titre qu'à un nom de site
+const MAX_SITE_NAME_WORDS: usize = 4;
+
+impl BrowserTitleParser {
+    pub fn new() -> Self {
+        Self::with_categories(SiteCategories::default())
+    }
+
+    pub fn with_categories(categories: SiteCategories) -> Self {
+        Self { categories }
+    }
+
+    pub fn parse_window(&self, window: &WindowInfo) -> Option<ParsedTabTitle> {
+        let family = BrowserFamily::from_class_name(&window.class_name)?;
+        Some(self.parse(family, &window.window_title))
+    }
+
+    pub fn parse(&self, family: BrowserFamily, raw_title: &str) -> ParsedTabTitle {
+        let (title, browser) = Self::strip_browser_suffix(family, raw_title.trim());
+        let title = Self::strip_edge_tab_count(Self::strip_notification_count(title));
+        let (page_title, site_name) = Self::split_site_name(title);
+        let category = self.categories.classify(site_name.as_deref(), &page_title);
+
+        ParsedTabTitle {
+            raw_title: raw_title.to_string(),
+            browser,
+            page_title,
+            site_name,
+            category,
+        }
+    }
+
+    fn strip_browser_suffix(family: BrowserFamily, title: &str) -> (&str, Option<String>) {
+        for suffix in family.title_suffixes() {
+            if title == *suffix {
+                return ("", Some(suffix.replace('\u{200b}', "")));
+            }
+            for separator in [" — ", " - ", " – "] {
+                if let Some(rest) = title.strip_suffix(suffix).and_then(|t| t.strip_suffix(separator)) {
+                    return (rest.trim(), Some(suffix.replace('\u{200b}', "")));
+                }
+            }
+        }
+        (title, None)
+    }
+
+    // "(3) Accueil / X" -> "Accueil / X"
+    fn strip_notification_count(title: &str) -> &str {
+        if let Some(rest) = title.strip_prefix('(') {
+            if let Some(end) = rest.find(") ") {
+                if !rest[..end].is_empty() && rest[..end].chars().all(|c| c.is_ascii_digit() || c == '+') {
+                    return rest[end + 2..].trim_start();
+                }
+            }
+        }
+        title
+    }
+
+    // Edge ajoute "and 3 more pages" quand plusieurs onglets sont ouverts, suivi du nom du profil
+    // s'il n'est pas celui par défaut : "Docs and 3 more pages - Personal"
+    fn strip_edge_tab_count(title: &str) -> &str {
+        for (index, _) in title.match_indices(" and ") {
+            let tail = &title[index + 5..];
+            let digits = tail.chars().take_while(|c| c.is_ascii_digit()).count();
+            if digits == 0 {
+                continue;
+            }
+            let Some(rest) = tail[digits..].strip_prefix(" more page") else {
+                continue;
+            };
+            let rest = rest.strip_prefix('s').unwrap_or(rest);
+            if rest.is_empty() || SITE_SEPARATORS.iter().any(|separator| rest.starts_with(separator)) {
+                return &title[..index];
+            }
+        }
+        title
+    }
+
+    fn split_site_name(title: &str) -> (String, Option<String>) {
+        let split = SITE_SEPARATORS
+            .iter()
+            .filter_map(|separator| title.rfind(separator).map(|index| (index, separator.len())))
+            .max_by_key(|(index, _)| *index);
+
+        if let Some((index, separator_len)) = split {
+            let page = title[..index].trim();
+            let site = title[index + separator_len..].trim();
+            if !page.is_empty() && !site.is_empty() && site.split_whitespace().count() <= MAX_SITE_NAME_WORDS {
+                return (page.to_string(), Some(site.to_string()));
+            }
+        }
+
+        (title.to_string(), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(family: BrowserFamily, title: &str) -> ParsedTabTitle {
+        BrowserTitleParser::new().parse(family, title)
+    }
+
+    #[test]
+    fn browser_suffixes_are_removed() {
+        let firefox = parse(BrowserFamily::Firefox, "Rust Programming Language — Mozilla Firefox");
+        assert_eq!(firefox.browser.as_deref(), Some("Mozilla Firefox"));
+        assert_eq!(firefox.page_title, "Rust Programming Language");
+
+        let private = parse(BrowserFamily::Firefox, "Docs — Mozilla Firefox Private Browsing");
+        assert_eq!(private.browser.as_deref(), Some("Mozilla Firefox Private Browsing"));
+        assert_eq!(private.page_title, "Docs");
+
+        let chrome = parse(BrowserFamily::Chromium, "Pull requests · rust-lang/rust - Google Chrome");
+        assert_eq!(chrome.browser.as_deref(), Some("Google Chrome"));
+        assert_eq!(chrome.page_title, "Pull requests");
+        assert_eq!(chrome.site_name.as_deref(), Some("rust-lang/rust"));
+
+        // Edge insère un espace sans chasse dans son nom
+        let edge = parse(BrowserFamily::Chromium, "Bing - Microsoft\u{200b} Edge");
+        assert_eq!(edge.browser.as_deref(), Some("Microsoft Edge"));
+        assert_eq!(edge.page_title, "Bing");
+
+        let new_tab = parse(BrowserFamily::Chromium, "Google Chrome");
+        assert_eq!((new_tab.page_title.as_str(), new_tab.browser.as_deref()), ("", Some("Google Chrome")));
+
+        // Suffixe inconnu : titre conservé
+        let unknown = parse(BrowserFamily::Firefox, "Docs - Google Chrome");
+        assert_eq!(unknown.browser, None);
+    }
+
+    #[test]
+    fn counters_are_removed() {
+        let notifications = parse(BrowserFamily::Chromium, "(3) Accueil / X - Google Chrome");
+        assert_eq!(notifications.page_title, "Accueil / X");
+        let many = parse(BrowserFamily::Firefox, "(99+) Inbox — Mozilla Firefox");
+        assert_eq!(many.page_title, "Inbox");
+        // Parenthèses qui ne sont pas un compteur
+        let text = parse(BrowserFamily::Firefox, "(draft) Notes — Mozilla Firefox");
+        assert_eq!(text.page_title, "(draft) Notes");
+
+        let edge = parse(BrowserFamily::Chromium, "Docs and 3 more pages - Microsoft\u{200b} Edge");
+        assert_eq!((edge.page_title.as_str(), edge.site_name), ("Docs", None));
+        let single = parse(BrowserFamily::Chromium, "Docs and 1 more page - Microsoft Edge");
+        assert_eq!(single.page_title, "Docs");
+
+        // Profil nommé après le compteur : ni compteur ni profil ne restent
+        let profile = parse(BrowserFamily::Chromium, "Docs and 3 more pages - Personal - Microsoft\u{200b} Edge");
+        assert_eq!((profile.page_title.as_str(), profile.site_name), ("Docs", None));
+        let site = parse(BrowserFamily::Chromium, "Issues - GitHub and 12 more pages - Work - Microsoft Edge");
+        assert_eq!(site.page_title, "Issues");
+        assert_eq!(site.site_name.as_deref(), Some("GitHub"));
+
+        // "and" ordinaire dans le titre
+        let plain = parse(BrowserFamily::Chromium, "Salt and 3 more spices - Microsoft Edge");
+        assert_eq!(plain.page_title, "Salt and 3 more spices");
+    }
+
+    #[test]
+    fn site_name_is_split_from_the_last_separator() {
+        let youtube = parse(BrowserFamily::Firefox, "Lo-fi beats - live | YouTube — Mozilla Firefox");
+        assert_eq!(youtube.page_title, "Lo-fi beats - live");
+        assert_eq!(youtube.site_name.as_deref(), Some("YouTube"));
+
+        let four_words = parse(BrowserFamily::Chromium, "Article - The New York Times - Google Chrome");
+        assert_eq!(four_words.site_name.as_deref(), Some("The New York Times"));
+
+        // Plus de quatre mots : probablement la suite du titre
+        let sentence = parse(BrowserFamily::Chromium, "Breaking - how we shipped the release - Google Chrome");
+        assert_eq!(sentence.site_name, None);
+        assert_eq!(sentence.page_title, "Breaking - how we shipped the release");
+
+        let no_separator = parse(BrowserFamily::Chromium, "Inbox - Google Chrome");
+        assert_eq!((no_separator.page_title.as_str(), no_separator.site_name), ("Inbox", None));
+    }
+
+    #[test]
+    fn site_name_takes_precedence_for_categories() {
+        // Le titre évoque YouTube (video) mais le site est GitHub (work)
+        let parsed = parse(BrowserFamily::Chromium, "youtube-dl issues - GitHub - Google Chrome");
+        assert_eq!(parsed.category.as_deref(), Some("work"));
+
+        let from_title = parse(BrowserFamily::Chromium, "Watching YouTube - Google Chrome");
+        assert_eq!(from_title.category.as_deref(), Some("video"));
+
+        let categories = SiteCategories {
+            categories: vec![SiteCategory {
+                name: "docs".to_string(),
+                keywords: vec!["DOCS.RS".to_string()],
+            }],
+        };
+        let custom = BrowserTitleParser::with_categories(categories).parse(BrowserFamily::Firefox, "serde - Docs.rs — Mozilla Firefox");
+        assert_eq!(custom.category.as_deref(), Some("docs"));
+        assert_eq!(parse(BrowserFamily::Firefox, "Notes — Mozilla Firefox").category, None);
+    }
+}
@@ -0,0 +1,305 @@
+use crate::models::{CommandLineInfo, ProcessRole};
+use std::path::Path;
+
+/// Découper une ligne de commande Windows en arguments (règles de CommandLineToArgvW :
+/// guillemets, `\"` échappé, backslashes littéraux hors guillemets)
+pub fn split_windows_command_line(command_line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_arg = false;
+    let mut backslashes = 0usize;
+    let mut chars = command_line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                backslashes += 1;
+                has_arg = true;
+            }
+            '"' => {
+                // 2n backslashes + " -> n backslashes et bascule ; 2n+1 -> n backslashes et " littéral
+                current.extend(std::iter::repeat_n('\\', backslashes / 2));
+                if backslashes % 2 == 1 {
+                    current.push('"');
+                } else if in_quotes && chars.peek() == Some(&'"') {
+                    // "" dans une zone entre guillemets : guillemet littéral
+                    current.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = !in_quotes;
+                }
+                backslashes = 0;
+                has_arg = true;
+            }
+            ' ' | '\t' if !in_quotes => {
+                current.extend(std::iter::repeat_n('\\', backslashes));
+                backslashes = 0;
+                if has_arg {
+                    args.push(std::mem::take(&mut current));
+                    has_arg = false;
+                }
+            }
+            c => {
+                current.extend(std::iter::repeat_n('\\', backslashes));
+                backslashes = 0;
+                current.push(c);
+                has_arg = true;
+            }
+        }
+    }
+
+    current.extend(std::iter::repeat_n('\\', backslashes));
+    if has_arg {
+        args.push(current);
+    }
+    args
+}
+
+/// Reconstituer une ligne de commande lisible (arguments contenant des espaces entre guillemets)
+pub fn join_command_line(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            if arg.is_empty() || arg.chars().any(char::is_whitespace) {
+                format!("\"{}\"", arg.replace('"', "\\\""))
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Valeur d'une option `--nom=valeur` (ou `--nom valeur`)
+pub fn option_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let prefix = format!("{}=", name);
+    args.iter().enumerate().find_map(|(index, arg)| {
+        if let Some(value) = arg.strip_prefix(&prefix) {
+            Some(value)
+        } else if arg == name {
+            args.get(index + 1).map(|value| value.as_str())
+        } else {
+            None
+        }
+    })
+}
+
+/// Rôle du processus déduit de ses arguments
+pub fn analyze_command_line(process_name: &str, args: &[String]) -> CommandLineInfo {
+    // Chromium/Electron : les processus enfants reçoivent --type=renderer, gpu-process, utility...
+    let process_type = args
+        .iter()
+        .skip(1)
+        .find_map(|arg| arg.strip_prefix("--type="))
+        .map(|value| value.to_string());
+
+    let program = args
+        .first()
+        .map(|arg| executable_stem(arg))
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or_else(|| executable_stem(process_name));
+    let rest = args.get(1..).unwrap_or(&[]);
+
+    let (runtime, script) = if program.starts_with("java") {
+        (Some("java"), java_main(rest))
+    } else if program.starts_with("python") || program == "py" || program == "pyw" {
+        (Some("python"), python_script(rest))
+    } else if program == "node" || program == "nodejs" {
+        (Some("node"), first_operand(rest, &["-r", "--require", "--import", "--loader"]))
+    } else {
+        (None, None)
+    };
+
+    let utility_sub_type = option_value(rest, "--utility-sub-type").map(str::to_string);
+
+    CommandLineInfo {
+        process_type,
+        utility_sub_type,
+        runtime: runtime.map(|runtime| runtime.to_string()),
+        script,
+    }
+}
+
+/// Rôle Chromium/Electron d'un processus : sans --type, c'est le processus principal
+pub fn chromium_role(info: &CommandLineInfo) -> ProcessRole {
+    match info.process_type.as_deref() {
+        None => ProcessRole::Browser,
+        Some("renderer") => ProcessRole::Renderer,
+        Some("gpu-process") => ProcessRole::Gpu,
+        Some("utility") => ProcessRole::Utility,
+        Some("crashpad-handler") => ProcessRole::CrashpadHandler,
+        Some("zygote") => ProcessRole::Zygote,
+        Some(_) => ProcessRole::Other,
+    }
+}
+
+/// Arguments d'un processus quelconque, sans passer par le collecteur complet
+#[cfg(target_os = "linux")]
+pub fn read_process_args(pid: u32) -> Option<Vec<String>> {
+    crate::linux::procfs::read_cmdline(pid).filter(|args| !args.is_empty())
+}
+
+/// Arguments lus dans le PEB (refusé pour les processus protégés)
+#[cfg(windows)]
+pub fn read_process_args(pid: u32) -> Option<Vec<String>> {
+    use crate::metadata::process_parameters::ProcessParameters;
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::OpenProcess;
+    use winapi::um::winnt::{PROCESS_QUERY_INFORMATION, PROCESS_VM_READ};
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, 0, pid);
+        if handle.is_null() {
+            return None;
+        }
+        let command_line = ProcessParameters::open(handle).and_then(|parameters| parameters.command_line());
+        CloseHandle(handle);
+        Some(split_windows_command_line(&command_line.ok()?)).filter(|args| !args.is_empty())
+    }
+}
+
+/// "C:\\Program Files\\Java\\bin\\javaw.exe" -> "javaw"
+fn executable_stem(path: &str) -> String {
+    let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    Path::new(file_name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Archive (-jar), module (-m) ou classe principale
+fn java_main(args: &[String]) -> Option<String> {
+    if let Some(jar) = option_value(args, "-jar") {
+        return Some(jar.to_string());
+    }
+    if let Some(module) = option_value(args, "-m").or_else(|| option_value(args, "--module")) {
+        return Some(module.to_string());
+    }
+    first_operand(
+        args,
+        &["-cp", "-classpath", "--class-path", "-p", "--module-path", "--add-modules", "--add-opens"],
+    )
+}
+
+/// Script, module (-m) ou code en ligne (-c)
+fn python_script(args: &[String]) -> Option<String> {
+    let mut skip_next = false;
+    for (index, arg) in args.iter().enumerate() {
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+        match arg.as_str() {
+            "-m" => return args.get(index + 1).map(|module| format!("-m {}", module)),
+            "-c" => return Some("-c".to_string()),
+            // Options qui prennent une valeur séparée
+            "-W" | "-X" | "--check-hash-based-pycs" => skip_next = true,
+            _ if arg.starts_with('-') => {}
+            _ => return Some(arg.clone()),
+        }
+    }
+    None
+}
+
+/// Premier argument qui n'est ni une option ni la valeur d'une option listée
+fn first_operand(args: &[String], options_with_value: &[&str]) -> Option<String> {
+    let mut skip_next = false;
+    for arg in args {
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+        if options_with_value.contains(&arg.as_str()) {
+            skip_next = true;
+            continue;
+        }
+        if !arg.starts_with('-') {
+            return Some(arg.clone());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn windows_command_lines_follow_argv_rules() {
+        let cases: &[(&str, &[&str])] = &[
+            (r#"app.exe a  b	c"#, &["app.exe", "a", "b", "c"]),
+            (r#""C:\Program Files\App\app.exe" --flag"#, &[r"C:\Program Files\App\app.exe", "--flag"]),
+            (r#"app.exe "a b" c"d e"f"#, &["app.exe", "a b", "cd ef"]),
+            // Backslashes littéraux hors d'une séquence \"
+            (r#"app.exe C:\dir\ \\server\share"#, &["app.exe", r"C:\dir\", r"\\server\share"]),
+            // \" : guillemet littéral ; \\" : un backslash puis bascule
+            (r#"app.exe a\"b "c\\" d"#, &["app.exe", r#"a"b"#, r"c\", "d"]),
+            (r#"app.exe "a\\\"b""#, &["app.exe", r#"a\"b"#]),
+            // "" dans une zone entre guillemets
+            (r#"app.exe "say ""hi""""#, &["app.exe", r#"say "hi""#]),
+            (r#"app.exe """#, &["app.exe", ""]),
+            ("  ", &[]),
+        ];
+        for (command_line, expected) in cases {
+            assert_eq!(split_windows_command_line(command_line), args(expected), "{}", command_line);
+        }
+    }
+
+    #[test]
+    fn chromium_types_map_to_roles() {
+        let cases = [
+            ("--type=renderer", ProcessRole::Renderer),
+            ("--type=gpu-process", ProcessRole::Gpu),
+            ("--type=utility", ProcessRole::Utility),
+            ("--type=crashpad-handler", ProcessRole::CrashpadHandler),
+            ("--type=zygote", ProcessRole::Zygote),
+            ("--type=ppapi-broker", ProcessRole::Other),
+        ];
+        for (flag, role) in cases {
+            let info = analyze_command_line("chrome.exe", &args(&["chrome.exe", flag, "--lang=fr"]));
+            assert_eq!(chromium_role(&info), role, "{}", flag);
+        }
+
+        let utility = analyze_command_line(
+            "chrome",
+            &args(&["/opt/google/chrome/chrome", "--type=utility", "--utility-sub-type=audio.mojom.AudioService"]),
+        );
+        assert_eq!(utility.process_type.as_deref(), Some("utility"));
+        assert_eq!(utility.utility_sub_type.as_deref(), Some("audio.mojom.AudioService"));
+    }
+
+    #[test]
+    fn electron_main_has_no_type() {
+        // Processus principal d'Electron : ni --type, ni runtime d'interpréteur
+        let main = analyze_command_line(
+            "Code.exe",
+            &args(&[r"C:\Users\me\AppData\Local\Programs\Microsoft VS Code\Code.exe", "--new-window", "."]),
+        );
+        assert_eq!(main.process_type, None);
+        assert_eq!(main.runtime, None);
+        assert_eq!(chromium_role(&main), ProcessRole::Browser);
+
+        // --type= en premier argument est le programme, pas une option
+        let program = analyze_command_line("x", &args(&["--type=renderer"]));
+        assert_eq!(program.process_type, None);
+
+        let renderer = analyze_command_line("code", &args(&["/usr/share/code/code", "--type=renderer", "--app-path=/usr/share/code/resources/app"]));
+        assert_eq!(chromium_role(&renderer), ProcessRole::Renderer);
+    }
+
+    #[test]
+    fn interpreters_report_their_script() {
+        let java = analyze_command_line("java", &args(&["/usr/bin/java", "-Xmx1g", "-jar", "/opt/app/server.jar"]));
+        assert_eq!((java.runtime.as_deref(), java.script.as_deref()), (Some("java"), Some("/opt/app/server.jar")));
+
+        let python = analyze_command_line("python3", &args(&["python3", "-X", "dev", "-m", "http.server"]));
+        assert_eq!((python.runtime.as_deref(), python.script.as_deref()), (Some("python"), Some("-m http.server")));
+
+        let node = analyze_command_line("node.exe", &args(&[r"C:\nodejs\node.exe", "-r", "dotenv/config", "server.js"]));
+        assert_eq!((node.runtime.as_deref(), node.script.as_deref()), (Some("node"), Some("server.js")));
+    }
+}
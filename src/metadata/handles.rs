@@ -0,0 +1,354 @@
+use crate::models::HandleInfo;
+use anyhow::Result;
+
+/// Handles (Windows) ou descripteurs de fichiers (Linux) ouverts par un processus.
+/// `types` filtre par type (insensible à la casse, vide = tous), `limit` plafonne le nombre retourné.
+pub fn enumerate_handles(pid: u32, types: &[String], limit: usize) -> Result<Vec<HandleInfo>> {
+    if limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    #[cfg(windows)]
+    let handles = unsafe { windows_handles::enumerate(pid, types, limit) };
+    #[cfg(target_os = "linux")]
+    let handles = linux_fds::enumerate(pid, types, limit);
+
+    handles
+}
+
+fn type_wanted(types: &[String], handle_type: &str) -> bool {
+    types.is_empty() || types.iter().any(|wanted| wanted.eq_ignore_ascii_case(handle_type))
+}
+
+#[cfg(target_os = "linux")]
+mod linux_fds {
+    use super::type_wanted;
+    use crate::models::HandleInfo;
+    use anyhow::Result;
+    use std::fs;
+
+    pub fn enumerate(pid: u32, types: &[String], limit: usize) -> Result<Vec<HandleInfo>> {
+        let entries = fs::read_dir(format!("/proc/{}/fd", pid)).map_err(|e| match e.kind() {
+            std::io::ErrorKind::PermissionDenied => {
+                anyhow::anyhow!("Accès refusé aux descripteurs du PID {}", pid)
+            }
+            _ => anyhow::anyhow!("Impossible de lire /proc/{}/fd: {}", pid, e),
+        })?;
+
+        let mut fds: Vec<u32> = entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().to_str().and_then(|name| name.parse().ok()))
+            .collect();
+        fds.sort_unstable();
+
+        let mut handles = Vec::new();
+        for fd in fds {
+            if handles.len() >= limit {
+                break;
+            }
+
+            // Le descripteur a pu être fermé entre la liste et la lecture
+            let Ok(target) = fs::read_link(format!("/proc/{}/fd/{}", pid, fd)) else {
+                continue;
+            };
+            let target = target.to_string_lossy().to_string();
+            let handle_type = classify(&target);
+            if !type_wanted(types, handle_type) {
+                continue;
+            }
+
+            handles.push(HandleInfo {
+                handle_type: handle_type.to_string(),
+                handle_value: fd as u64,
+                object_name: Some(target),
+                access_mask: open_flags(pid, fd).unwrap_or(0),
+            });
+        }
+
+        Ok(handles)
+    }
+
+    /// "/home/x/f.txt", "socket:[1234]", "pipe:[5678]", "anon_inode:[eventfd]"...
+    fn classify(target: &str) -> &'static str {
+        if target.starts_with('/') {
+            "file"
+        } else if target.starts_with("socket:") {
+            "socket"
+        } else if target.starts_with("pipe:") {
+            "pipe"
+        } else if target == "anon_inode:[eventfd]" {
+            "eventfd"
+        } else if target.starts_with("anon_inode:") {
+            "anon_inode"
+        } else {
+            "other"
+        }
+    }
+
+    /// Drapeaux d'ouverture (O_RDONLY, O_WRONLY, O_NONBLOCK...) : ligne "flags:" en octal de fdinfo
+    fn open_flags(pid: u32, fd: u32) -> Option<u32> {
+        let content = fs::read_to_string(format!("/proc/{}/fdinfo/{}", pid, fd)).ok()?;
+        parse_flags(&content)
+    }
+
+    fn parse_flags(fdinfo: &str) -> Option<u32> {
+        fdinfo
+            .lines()
+            .find_map(|line| line.strip_prefix("flags:"))
+            .and_then(|value| u32::from_str_radix(value.trim(), 8).ok())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::os::fd::AsRawFd;
+        use std::os::unix::net::UnixStream;
+
+        #[test]
+        fn fd_targets_are_classified() {
+            let cases = [
+                ("/home/me/notes.txt", "file"),
+                ("/dev/null", "file"),
+                ("/tmp/old.log (deleted)", "file"),
+                ("socket:[123456]", "socket"),
+                ("pipe:[7890]", "pipe"),
+                ("anon_inode:[eventfd]", "eventfd"),
+                ("anon_inode:[eventpoll]", "anon_inode"),
+                ("anon_inode:inotify", "anon_inode"),
+                ("net:[4026531840]", "other"),
+            ];
+            for (target, expected) in cases {
+                assert_eq!(classify(target), expected, "{}", target);
+            }
+        }
+
+        #[test]
+        fn open_flags_are_read_in_octal() {
+            let fdinfo = "pos:\t0\nflags:\t02004002\nmnt_id:\t15\nino:\t1057\n";
+            let flags = parse_flags(fdinfo).unwrap();
+            assert_eq!(flags & libc::O_ACCMODE as u32, libc::O_RDWR as u32);
+            assert_ne!(flags & libc::O_NONBLOCK as u32, 0);
+            assert_ne!(flags & libc::O_CLOEXEC as u32, 0);
+            assert_eq!(parse_flags("pos:\t0\n"), None);
+        }
+
+        #[test]
+        fn own_descriptors_are_filtered_by_type() {
+            let (left, right) = UnixStream::pair().unwrap();
+            let pid = std::process::id();
+
+            let sockets = enumerate(pid, &["SOCKET".to_string()], usize::MAX).unwrap();
+            assert!(sockets.iter().all(|handle| handle.handle_type == "socket"));
+            for stream in [&left, &right] {
+                let fd = stream.as_raw_fd() as u64;
+                let handle = sockets.iter().find(|handle| handle.handle_value == fd).unwrap();
+                assert!(handle.object_name.as_deref().unwrap().starts_with("socket:["));
+                assert_eq!(handle.access_mask & libc::O_ACCMODE as u32, libc::O_RDWR as u32);
+            }
+
+            assert_eq!(enumerate(pid, &[], 1).unwrap().len(), 1);
+        }
+    }
+}
+
+#[cfg(windows)]
+mod windows_handles {
+    use super::type_wanted;
+    use crate::models::HandleInfo;
+    use anyhow::{bail, Result};
+    use std::collections::HashMap;
+    use std::mem;
+    use std::ptr::{null_mut, read_unaligned};
+    use winapi::shared::ntdef::HANDLE;
+    use winapi::um::fileapi::GetFileType;
+    use winapi::um::handleapi::{CloseHandle, DuplicateHandle};
+    use winapi::um::processthreadsapi::{GetCurrentProcess, OpenProcess};
+    use winapi::um::winbase::FILE_TYPE_DISK;
+    use winapi::um::winnt::{DUPLICATE_SAME_ACCESS, PROCESS_DUP_HANDLE};
+    use windows::Wdk::Foundation::{NtQueryObject, ObjectTypeInformation, OBJECT_INFORMATION_CLASS};
+    use windows::Wdk::System::SystemInformation::{NtQuerySystemInformation, SYSTEM_INFORMATION_CLASS};
+    use windows::Win32::Foundation::{HANDLE as WinHandle, UNICODE_STRING};
+
+    // Classes non exposées par les en-têtes publics
+    const SYSTEM_EXTENDED_HANDLE_INFORMATION: SYSTEM_INFORMATION_CLASS = SYSTEM_INFORMATION_CLASS(64);
+    const OBJECT_NAME_INFORMATION: OBJECT_INFORMATION_CLASS = OBJECT_INFORMATION_CLASS(1);
+    const STATUS_INFO_LENGTH_MISMATCH: i32 = 0xC0000004u32 as i32;
+    const STATUS_BUFFER_OVERFLOW: i32 = 0x80000005u32 as i32;
+    const MAX_SYSTEM_HANDLES_BUFFER: usize = 512 * 1024 * 1024;
+
+    /// SYSTEM_HANDLE_TABLE_ENTRY_INFO_EX
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct SystemHandleEntry {
+        object: usize,
+        unique_process_id: usize,
+        handle_value: usize,
+        granted_access: u32,
+        creator_back_trace_index: u16,
+        object_type_index: u16,
+        handle_attributes: u32,
+        reserved: u32,
+    }
+
+    pub unsafe fn enumerate(pid: u32, types: &[String], limit: usize) -> Result<Vec<HandleInfo>> {
+        let entries = system_handles(pid)?;
+
+        // PROCESS_DUP_HANDLE : nécessaire pour copier chaque handle et interroger son objet
+        let process = OpenProcess(PROCESS_DUP_HANDLE, 0, pid);
+        if process.is_null() {
+            bail!("Accès refusé aux handles du PID {} (PROCESS_DUP_HANDLE)", pid);
+        }
+
+        // Le nom de type ne dépend que de l'index : une requête par type suffit
+        let mut type_names: HashMap<u16, String> = HashMap::new();
+        let mut handles = Vec::new();
+
+        for entry in entries {
+            if handles.len() >= limit {
+                break;
+            }
+
+            let mut duplicate: HANDLE = null_mut();
+            let duplicated = DuplicateHandle(
+                process,
+                entry.handle_value as HANDLE,
+                GetCurrentProcess(),
+                &mut duplicate,
+                0,
+                0,
+                DUPLICATE_SAME_ACCESS,
+            ) != 0;
+
+            if duplicated && !type_names.contains_key(&entry.object_type_index) {
+                if let Some(name) = query_unicode(duplicate, ObjectTypeInformation) {
+                    type_names.insert(entry.object_type_index, name);
+                }
+            }
+            let handle_type = type_names
+                .get(&entry.object_type_index)
+                .cloned()
+                .unwrap_or_else(|| format!("Type{}", entry.object_type_index));
+
+            if !type_wanted(types, &handle_type) {
+                if duplicated {
+                    CloseHandle(duplicate);
+                }
+                continue;
+            }
+
+            let object_name = if duplicated {
+                let name = object_name(duplicate, &handle_type);
+                CloseHandle(duplicate);
+                name
+            } else {
+                None
+            };
+
+            handles.push(HandleInfo {
+                handle_type,
+                handle_value: entry.handle_value as u64,
+                object_name,
+                access_mask: entry.granted_access,
+            });
+        }
+
+        CloseHandle(process);
+        Ok(handles)
+    }
+
+    /// Handles du système entier, filtrés sur le processus
+    unsafe fn system_handles(pid: u32) -> Result<Vec<SystemHandleEntry>> {
+        let mut buffer: Vec<u64> = vec![0; 1024 * 1024 / 8];
+        loop {
+            let size = buffer.len() * 8;
+            let mut needed = 0u32;
+            let status = NtQuerySystemInformation(
+                SYSTEM_EXTENDED_HANDLE_INFORMATION,
+                buffer.as_mut_ptr() as *mut _,
+                size as u32,
+                &mut needed,
+            );
+
+            if status.0 == STATUS_INFO_LENGTH_MISMATCH {
+                // La table grossit entre deux appels : marge en plus de la taille demandée
+                let next = (needed as usize).max(size * 2);
+                if next > MAX_SYSTEM_HANDLES_BUFFER {
+                    bail!("Table des handles du système trop grande");
+                }
+                buffer = vec![0; next / 8 + 1];
+                continue;
+            }
+            if status.0 < 0 {
+                bail!("NtQuerySystemInformation a échoué (statut 0x{:08X})", status.0 as u32);
+            }
+            break;
+        }
+
+        // SYSTEM_HANDLE_INFORMATION_EX : NumberOfHandles, Reserved, puis les entrées
+        let base = buffer.as_ptr() as *const u8;
+        let count = read_unaligned(base as *const usize);
+        let entries = base.add(2 * mem::size_of::<usize>()) as *const SystemHandleEntry;
+        let capacity = (buffer.len() * 8 - 2 * mem::size_of::<usize>()) / mem::size_of::<SystemHandleEntry>();
+
+        Ok((0..count.min(capacity))
+            .map(|index| read_unaligned(entries.add(index)))
+            .filter(|entry| entry.unique_process_id == pid as usize)
+            .collect())
+    }
+
+    unsafe fn object_name(handle: HANDLE, handle_type: &str) -> Option<String> {
+        // NtQueryObject peut bloquer indéfiniment sur un pipe synchrone : pour les
+        // fichiers, seuls ceux sur disque sont interrogés
+        if handle_type == "File" && GetFileType(handle) != FILE_TYPE_DISK {
+            return None;
+        }
+        query_unicode(handle, OBJECT_NAME_INFORMATION).filter(|name| !name.is_empty())
+    }
+
+    /// OBJECT_NAME_INFORMATION et PUBLIC_OBJECT_TYPE_INFORMATION commencent par un UNICODE_STRING
+    unsafe fn query_unicode(handle: HANDLE, class: OBJECT_INFORMATION_CLASS) -> Option<String> {
+        let mut buffer: Vec<u64> = vec![0; 128];
+        for _ in 0..4 {
+            let mut needed = 0u32;
+            let status = NtQueryObject(
+                Some(WinHandle(handle as *mut core::ffi::c_void)),
+                class,
+                Some(buffer.as_mut_ptr() as *mut core::ffi::c_void),
+                (buffer.len() * 8) as u32,
+                Some(&mut needed),
+            );
+
+            if status.0 == STATUS_INFO_LENGTH_MISMATCH || status.0 == STATUS_BUFFER_OVERFLOW {
+                buffer = vec![0; (needed as usize).max(buffer.len() * 16) / 8 + 1];
+                continue;
+            }
+            if status.0 < 0 {
+                return None;
+            }
+
+            let text = &*(buffer.as_ptr() as *const UNICODE_STRING);
+            if text.Buffer.is_null() || text.Length == 0 {
+                return Some(String::new());
+            }
+            let chars = std::slice::from_raw_parts(text.Buffer.0, text.Length as usize / 2);
+            return Some(String::from_utf16_lossy(chars));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn types_are_matched_without_case() {
+        let types = vec!["File".to_string(), "socket".to_string()];
+        assert!(type_wanted(&types, "file"));
+        assert!(type_wanted(&types, "SOCKET"));
+        assert!(!type_wanted(&types, "pipe"));
+        // Aucun filtre : tous les types
+        assert!(type_wanted(&[], "Mutant"));
+        assert!(enumerate_handles(std::process::id(), &[], 0).unwrap().is_empty());
+    }
+}
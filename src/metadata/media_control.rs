@@ -1,28 +1,26 @@
-use crate::models::{MediaSessionInfo, MetadataOptions};
+use crate::metadata::ProcessMetadataCollector;
+use crate::models::{MediaPlaybackStatus, MediaSessionInfo, MetadataOptions};
+use crate::system_snapshot::SnapshotCache;
 use anyhow::Result;
 use std::collections::HashMap;
-use std::mem;
-use std::ptr::null_mut;
-use winapi::{
-    shared::ntdef::HANDLE,
-    um::{
-        handleapi::CloseHandle,
-        tlhelp32::{
-            CreateToolhelp32Snapshot, Process32First, Process32Next, PROCESSENTRY32,
-            TH32CS_SNAPPROCESS,
-        },
-    },
-};
+use std::sync::Arc;
+use sup_common::debug_eprintln;
 use windows::Media::Control::{
     GlobalSystemMediaTransportControlsSessionManager,
     GlobalSystemMediaTransportControlsSession
 };
 
-pub struct MediaControlCollector;
+pub struct MediaControlCollector {
+    snapshots: Arc<SnapshotCache>,
+}
 
 impl MediaControlCollector {
     pub fn new() -> Self {
-        Self
+        Self::with_snapshot_cache(SnapshotCache::shared())
+    }
+
+    pub fn with_snapshot_cache(snapshots: Arc<SnapshotCache>) -> Self {
+        Self { snapshots }
     }
 
     pub async fn get_media_sessions_for_process(
@@ -84,6 +82,84 @@ impl MediaControlCollector {
         Ok(raw_data)
     }
 
+    /// Sessions et propriétés brutes en un seul passage, avec un gestionnaire déjà obtenu
+    /// (utilisé par le thread média qui conserve le gestionnaire entre les appels)
+    pub async fn collect_for_process(
+        &self,
+        manager: &GlobalSystemMediaTransportControlsSessionManager,
+        pid: u32,
+        options: &MetadataOptions,
+    ) -> Result<(Vec<MediaSessionInfo>, HashMap<String, serde_json::Value>)> {
+        let mut sessions = Vec::new();
+        let mut raw_data = HashMap::new();
+
+        for session in manager.GetSessions()? {
+            if !self.session_matches_process(&session, pid, options) {
+                continue;
+            }
+
+            sessions.push(self.extract_raw_session_info(&session, pid).await?);
+            // On garde les propriétés brutes de la première session qui correspond
+            if !raw_data.contains_key("media_control_session") {
+                let session_data = self.extract_all_raw_properties(&session).await?;
+                raw_data.insert("media_control_session".to_string(), session_data);
+            }
+        }
+
+        Ok((sessions, raw_data))
+    }
+
+    /// PIDs des processus qui émettent du son (sessions audio actives du périphérique de sortie par défaut).
+    /// Les sessions Media Control n'exposent pas de PID : l'audio permet de désigner la bonne instance.
+    pub fn get_active_audio_process_ids(&self) -> Vec<u32> {
+        use windows::core::Interface;
+        use windows::Win32::Media::Audio::{
+            eMultimedia, eRender, AudioSessionStateActive, IAudioSessionControl2,
+            IAudioSessionManager2, IMMDeviceEnumerator, MMDeviceEnumerator,
+        };
+        use windows::Win32::System::Com::{
+            CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_ALL, COINIT_MULTITHREADED,
+        };
+
+        let mut pids = Vec::new();
+
+        unsafe {
+            // COM peut déjà être initialisé sur ce thread dans un autre mode : on continue quand même
+            let initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+
+            let result = (|| -> windows::core::Result<()> {
+                let enumerator: IMMDeviceEnumerator =
+                    CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+                let device = enumerator.GetDefaultAudioEndpoint(eRender, eMultimedia)?;
+                let manager: IAudioSessionManager2 = device.Activate(CLSCTX_ALL, None)?;
+                let sessions = manager.GetSessionEnumerator()?;
+
+                for index in 0..sessions.GetCount()? {
+                    let control = sessions.GetSession(index)?;
+                    if control.GetState()? != AudioSessionStateActive {
+                        continue;
+                    }
+                    if let Ok(pid) = control.cast::<IAudioSessionControl2>()?.GetProcessId() {
+                        if pid != 0 && !pids.contains(&pid) {
+                            pids.push(pid);
+                        }
+                    }
+                }
+                Ok(())
+            })();
+
+            if let Err(e) = result {
+                debug_eprintln!("⚠️ Impossible d'énumérer les sessions audio: {}", e);
+            }
+
+            if initialized {
+                CoUninitialize();
+            }
+        }
+
+        pids
+    }
+
     fn session_matches_process(
         &self,
         session: &GlobalSystemMediaTransportControlsSession,
@@ -95,7 +171,8 @@ impl MediaControlCollector {
             name.clone()
         } else {
             // Sinon, récupérer le nom du processus pour le PID cible
-            self.get_process_name_by_pid(target_pid)
+            ProcessMetadataCollector::with_snapshot_cache(Arc::clone(&self.snapshots))
+                .get_process_name_by_pid(target_pid)
         };
 
         // Récupérer l'AppUserModelId de la session
@@ -149,6 +226,7 @@ impl MediaControlCollector {
             app_user_model_id,
             media_type: None,
             playback_status: None,
+            playback_state: None,
             title: None,
             artist: None,
             album: None,
@@ -170,7 +248,9 @@ impl MediaControlCollector {
 
         // Extraire les informations de lecture BRUTES
         if let Some(playback) = playback_info {
-            session_info.playback_status = Some(format!("{:?}", playback.PlaybackStatus()));
+            let status = playback.PlaybackStatus();
+            session_info.playback_status = Some(format!("{:?}", status));
+            session_info.playback_state = status.ok().map(|status| MediaPlaybackStatus::from_code(status.0));
         }
 
         Ok(session_info)
@@ -292,36 +372,4 @@ impl MediaControlCollector {
 
         Ok(all_properties)
     }
-
-    fn get_process_name_by_pid(&self, pid: u32) -> String {
-        unsafe {
-            let snapshot: HANDLE = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
-            if snapshot != null_mut() {
-                let mut pe32: PROCESSENTRY32 = mem::zeroed();
-                pe32.dwSize = mem::size_of::<PROCESSENTRY32>() as u32;
-
-                if Process32First(snapshot, &mut pe32) != 0 {
-                    loop {
-                        if pe32.th32ProcessID == pid {
-                            let name = self.c_string_to_string_260(&pe32.szExeFile);
-                            CloseHandle(snapshot);
-                            return name.to_lowercase();
-                        }
-
-                        if Process32Next(snapshot, &mut pe32) == 0 {
-                            break;
-                        }
-                    }
-                }
-                CloseHandle(snapshot);
-            }
-        }
-        String::new()
-    }
-
-    fn c_string_to_string_260(&self, c_str: &[i8; 260]) -> String {
-        let end = c_str.iter().position(|&x| x == 0).unwrap_or(c_str.len());
-        let bytes: Vec<u8> = c_str[..end].iter().map(|&x| x as u8).collect();
-        String::from_utf8_lossy(&bytes).to_string()
-    }
 }
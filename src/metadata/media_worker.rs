@@ -0,0 +1,150 @@
+use crate::metadata::MediaControlCollector;
+use crate::models::{MediaSessionInfo, MetadataOptions};
+use crate::system_snapshot::SnapshotCache;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::sync::{Arc, OnceLock};
+use sup_common::debug_eprintln;
+use tokio::sync::oneshot;
+use windows::Media::Control::GlobalSystemMediaTransportControlsSessionManager;
+
+/// Sessions média d'un processus et propriétés brutes de la première session trouvée
+pub type MediaCollection = (Vec<MediaSessionInfo>, HashMap<String, serde_json::Value>);
+
+type Job = Box<dyn FnOnce(&mut MediaContext) + Send>;
+
+/// État propre au thread média : runtime local et objets WinRT réutilisés entre les appels
+pub struct MediaContext {
+    runtime: tokio::runtime::Runtime,
+    manager: Option<GlobalSystemMediaTransportControlsSessionManager>,
+}
+
+impl MediaContext {
+    /// Gestionnaire de sessions, demandé une seule fois puis conservé
+    fn manager(&mut self) -> Result<GlobalSystemMediaTransportControlsSessionManager> {
+        if let Some(manager) = &self.manager {
+            return Ok(manager.clone());
+        }
+
+        let manager = self
+            .runtime
+            .block_on(async { GlobalSystemMediaTransportControlsSessionManager::RequestAsync()?.await })?;
+        self.manager = Some(manager.clone());
+        Ok(manager)
+    }
+}
+
+/// Thread dédié aux API Media Control : les appelants (async ou non) lui envoient des tâches
+/// par canal au lieu de créer un runtime à chaque collecte
+pub struct MediaWorker {
+    jobs: mpsc::Sender<Job>,
+}
+
+static SHARED_WORKER: OnceLock<MediaWorker> = OnceLock::new();
+
+impl MediaWorker {
+    pub fn start() -> Result<Self> {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+
+        std::thread::Builder::new()
+            .name("sup_mtracker-media".to_string())
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                    Ok(runtime) => runtime,
+                    Err(e) => {
+                        debug_eprintln!("❌ Impossible de démarrer le runtime média: {}", e);
+                        return;
+                    }
+                };
+                let mut context = MediaContext { runtime, manager: None };
+
+                // Le thread s'arrête quand tous les émetteurs sont détruits
+                while let Ok(job) = receiver.recv() {
+                    job(&mut context);
+                }
+            })?;
+
+        Ok(Self { jobs })
+    }
+
+    /// Worker commun au processus, démarré au premier usage (nouvelle tentative au prochain appel en cas d'échec)
+    pub fn shared() -> Result<&'static Self> {
+        if let Some(worker) = SHARED_WORKER.get() {
+            return Ok(worker);
+        }
+        let worker = Self::start().map_err(|e| anyhow::anyhow!("Impossible de démarrer le thread média: {}", e))?;
+        // Course entre deux premiers appels : le worker perdant est détruit et son thread s'arrête
+        Ok(SHARED_WORKER.get_or_init(|| worker))
+    }
+
+    /// Exécuter une tâche sur le thread média et attendre son résultat
+    pub async fn run<T, F>(&self, job: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut MediaContext) -> T + Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+        self.submit(Box::new(move |context| {
+            let _ = reply.send(job(context));
+        }))?;
+        result.await.map_err(|_| anyhow::anyhow!("Le thread média s'est arrêté"))
+    }
+
+    /// Variante bloquante de `run`, à ne pas appeler depuis une tâche async
+    pub fn run_blocking<T, F>(&self, job: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut MediaContext) -> T + Send + 'static,
+    {
+        let (reply, result) = mpsc::channel();
+        self.submit(Box::new(move |context| {
+            let _ = reply.send(job(context));
+        }))?;
+        result.recv().map_err(|_| anyhow::anyhow!("Le thread média s'est arrêté"))
+    }
+
+    fn submit(&self, job: Job) -> Result<()> {
+        self.jobs
+            .send(job)
+            .map_err(|_| anyhow::anyhow!("Le thread média s'est arrêté"))
+    }
+
+    /// Sessions média et propriétés brutes d'un processus
+    pub async fn media_for_process(
+        &self,
+        pid: u32,
+        options: MetadataOptions,
+        snapshots: Arc<SnapshotCache>,
+    ) -> Result<MediaCollection> {
+        self.run(move |context| collect(context, pid, &options, snapshots)).await?
+    }
+
+    pub fn media_for_process_blocking(
+        &self,
+        pid: u32,
+        options: MetadataOptions,
+        snapshots: Arc<SnapshotCache>,
+    ) -> Result<MediaCollection> {
+        self.run_blocking(move |context| collect(context, pid, &options, snapshots))?
+    }
+
+    /// PIDs des processus qui émettent du son (COM initialisé une fois sur le thread média)
+    pub fn active_audio_process_ids_blocking(&self, snapshots: Arc<SnapshotCache>) -> Vec<u32> {
+        self.run_blocking(move |_| MediaControlCollector::with_snapshot_cache(snapshots).get_active_audio_process_ids())
+            .unwrap_or_default()
+    }
+}
+
+fn collect(
+    context: &mut MediaContext,
+    pid: u32,
+    options: &MetadataOptions,
+    snapshots: Arc<SnapshotCache>,
+) -> Result<MediaCollection> {
+    let manager = context.manager()?;
+    let collector = MediaControlCollector::with_snapshot_cache(snapshots);
+    context
+        .runtime
+        .block_on(collector.collect_for_process(&manager, pid, options))
+}
@@ -0,0 +1,559 @@
+use crate::models::{MemoryCategory, MemoryMap, MemoryRegion, MemoryUsage};
+use anyhow::Result;
+
+// Ordre d'affichage de la répartition
+const KINDS: [&str; 6] = ["heap", "stack", "image", "anonymous", "file", "other"];
+
+/// Carte mémoire d'un processus : régions projetées (smaps / VirtualQueryEx), répartition
+/// par catégorie et les `limit` plus grosses régions
+pub fn collect_memory_map(pid: u32, limit: usize) -> Result<MemoryMap> {
+    #[cfg(windows)]
+    let regions = unsafe { windows_regions::collect(pid) };
+    #[cfg(target_os = "linux")]
+    let regions = linux_smaps::collect(pid);
+
+    let regions = regions?;
+    #[allow(unused_mut)] // Totaux remplacés par smaps_rollup sous Linux
+    let mut map = summarize(regions, limit);
+
+    // smaps_rollup : PSS exact, agrégé par le noyau
+    #[cfg(target_os = "linux")]
+    if let Some(rollup) = linux_smaps::rollup(pid) {
+        linux_smaps::apply_rollup(&mut map, rollup);
+    }
+
+    Ok(map)
+}
+
+fn summarize(mut regions: Vec<MemoryRegion>, limit: usize) -> MemoryMap {
+    let mut total = MemoryUsage::default();
+    let mut breakdown: Vec<MemoryCategory> = KINDS
+        .iter()
+        .map(|kind| MemoryCategory {
+            kind: kind.to_string(),
+            region_count: 0,
+            usage: MemoryUsage::default(),
+        })
+        .collect();
+
+    for region in &regions {
+        accumulate(&mut total, &region.usage);
+        if let Some(category) = breakdown.iter_mut().find(|category| category.kind == region.kind) {
+            category.region_count += 1;
+            accumulate(&mut category.usage, &region.usage);
+        }
+    }
+    breakdown.retain(|category| category.region_count > 0);
+
+    // Les plus grosses régions (résident si connu, sinon taille), restituées dans l'ordre des adresses
+    let region_count = regions.len();
+    regions.sort_by_key(|region| std::cmp::Reverse(region.usage.rss.unwrap_or(region.usage.size)));
+    regions.truncate(limit);
+    regions.sort_by_key(|region| region.start_address);
+
+    MemoryMap {
+        total,
+        breakdown,
+        region_count,
+        regions,
+    }
+}
+
+fn accumulate(total: &mut MemoryUsage, usage: &MemoryUsage) {
+    let add = |total: Option<u64>, value: Option<u64>| match (total, value) {
+        (None, None) => None,
+        (total, value) => Some(total.unwrap_or(0) + value.unwrap_or(0)),
+    };
+    total.size += usage.size;
+    total.rss = add(total.rss, usage.rss);
+    total.pss = add(total.pss, usage.pss);
+    total.private += usage.private;
+    total.shared += usage.shared;
+    total.swap = add(total.swap, usage.swap);
+}
+
+#[cfg(target_os = "linux")]
+mod linux_smaps {
+    use crate::linux::procfs::parse_kb;
+    use crate::models::{MemoryMap, MemoryRegion, MemoryUsage};
+    use anyhow::{anyhow, Result};
+    use std::collections::{HashMap, HashSet};
+    use std::fs;
+
+    pub fn collect(pid: u32) -> Result<Vec<MemoryRegion>> {
+        let content = fs::read_to_string(format!("/proc/{}/smaps", pid)).map_err(|e| match e.kind() {
+            std::io::ErrorKind::PermissionDenied => anyhow!("Accès refusé à la carte mémoire du PID {}", pid),
+            _ => anyhow!("Impossible de lire /proc/{}/smaps: {}", pid, e),
+        })?;
+        Ok(parse_smaps(&content))
+    }
+
+    fn parse_smaps(content: &str) -> Vec<MemoryRegion> {
+        let mut regions = Vec::new();
+        let mut fields: HashMap<String, u64> = HashMap::new();
+        let mut header: Option<(u64, u64, String, Option<String>)> = None;
+
+        for line in content.lines() {
+            if let Some(parsed) = parse_header(line) {
+                if let Some(previous) = header.take() {
+                    regions.push(region(previous, &fields));
+                }
+                fields.clear();
+                header = Some(parsed);
+            } else if let Some((key, value)) = line.split_once(':') {
+                if let Some(bytes) = parse_kb(value.trim()) {
+                    fields.insert(key.to_string(), bytes);
+                }
+            }
+        }
+        if let Some(previous) = header {
+            regions.push(region(previous, &fields));
+        }
+
+        // Bibliothèques et exécutables : fichiers dont au moins une projection est exécutable
+        let images: HashSet<String> = regions
+            .iter()
+            .filter(|region| region.permissions.as_bytes().get(2) == Some(&b'x'))
+            .filter_map(|region| region.path.clone())
+            .filter(|path| path.starts_with('/'))
+            .collect();
+        for region in &mut regions {
+            region.kind = kind(region.path.as_deref(), &images).to_string();
+        }
+
+        regions
+    }
+
+    /// Totaux agrégés par le noyau (Linux 4.14+)
+    pub fn rollup(pid: u32) -> Option<MemoryUsage> {
+        let content = fs::read_to_string(format!("/proc/{}/smaps_rollup", pid)).ok()?;
+        Some(parse_rollup(&content))
+    }
+
+    fn parse_rollup(content: &str) -> MemoryUsage {
+        let fields: HashMap<String, u64> = content
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .filter_map(|(key, value)| Some((key.to_string(), parse_kb(value.trim())?)))
+            .collect();
+        usage(&fields)
+    }
+
+    /// Les totaux du noyau remplacent les sommes, sauf la taille projetée absente de smaps_rollup
+    pub fn apply_rollup(map: &mut MemoryMap, rollup: MemoryUsage) {
+        map.total = MemoryUsage {
+            size: map.total.size,
+            ..rollup
+        };
+    }
+
+    /// "7f1c2a000000-7f1c2a021000 rw-p 00000000 00:00 0   [heap]"
+    fn parse_header(line: &str) -> Option<(u64, u64, String, Option<String>)> {
+        let mut parts = line.splitn(6, ' ');
+        let (start, end) = parts.next()?.split_once('-')?;
+        let start = u64::from_str_radix(start, 16).ok()?;
+        let end = u64::from_str_radix(end, 16).ok()?;
+        let permissions = parts.next()?.to_string();
+        // Position, périphérique, inode
+        for _ in 0..3 {
+            parts.next()?;
+        }
+        let path = parts
+            .next()
+            .map(|path| path.trim().trim_end_matches(" (deleted)").to_string())
+            .filter(|path| !path.is_empty());
+        Some((start, end, permissions, path))
+    }
+
+    fn region((start, end, permissions, path): (u64, u64, String, Option<String>), fields: &HashMap<String, u64>) -> MemoryRegion {
+        let mut usage = usage(fields);
+        usage.size = end - start;
+        MemoryRegion {
+            start_address: start,
+            end_address: end,
+            permissions,
+            kind: String::new(),
+            path,
+            usage,
+        }
+    }
+
+    fn usage(fields: &HashMap<String, u64>) -> MemoryUsage {
+        let field = |key: &str| fields.get(key).copied().unwrap_or(0);
+        MemoryUsage {
+            size: field("Size"),
+            rss: Some(field("Rss")),
+            pss: Some(field("Pss")),
+            private: field("Private_Clean") + field("Private_Dirty"),
+            shared: field("Shared_Clean") + field("Shared_Dirty"),
+            swap: Some(field("Swap")),
+        }
+    }
+
+    fn kind(path: Option<&str>, images: &HashSet<String>) -> &'static str {
+        match path {
+            None => "anonymous",
+            Some("[heap]") => "heap",
+            Some(path) if path.starts_with("[stack") => "stack",
+            // Régions anonymes nommées par prctl(PR_SET_VMA_ANON_NAME)
+            Some(path) if path.starts_with("[anon") => "anonymous",
+            Some(path) if path.starts_with('[') => "other",
+            Some(path) if images.contains(path) => "image",
+            Some(_) => "file",
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const SMAPS: &str = "\
+55d4c3a00000-55d4c3a01000 r-xp 00000000 08:01 4242                       /opt/app/app (deleted)
+Size:                  4 kB
+Rss:                   4 kB
+Pss:                   4 kB
+Shared_Clean:          0 kB
+Private_Clean:         4 kB
+55d4c3b00000-55d4c3b21000 rw-p 00000000 00:00 0                          [heap]
+Size:                132 kB
+Rss:                  64 kB
+Pss:                  64 kB
+Private_Dirty:        64 kB
+Swap:                  8 kB
+VmFlags: rd wr mr mw me ac
+7f1c2a000000-7f1c2a028000 r--p 00000000 08:01 1234                       /usr/lib/x86_64-linux-gnu/libc.so.6
+Size:                160 kB
+Rss:                 160 kB
+Pss:                  20 kB
+Shared_Clean:        160 kB
+7f1c2a028000-7f1c2a1bd000 r-xp 00028000 08:01 1234                       /usr/lib/x86_64-linux-gnu/libc.so.6
+Size:               1620 kB
+Rss:                 900 kB
+Pss:                 100 kB
+Shared_Clean:        900 kB
+7f1c2a300000-7f1c2a400000 rw-p 00000000 00:00 0                          [anon:scudo:primary]
+Size:               1024 kB
+Rss:                  12 kB
+Private_Dirty:        12 kB
+7f1c2a500000-7f1c2a501000 r--p 00000000 08:01 5678                       /home/me/My Files/cache.db (deleted)
+Size:                  4 kB
+Rss:                   4 kB
+7f1c2a600000-7f1c2a700000 rw-p 00000000 00:00 0 
+Size:               1024 kB
+Rss:                 256 kB
+Private_Dirty:       256 kB
+7ffd12300000-7ffd12321000 rw-p 00000000 00:00 0                          [stack]
+Size:                132 kB
+Rss:                  16 kB
+Private_Dirty:        16 kB
+7ffd123f0000-7ffd123f2000 r-xp 00000000 00:00 0                          [vdso]
+Size:                  8 kB
+Rss:                   4 kB
+";
+
+        #[test]
+        fn smaps_regions_are_parsed_and_classified() {
+            let regions = parse_smaps(SMAPS);
+            let summary: Vec<(&str, Option<&str>)> =
+                regions.iter().map(|region| (region.kind.as_str(), region.path.as_deref())).collect();
+            assert_eq!(
+                summary,
+                [
+                    ("image", Some("/opt/app/app")),
+                    ("heap", Some("[heap]")),
+                    // Projection non exécutable d'une bibliothèque dont une autre l'est
+                    ("image", Some("/usr/lib/x86_64-linux-gnu/libc.so.6")),
+                    ("image", Some("/usr/lib/x86_64-linux-gnu/libc.so.6")),
+                    ("anonymous", Some("[anon:scudo:primary]")),
+                    ("file", Some("/home/me/My Files/cache.db")),
+                    ("anonymous", None),
+                    ("stack", Some("[stack]")),
+                    ("other", Some("[vdso]")),
+                ]
+            );
+
+            let heap = &regions[1];
+            assert_eq!((heap.start_address, heap.end_address), (0x55d4c3b00000, 0x55d4c3b21000));
+            assert_eq!(heap.permissions, "rw-p");
+            assert_eq!(
+                heap.usage,
+                MemoryUsage {
+                    size: 0x21000,
+                    rss: Some(64 * 1024),
+                    pss: Some(64 * 1024),
+                    private: 64 * 1024,
+                    shared: 0,
+                    swap: Some(8 * 1024),
+                }
+            );
+            assert_eq!(regions[3].usage.shared, 900 * 1024);
+            assert!(parse_header("Rss:                   4 kB").is_none());
+            assert!(parse_header("VmFlags: rd ex mr mw me dw").is_none());
+        }
+
+        #[test]
+        fn rollup_totals_replace_summed_ones_except_size() {
+            let mut map = crate::metadata::memory_map::summarize(parse_smaps(SMAPS), 3);
+            assert_eq!(map.total.rss, Some((4 + 64 + 160 + 900 + 12 + 4 + 256 + 16 + 4) * 1024));
+            let size = map.total.size;
+
+            let rollup = parse_rollup(
+                "55d4c3a00000-7ffd123f2000 ---p 00000000 00:00 0                          [rollup]\n\
+                 Rss:                1500 kB\nPss:                 400 kB\nShared_Clean:        1000 kB\n\
+                 Private_Dirty:       348 kB\nPrivate_Clean:         4 kB\nSwap:                  8 kB\n",
+            );
+            apply_rollup(&mut map, rollup);
+            assert_eq!(
+                map.total,
+                MemoryUsage {
+                    size,
+                    rss: Some(1500 * 1024),
+                    pss: Some(400 * 1024),
+                    private: 352 * 1024,
+                    shared: 1000 * 1024,
+                    swap: Some(8 * 1024),
+                }
+            );
+        }
+    }
+}
+
+#[cfg(windows)]
+mod windows_regions {
+    use crate::models::{MemoryRegion, MemoryUsage};
+    use anyhow::{bail, Result};
+    use std::collections::HashSet;
+    use std::mem;
+    use winapi::shared::ntdef::HANDLE;
+    use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+    use winapi::um::memoryapi::VirtualQueryEx;
+    use winapi::um::processthreadsapi::OpenProcess;
+    use winapi::um::psapi::{GetMappedFileNameW, QueryWorkingSetEx, PSAPI_WORKING_SET_EX_INFORMATION};
+    use winapi::um::sysinfoapi::{GetSystemInfo, SYSTEM_INFO};
+    use winapi::um::tlhelp32::{CreateToolhelp32Snapshot, Heap32ListFirst, Heap32ListNext, HEAPLIST32, TH32CS_SNAPHEAPLIST};
+    use winapi::um::winnt::{
+        MEMORY_BASIC_INFORMATION, MEM_COMMIT, MEM_FREE, MEM_IMAGE, MEM_MAPPED, MEM_PRIVATE, PAGE_EXECUTE,
+        PAGE_EXECUTE_READ, PAGE_EXECUTE_READWRITE, PAGE_EXECUTE_WRITECOPY, PAGE_GUARD, PAGE_READONLY, PAGE_READWRITE,
+        PAGE_WRITECOPY, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ,
+    };
+
+    // Pages interrogées par appel à QueryWorkingSetEx (16 octets par page)
+    const WORKING_SET_BATCH: usize = 16 * 1024;
+
+    pub unsafe fn collect(pid: u32) -> Result<Vec<MemoryRegion>> {
+        let process = OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, 0, pid);
+        if process.is_null() {
+            bail!("Accès refusé à la carte mémoire du PID {}", pid);
+        }
+
+        let regions = collect_regions(process, pid);
+        CloseHandle(process);
+        regions
+    }
+
+    unsafe fn collect_regions(process: HANDLE, pid: u32) -> Result<Vec<MemoryRegion>> {
+        let page_size = page_size();
+        let raw = query_regions(process);
+        let heaps = heap_bases(pid);
+        // Piles des threads : réservation privée dont une page porte PAGE_GUARD
+        let stacks: HashSet<usize> = raw
+            .iter()
+            .filter(|info| info.Type == MEM_PRIVATE && info.Protect & PAGE_GUARD != 0)
+            .map(|info| info.AllocationBase as usize)
+            .collect();
+
+        let mut regions = Vec::new();
+        for info in raw.iter().filter(|info| info.State == MEM_COMMIT) {
+            let base = info.AllocationBase as usize;
+            let path = if info.Type == MEM_IMAGE || info.Type == MEM_MAPPED {
+                mapped_file_name(process, info.BaseAddress as usize)
+            } else {
+                None
+            };
+            let kind = match info.Type {
+                MEM_IMAGE => "image",
+                MEM_MAPPED if path.is_some() => "file",
+                MEM_MAPPED => "other",
+                _ if heaps.contains(&base) => "heap",
+                _ if stacks.contains(&base) => "stack",
+                _ => "anonymous",
+            };
+            let size = info.RegionSize as u64;
+            let is_private = info.Type == MEM_PRIVATE;
+            // Échec ponctuel (région libérée entre-temps) : résident inconnu pour cette région seulement
+            let residency = residency(process, info.BaseAddress as usize, info.RegionSize, page_size).ok();
+
+            regions.push(MemoryRegion {
+                start_address: info.BaseAddress as u64,
+                end_address: info.BaseAddress as u64 + size,
+                permissions: permissions(info.Protect, is_private),
+                kind: kind.to_string(),
+                path,
+                usage: MemoryUsage {
+                    size,
+                    rss: residency.as_ref().map(|residency| residency.resident),
+                    pss: None,
+                    private: residency.as_ref().map_or(0, |residency| residency.private),
+                    shared: residency.as_ref().map_or(0, |residency| residency.shared),
+                    swap: None,
+                },
+            });
+        }
+
+        Ok(regions)
+    }
+
+    /// Octets résidents d'une région, répartis comme Private_* / Shared_* de smaps
+    #[derive(Default)]
+    struct Residency {
+        resident: u64,
+        private: u64,
+        shared: u64,
+    }
+
+    /// Présence de chaque page dans le working set (QueryWorkingSetEx)
+    unsafe fn residency(process: HANDLE, base: usize, size: usize, page_size: usize) -> Result<Residency> {
+        let mut residency = Residency::default();
+        let pages = size / page_size;
+        let mut entries: Vec<PSAPI_WORKING_SET_EX_INFORMATION> = Vec::with_capacity(pages.min(WORKING_SET_BATCH));
+
+        for first in (0..pages).step_by(WORKING_SET_BATCH) {
+            entries.clear();
+            for page in first..(first + WORKING_SET_BATCH).min(pages) {
+                let mut entry: PSAPI_WORKING_SET_EX_INFORMATION = mem::zeroed();
+                entry.VirtualAddress = (base + page * page_size) as *mut _;
+                entries.push(entry);
+            }
+            let length = entries.len() * mem::size_of::<PSAPI_WORKING_SET_EX_INFORMATION>();
+            if QueryWorkingSetEx(process, entries.as_mut_ptr() as *mut _, length as u32) == 0 {
+                bail!("QueryWorkingSetEx: {}", std::io::Error::last_os_error());
+            }
+
+            for entry in &entries {
+                let attributes = &entry.VirtualAttributes;
+                if attributes.Valid() == 0 {
+                    continue;
+                }
+                residency.resident += page_size as u64;
+                // Partagée seulement si un autre processus la projette aussi (ShareCount sature à 7)
+                if attributes.Shared() != 0 && attributes.ShareCount() > 1 {
+                    residency.shared += page_size as u64;
+                } else {
+                    residency.private += page_size as u64;
+                }
+            }
+        }
+        Ok(residency)
+    }
+
+    unsafe fn page_size() -> usize {
+        let mut info: SYSTEM_INFO = mem::zeroed();
+        GetSystemInfo(&mut info);
+        (info.dwPageSize as usize).max(4096)
+    }
+
+    unsafe fn query_regions(process: HANDLE) -> Vec<MEMORY_BASIC_INFORMATION> {
+        let mut regions = Vec::new();
+        let mut address = 0usize;
+        loop {
+            let mut info: MEMORY_BASIC_INFORMATION = mem::zeroed();
+            let written = VirtualQueryEx(process, address as *const _, &mut info, mem::size_of::<MEMORY_BASIC_INFORMATION>());
+            if written == 0 || info.RegionSize == 0 {
+                break;
+            }
+            address = info.BaseAddress as usize + info.RegionSize;
+            if info.State != MEM_FREE {
+                regions.push(info);
+            }
+        }
+        regions
+    }
+
+    /// Adresses de base des tas (l'identifiant Toolhelp d'un tas est son adresse)
+    unsafe fn heap_bases(pid: u32) -> HashSet<usize> {
+        let mut heaps = HashSet::new();
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPHEAPLIST, pid);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return heaps;
+        }
+        let mut entry: HEAPLIST32 = mem::zeroed();
+        entry.dwSize = mem::size_of::<HEAPLIST32>();
+        if Heap32ListFirst(snapshot, &mut entry) != 0 {
+            loop {
+                heaps.insert(entry.th32HeapID);
+                if Heap32ListNext(snapshot, &mut entry) == 0 {
+                    break;
+                }
+            }
+        }
+        CloseHandle(snapshot);
+        heaps
+    }
+
+    /// Chemin NT du fichier projeté ("\Device\HarddiskVolume3\Windows\System32\ntdll.dll")
+    unsafe fn mapped_file_name(process: HANDLE, address: usize) -> Option<String> {
+        let mut buffer = [0u16; 1024];
+        let length = GetMappedFileNameW(process, address as *mut _, buffer.as_mut_ptr(), buffer.len() as u32);
+        (length > 0).then(|| String::from_utf16_lossy(&buffer[..length as usize]))
+    }
+
+    /// Même notation que /proc/<pid>/maps
+    fn permissions(protect: u32, is_private: bool) -> String {
+        let (read, write, execute) = match protect & 0xFF {
+            PAGE_READONLY => ('r', '-', '-'),
+            PAGE_READWRITE | PAGE_WRITECOPY => ('r', 'w', '-'),
+            PAGE_EXECUTE => ('-', '-', 'x'),
+            PAGE_EXECUTE_READ => ('r', '-', 'x'),
+            PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY => ('r', 'w', 'x'),
+            _ => ('-', '-', '-'),
+        };
+        let sharing = if is_private { 'p' } else { 's' };
+        [read, write, execute, sharing].iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(start_address: u64, kind: &str, size: u64, rss: Option<u64>) -> MemoryRegion {
+        MemoryRegion {
+            start_address,
+            end_address: start_address + size,
+            permissions: "rw-p".to_string(),
+            kind: kind.to_string(),
+            path: None,
+            usage: MemoryUsage {
+                size,
+                rss,
+                private: rss.unwrap_or(0),
+                ..MemoryUsage::default()
+            },
+        }
+    }
+
+    #[test]
+    fn largest_regions_are_kept_in_address_order() {
+        let regions = vec![
+            region(0x4000, "heap", 100, Some(10)),
+            region(0x1000, "image", 100, Some(30)),
+            region(0x3000, "anonymous", 100, Some(20)),
+            // Résident inconnu : la taille sert au classement
+            region(0x2000, "other", 25, None),
+        ];
+
+        let map = summarize(regions, 3);
+        assert_eq!(map.region_count, 4);
+        let kept: Vec<u64> = map.regions.iter().map(|region| region.start_address).collect();
+        assert_eq!(kept, [0x1000, 0x2000, 0x3000]);
+
+        assert_eq!(map.total.size, 325);
+        assert_eq!(map.total.rss, Some(60));
+        assert_eq!(map.total.pss, None);
+        let kinds: Vec<(&str, usize)> = map.breakdown.iter().map(|category| (category.kind.as_str(), category.region_count)).collect();
+        assert_eq!(kinds, [("heap", 1), ("image", 1), ("anonymous", 1), ("other", 1)]);
+
+        assert!(summarize(Vec::new(), 3).breakdown.is_empty());
+        assert!(summarize(vec![region(0x1000, "heap", 1, None)], 0).regions.is_empty());
+    }
+}
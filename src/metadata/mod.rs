@@ -1,5 +1,20 @@
 pub mod process_metadata;
+#[cfg(windows)]
 pub mod media_control;
+#[cfg(windows)]
+pub mod media_worker;
+#[cfg(windows)]
+pub mod process_parameters;
+pub mod browser_tab;
+pub mod command_line;
+pub mod handles;
+pub mod memory_map;
+pub mod network;
+pub mod owner;
 
 pub use process_metadata::ProcessMetadataCollector;
+#[cfg(windows)]
 pub use media_control::MediaControlCollector;
+#[cfg(windows)]
+pub use media_worker::MediaWorker;
+pub use browser_tab::{BrowserTitleParser, BrowserFamily, SiteCategories, SiteCategory};
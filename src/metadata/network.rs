@@ -0,0 +1,375 @@
+use crate::models::NetworkConnection;
+use anyhow::Result;
+
+/// Connexions TCP/UDP (IPv4 et IPv6) appartenant à un processus
+pub fn collect_connections(pid: u32) -> Result<Vec<NetworkConnection>> {
+    #[cfg(windows)]
+    let connections = unsafe { windows_tables::collect(pid) };
+    #[cfg(target_os = "linux")]
+    let connections = linux_sockets::collect(pid);
+
+    connections
+}
+
+/// Adresse distante d'un socket : absente pour un socket en écoute ou non connecté
+fn remote_endpoint(address: std::net::IpAddr, port: u16) -> (Option<String>, Option<u16>) {
+    if address.is_unspecified() && port == 0 {
+        (None, None)
+    } else {
+        (Some(address.to_string()), Some(port))
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux_sockets {
+    use super::remote_endpoint;
+    use crate::models::NetworkConnection;
+    use anyhow::Result;
+    use std::collections::HashSet;
+    use std::fs;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    // (fichier sous /proc/<pid>/net, protocole, TCP ?)
+    const TABLES: [(&str, &str, bool); 4] = [
+        ("tcp", "tcp", true),
+        ("tcp6", "tcp6", true),
+        ("udp", "udp", false),
+        ("udp6", "udp6", false),
+    ];
+
+    pub fn collect(pid: u32) -> Result<Vec<NetworkConnection>> {
+        let inodes = socket_inodes(pid)?;
+        if inodes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // /proc/<pid>/net : tables de l'espace de noms réseau du processus (= /proc/net hors conteneur)
+        let mut connections = Vec::new();
+        for (file, protocol, is_tcp) in TABLES {
+            let Ok(content) = fs::read_to_string(format!("/proc/{}/net/{}", pid, file)) else {
+                // IPv6 désactivé : pas de table tcp6/udp6
+                continue;
+            };
+
+            connections.extend(
+                content
+                    .lines()
+                    .skip(1)
+                    .filter_map(|line| parse_line(line, protocol, is_tcp, pid))
+                    .filter(|connection| connection.inode.is_some_and(|inode| inodes.contains(&inode))),
+            );
+        }
+
+        Ok(connections)
+    }
+
+    /// Inodes des sockets ouverts par le processus ("socket:[12345]" dans /proc/<pid>/fd)
+    fn socket_inodes(pid: u32) -> Result<HashSet<u64>> {
+        let entries = fs::read_dir(format!("/proc/{}/fd", pid)).map_err(|e| match e.kind() {
+            std::io::ErrorKind::PermissionDenied => {
+                anyhow::anyhow!("Accès refusé aux descripteurs du PID {}", pid)
+            }
+            _ => anyhow::anyhow!("Impossible de lire /proc/{}/fd: {}", pid, e),
+        })?;
+
+        Ok(entries
+            .flatten()
+            .filter_map(|entry| fs::read_link(entry.path()).ok())
+            .filter_map(|target| {
+                target
+                    .to_str()?
+                    .strip_prefix("socket:[")?
+                    .strip_suffix(']')?
+                    .parse()
+                    .ok()
+            })
+            .collect())
+    }
+
+    /// "  0: 0100007F:0035 00000000:0000 0A 00000000:00000000 00:00000000 00000000   101        0 12345 ..."
+    fn parse_line(line: &str, protocol: &str, is_tcp: bool, pid: u32) -> Option<NetworkConnection> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 {
+            return None;
+        }
+
+        let (local_address, local_port) = parse_endpoint(fields[1])?;
+        let (remote_address, remote_port) = parse_endpoint(fields[2])?;
+        let (remote_address, remote_port) = remote_endpoint(remote_address, remote_port);
+        let state = if is_tcp {
+            Some(tcp_state(u8::from_str_radix(fields[3], 16).ok()?).to_string())
+        } else {
+            None
+        };
+
+        Some(NetworkConnection {
+            protocol: protocol.to_string(),
+            local_address: local_address.to_string(),
+            local_port,
+            remote_address,
+            remote_port,
+            state,
+            inode: fields[9].parse().ok(),
+            pid,
+        })
+    }
+
+    /// "0100007F:0035" : adresse en mots de 32 bits dans l'ordre de la machine, port en hexadécimal
+    fn parse_endpoint(text: &str) -> Option<(IpAddr, u16)> {
+        let (address, port) = text.split_once(':')?;
+        let port = u16::from_str_radix(port, 16).ok()?;
+
+        let mut bytes = Vec::with_capacity(16);
+        for index in (0..address.len()).step_by(8) {
+            let word = u32::from_str_radix(address.get(index..index + 8)?, 16).ok()?;
+            bytes.extend_from_slice(&word.to_ne_bytes());
+        }
+
+        let address = match bytes.len() {
+            4 => IpAddr::V4(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3])),
+            16 => {
+                let v6 = Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?);
+                // Adresse IPv4 mappée (::ffff:a.b.c.d) : affichée en IPv4
+                v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(IpAddr::V6(v6))
+            }
+            _ => return None,
+        };
+        Some((address, port))
+    }
+
+    // Codes de include/net/tcp_states.h
+    fn tcp_state(code: u8) -> &'static str {
+        match code {
+            0x01 => "ESTABLISHED",
+            0x02 => "SYN_SENT",
+            0x03 => "SYN_RECV",
+            0x04 => "FIN_WAIT1",
+            0x05 => "FIN_WAIT2",
+            0x06 => "TIME_WAIT",
+            0x07 => "CLOSE",
+            0x08 => "CLOSE_WAIT",
+            0x09 => "LAST_ACK",
+            0x0A => "LISTEN",
+            0x0B => "CLOSING",
+            0x0C => "NEW_SYN_RECV",
+            _ => "UNKNOWN",
+        }
+    }
+
+    // Lignes de /proc/net/tcp[6] relevées sur une machine little-endian (mots de 32 bits en ordre natif)
+    #[cfg(all(test, target_endian = "little"))]
+    mod tests {
+        use super::*;
+
+        const TRAILER: &str = "00000000:00000000 00:00000000 00000000  1000        0";
+
+        fn line(local: &str, remote: &str, state: &str, inode: u64) -> String {
+            format!("   0: {} {} {} {} {} 1 0000000000000000 20 4 30 10 -1", local, remote, state, TRAILER, inode)
+        }
+
+        #[test]
+        fn ipv4_sockets() {
+            let listening = parse_line(&line("0100007F:0035", "00000000:0000", "0A", 12345), "tcp", true, 7).unwrap();
+            assert_eq!((listening.local_address.as_str(), listening.local_port), ("127.0.0.1", 53));
+            assert_eq!((listening.remote_address, listening.remote_port), (None, None));
+            assert_eq!(listening.state.as_deref(), Some("LISTEN"));
+            assert_eq!((listening.inode, listening.pid), (Some(12345), 7));
+
+            let established = parse_line(&line("0F02000A:C350", "22D8B85D:01BB", "01", 67890), "tcp", true, 7).unwrap();
+            assert_eq!((established.local_address.as_str(), established.local_port), ("10.0.2.15", 50000));
+            assert_eq!(established.remote_address.as_deref(), Some("93.184.216.34"));
+            assert_eq!(established.remote_port, Some(443));
+            assert_eq!(established.state.as_deref(), Some("ESTABLISHED"));
+
+            // UDP : pas d'état ; port distant seul renseigné = adresse conservée
+            let udp = parse_line(&line("00000000:14E9", "00000000:0000", "07", 111), "udp", false, 7).unwrap();
+            assert_eq!((udp.local_address.as_str(), udp.local_port, udp.state), ("0.0.0.0", 5353, None));
+            assert_eq!(udp.remote_address, None);
+            let half = parse_line(&line("00000000:14E9", "00000000:0035", "07", 111), "udp", false, 7).unwrap();
+            assert_eq!((half.remote_address.as_deref(), half.remote_port), (Some("0.0.0.0"), Some(53)));
+        }
+
+        #[test]
+        fn ipv6_sockets() {
+            let loopback = parse_line(
+                &line("00000000000000000000000001000000:1F90", "00000000000000000000000000000000:0000", "0A", 1),
+                "tcp6",
+                true,
+                7,
+            )
+            .unwrap();
+            assert_eq!((loopback.local_address.as_str(), loopback.local_port), ("::1", 8080));
+            assert_eq!((loopback.remote_address, loopback.remote_port), (None, None));
+
+            let connected = parse_line(
+                &line("0000000000000000FFFF00000100007F:A2B4", "B80D0120000000000000000001000000:01BB", "08", 2),
+                "tcp6",
+                true,
+                7,
+            )
+            .unwrap();
+            // IPv4 mappée affichée en IPv4
+            assert_eq!((connected.local_address.as_str(), connected.local_port), ("127.0.0.1", 41652));
+            assert_eq!(connected.remote_address.as_deref(), Some("2001:db8::1"));
+            assert_eq!(connected.state.as_deref(), Some("CLOSE_WAIT"));
+        }
+
+        #[test]
+        fn malformed_lines_are_skipped() {
+            assert!(parse_line("  sl  local_address rem_address   st tx_queue rx_queue", "tcp", true, 7).is_none());
+            assert!(parse_line(&line("0100007F:ZZZZ", "00000000:0000", "0A", 1), "tcp", true, 7).is_none());
+            assert!(parse_line(&line("0100007F00:0035", "00000000:0000", "0A", 1), "tcp", true, 7).is_none());
+            assert!(parse_line(&line("0100007F:0035", "00000000:0000", "XY", 1), "tcp", true, 7).is_none());
+            assert_eq!(tcp_state(0x0C), "NEW_SYN_RECV");
+            assert_eq!(tcp_state(0x42), "UNKNOWN");
+        }
+    }
+}
+
+#[cfg(windows)]
+mod windows_tables {
+    use super::remote_endpoint;
+    use crate::models::NetworkConnection;
+    use anyhow::{bail, Result};
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    use std::ptr::null_mut;
+    use winapi::shared::iprtrmib::{TCP_TABLE_OWNER_PID_ALL, UDP_TABLE_OWNER_PID};
+    use winapi::shared::tcpmib::{MIB_TCP6ROW_OWNER_PID, MIB_TCPROW_OWNER_PID};
+    use winapi::shared::udpmib::{MIB_UDP6ROW_OWNER_PID, MIB_UDPROW_OWNER_PID};
+    use winapi::shared::winerror::{ERROR_INSUFFICIENT_BUFFER, NO_ERROR};
+    use winapi::shared::ws2def::{AF_INET, AF_INET6};
+    use winapi::um::iphlpapi::{GetExtendedTcpTable, GetExtendedUdpTable};
+
+    pub unsafe fn collect(pid: u32) -> Result<Vec<NetworkConnection>> {
+        let mut connections = Vec::new();
+
+        for row in table::<MIB_TCPROW_OWNER_PID>(|buffer, size| {
+            GetExtendedTcpTable(buffer, size, 0, AF_INET as u32, TCP_TABLE_OWNER_PID_ALL, 0)
+        })? {
+            if row.dwOwningPid != pid {
+                continue;
+            }
+            let (remote_address, remote_port) =
+                remote_endpoint(ipv4(row.dwRemoteAddr), port(row.dwRemotePort));
+            connections.push(NetworkConnection {
+                protocol: "tcp".to_string(),
+                local_address: ipv4(row.dwLocalAddr).to_string(),
+                local_port: port(row.dwLocalPort),
+                remote_address,
+                remote_port,
+                state: Some(tcp_state(row.dwState).to_string()),
+                inode: None,
+                pid,
+            });
+        }
+
+        for row in table::<MIB_TCP6ROW_OWNER_PID>(|buffer, size| {
+            GetExtendedTcpTable(buffer, size, 0, AF_INET6 as u32, TCP_TABLE_OWNER_PID_ALL, 0)
+        })? {
+            if row.dwOwningPid != pid {
+                continue;
+            }
+            let (remote_address, remote_port) =
+                remote_endpoint(ipv6(row.ucRemoteAddr), port(row.dwRemotePort));
+            connections.push(NetworkConnection {
+                protocol: "tcp6".to_string(),
+                local_address: ipv6(row.ucLocalAddr).to_string(),
+                local_port: port(row.dwLocalPort),
+                remote_address,
+                remote_port,
+                state: Some(tcp_state(row.dwState).to_string()),
+                inode: None,
+                pid,
+            });
+        }
+
+        // UDP : pas d'adresse distante dans les tables du système
+        for row in table::<MIB_UDPROW_OWNER_PID>(|buffer, size| {
+            GetExtendedUdpTable(buffer, size, 0, AF_INET as u32, UDP_TABLE_OWNER_PID, 0)
+        })? {
+            if row.dwOwningPid == pid {
+                connections.push(udp_connection("udp", ipv4(row.dwLocalAddr), port(row.dwLocalPort), pid));
+            }
+        }
+
+        for row in table::<MIB_UDP6ROW_OWNER_PID>(|buffer, size| {
+            GetExtendedUdpTable(buffer, size, 0, AF_INET6 as u32, UDP_TABLE_OWNER_PID, 0)
+        })? {
+            if row.dwOwningPid == pid {
+                connections.push(udp_connection("udp6", ipv6(row.ucLocalAddr), port(row.dwLocalPort), pid));
+            }
+        }
+
+        Ok(connections)
+    }
+
+    /// Tables MIB_*TABLE_OWNER_PID : dwNumEntries puis les lignes (alignées sur 4 octets)
+    unsafe fn table<Row: Copy>(query: impl Fn(*mut core::ffi::c_void, &mut u32) -> u32) -> Result<Vec<Row>> {
+        let mut size = 0u32;
+        let mut buffer: Vec<u32> = Vec::new();
+        // La table peut grossir entre l'appel de dimensionnement et la lecture
+        for _ in 0..4 {
+            let status = query(
+                if buffer.is_empty() { null_mut() } else { buffer.as_mut_ptr() as *mut _ },
+                &mut size,
+            );
+            match status {
+                NO_ERROR if !buffer.is_empty() => {
+                    let count = buffer[0] as usize;
+                    let rows = buffer.as_ptr().add(1) as *const Row;
+                    let capacity = (buffer.len() - 1) * 4 / std::mem::size_of::<Row>();
+                    return Ok((0..count.min(capacity)).map(|index| *rows.add(index)).collect());
+                }
+                NO_ERROR => return Ok(Vec::new()),
+                ERROR_INSUFFICIENT_BUFFER => buffer = vec![0; size as usize / 4 + 1],
+                error => bail!("Lecture de la table des connexions impossible (erreur {})", error),
+            }
+        }
+        bail!("Table des connexions instable")
+    }
+
+    fn udp_connection(protocol: &str, address: IpAddr, local_port: u16, pid: u32) -> NetworkConnection {
+        NetworkConnection {
+            protocol: protocol.to_string(),
+            local_address: address.to_string(),
+            local_port,
+            remote_address: None,
+            remote_port: None,
+            state: None,
+            inode: None,
+            pid,
+        }
+    }
+
+    // Adresses et ports stockés dans l'ordre réseau
+    fn ipv4(address: u32) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::from(address.to_ne_bytes()))
+    }
+
+    fn ipv6(address: [u8; 16]) -> IpAddr {
+        IpAddr::V6(Ipv6Addr::from(address))
+    }
+
+    fn port(value: u32) -> u16 {
+        u16::from_be(value as u16)
+    }
+
+    // MIB_TCP_STATE, avec les noms utilisés sous Linux
+    fn tcp_state(state: u32) -> &'static str {
+        match state {
+            1 => "CLOSE",
+            2 => "LISTEN",
+            3 => "SYN_SENT",
+            4 => "SYN_RECV",
+            5 => "ESTABLISHED",
+            6 => "FIN_WAIT1",
+            7 => "FIN_WAIT2",
+            8 => "CLOSE_WAIT",
+            9 => "CLOSING",
+            10 => "LAST_ACK",
+            11 => "TIME_WAIT",
+            12 => "DELETE_TCB",
+            _ => "UNKNOWN",
+        }
+    }
+}
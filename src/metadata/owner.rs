@@ -0,0 +1,281 @@
+use crate::models::OwnerInfo;
+use anyhow::Result;
+
+/// Utilisateur, groupe, session et privilèges d'un processus
+pub fn owner_info(pid: u32) -> Result<OwnerInfo> {
+    #[cfg(windows)]
+    let info = unsafe { windows_token::owner_info(pid) };
+    #[cfg(target_os = "linux")]
+    let info = linux_owner::owner_info(pid);
+
+    info
+}
+
+/// Identifiant du propriétaire seul (UID effectif ou SID), sans résolution de nom
+pub fn process_user_id(pid: u32) -> Option<String> {
+    #[cfg(windows)]
+    let user_id = unsafe { windows_token::user_sid(pid) };
+    #[cfg(target_os = "linux")]
+    let user_id = linux_owner::effective_uid(pid).map(|uid| uid.to_string());
+
+    user_id
+}
+
+/// Identifiant de l'utilisateur qui exécute le tracker
+pub fn current_user_id() -> Option<String> {
+    #[cfg(windows)]
+    let user_id = process_user_id(std::process::id());
+    #[cfg(target_os = "linux")]
+    let user_id = Some(unsafe { libc::geteuid() }.to_string());
+
+    user_id
+}
+
+#[cfg(target_os = "linux")]
+mod linux_owner {
+    use crate::linux::procfs;
+    use crate::models::OwnerInfo;
+    use anyhow::{anyhow, Result};
+    use std::ffi::CStr;
+    use std::fs;
+    use std::os::unix::fs::MetadataExt;
+    use std::{mem, ptr};
+
+    // Bits de capacités (linux/capability.h), dans l'ordre
+    const CAPABILITY_NAMES: [&str; 41] = [
+        "cap_chown", "cap_dac_override", "cap_dac_read_search", "cap_fowner", "cap_fsetid", "cap_kill",
+        "cap_setgid", "cap_setuid", "cap_setpcap", "cap_linux_immutable", "cap_net_bind_service",
+        "cap_net_broadcast", "cap_net_admin", "cap_net_raw", "cap_ipc_lock", "cap_ipc_owner", "cap_sys_module",
+        "cap_sys_rawio", "cap_sys_chroot", "cap_sys_ptrace", "cap_sys_pacct", "cap_sys_admin", "cap_sys_boot",
+        "cap_sys_nice", "cap_sys_resource", "cap_sys_time", "cap_sys_tty_config", "cap_mknod", "cap_lease",
+        "cap_audit_write", "cap_audit_control", "cap_setfcap", "cap_mac_override", "cap_mac_admin", "cap_syslog",
+        "cap_wake_alarm", "cap_block_suspend", "cap_audit_read", "cap_perfmon", "cap_bpf", "cap_checkpoint_restore",
+    ];
+
+    // /proc/<pid>/sessionid vaut (u32)-1 hors session d'audit (services, threads noyau)
+    const NO_SESSION: u32 = u32::MAX;
+    const SETUID_BITS: u32 = 0o6000;
+
+    pub fn owner_info(pid: u32) -> Result<OwnerInfo> {
+        let status = procfs::read_status(pid).ok_or_else(|| anyhow!("Impossible de lire /proc/{}/status", pid))?;
+        // Uid/Gid : réel, effectif, sauvegardé, système de fichiers
+        let ids = |key: &str| -> Vec<u32> {
+            status
+                .get(key)
+                .map(|value| value.split_whitespace().filter_map(|id| id.parse().ok()).collect())
+                .unwrap_or_default()
+        };
+        let uids = ids("Uid");
+        let gids = ids("Gid");
+        let (real_uid, effective_uid) = (uids.first().copied(), uids.get(1).copied());
+        let (real_gid, effective_gid) = (gids.first().copied(), gids.get(1).copied());
+
+        let effective_capabilities = status
+            .get("CapEff")
+            .and_then(|value| u64::from_str_radix(value, 16).ok())
+            .unwrap_or(0);
+        let capabilities: Vec<String> = CAPABILITY_NAMES
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| effective_capabilities & (1 << bit) != 0)
+            .map(|(_, name)| name.to_string())
+            .collect();
+
+        let setuid_binary = procfs::exe_path(pid)
+            .and_then(|path| fs::metadata(path).ok())
+            .is_some_and(|metadata| metadata.mode() & SETUID_BITS != 0);
+
+        Ok(OwnerInfo {
+            user_id: effective_uid.map(|uid| uid.to_string()),
+            user_name: effective_uid.and_then(user_name),
+            real_user_id: real_uid.map(|uid| uid.to_string()),
+            group_id: effective_gid.map(|gid| gid.to_string()),
+            group_name: effective_gid.and_then(group_name),
+            session_id: fs::read_to_string(format!("/proc/{}/sessionid", pid))
+                .ok()
+                .and_then(|id| id.trim().parse().ok())
+                .filter(|id| *id != NO_SESSION),
+            is_elevated: effective_uid == Some(0) || effective_capabilities != 0,
+            integrity_level: None,
+            capabilities,
+            is_setuid: setuid_binary || real_uid != effective_uid || real_gid != effective_gid,
+        })
+    }
+
+    pub fn effective_uid(pid: u32) -> Option<u32> {
+        let status = procfs::read_status(pid)?;
+        status.get("Uid")?.split_whitespace().nth(1)?.parse().ok()
+    }
+
+    /// Nom via NSS (getpwuid_r) : couvre aussi les comptes LDAP/SSSD absents de /etc/passwd
+    fn user_name(uid: u32) -> Option<String> {
+        unsafe {
+            let mut passwd: libc::passwd = mem::zeroed();
+            let mut buffer = vec![0 as libc::c_char; 16 * 1024];
+            let mut result = ptr::null_mut();
+            let status = libc::getpwuid_r(uid, &mut passwd, buffer.as_mut_ptr(), buffer.len(), &mut result);
+            if status != 0 || result.is_null() {
+                return None;
+            }
+            Some(CStr::from_ptr(passwd.pw_name).to_string_lossy().to_string())
+        }
+    }
+
+    fn group_name(gid: u32) -> Option<String> {
+        unsafe {
+            let mut group: libc::group = mem::zeroed();
+            let mut buffer = vec![0 as libc::c_char; 16 * 1024];
+            let mut result = ptr::null_mut();
+            let status = libc::getgrgid_r(gid, &mut group, buffer.as_mut_ptr(), buffer.len(), &mut result);
+            if status != 0 || result.is_null() {
+                return None;
+            }
+            Some(CStr::from_ptr(group.gr_name).to_string_lossy().to_string())
+        }
+    }
+}
+
+#[cfg(windows)]
+mod windows_token {
+    use crate::models::OwnerInfo;
+    use anyhow::{bail, Result};
+    use std::ptr::null_mut;
+    use winapi::shared::sddl::ConvertSidToStringSidW;
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::{OpenProcess, OpenProcessToken, ProcessIdToSessionId};
+    use winapi::um::securitybaseapi::{GetSidSubAuthority, GetSidSubAuthorityCount, GetTokenInformation};
+    use winapi::um::winbase::{LocalFree, LookupAccountSidW};
+    use winapi::um::winnt::{
+        TokenElevation, TokenIntegrityLevel, TokenPrimaryGroup, TokenUser, HANDLE, PROCESS_QUERY_LIMITED_INFORMATION,
+        PSID, SECURITY_MANDATORY_HIGH_RID, SECURITY_MANDATORY_LOW_RID, SECURITY_MANDATORY_MEDIUM_RID,
+        SECURITY_MANDATORY_SYSTEM_RID, SID_NAME_USE, TOKEN_ELEVATION, TOKEN_INFORMATION_CLASS, TOKEN_MANDATORY_LABEL,
+        TOKEN_PRIMARY_GROUP, TOKEN_QUERY, TOKEN_USER,
+    };
+
+    pub unsafe fn owner_info(pid: u32) -> Result<OwnerInfo> {
+        let token = open_token(pid)?;
+        let mut info = OwnerInfo::default();
+
+        if let Some(buffer) = token_information(token, TokenUser) {
+            let user = &*(buffer.as_ptr() as *const TOKEN_USER);
+            info.user_id = sid_string(user.User.Sid);
+            info.user_name = account_name(user.User.Sid);
+        }
+        if let Some(buffer) = token_information(token, TokenPrimaryGroup) {
+            let group = &*(buffer.as_ptr() as *const TOKEN_PRIMARY_GROUP);
+            info.group_id = sid_string(group.PrimaryGroup);
+            info.group_name = account_name(group.PrimaryGroup);
+        }
+        if let Some(buffer) = token_information(token, TokenElevation) {
+            let elevation = &*(buffer.as_ptr() as *const TOKEN_ELEVATION);
+            info.is_elevated = elevation.TokenIsElevated != 0;
+        }
+        if let Some(buffer) = token_information(token, TokenIntegrityLevel) {
+            let label = &*(buffer.as_ptr() as *const TOKEN_MANDATORY_LABEL);
+            info.integrity_level = Some(integrity_name(integrity_rid(label.Label.Sid)).to_string());
+        }
+        CloseHandle(token);
+
+        let mut session_id = 0u32;
+        if ProcessIdToSessionId(pid, &mut session_id) != 0 {
+            info.session_id = Some(session_id);
+        }
+        Ok(info)
+    }
+
+    pub unsafe fn user_sid(pid: u32) -> Option<String> {
+        let token = open_token(pid).ok()?;
+        let sid = token_information(token, TokenUser)
+            .and_then(|buffer| sid_string((*(buffer.as_ptr() as *const TOKEN_USER)).User.Sid));
+        CloseHandle(token);
+        sid
+    }
+
+    unsafe fn open_token(pid: u32) -> Result<HANDLE> {
+        // PROCESS_QUERY_LIMITED_INFORMATION suffit au jeton, même pour les processus élevés
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            bail!("Accès refusé au processus {}", pid);
+        }
+        let mut token: HANDLE = null_mut();
+        let opened = OpenProcessToken(process, TOKEN_QUERY, &mut token);
+        CloseHandle(process);
+        if opened == 0 {
+            bail!("Jeton du processus {} inaccessible", pid);
+        }
+        Ok(token)
+    }
+
+    /// Tampon aligné sur 8 octets : les structures retournées contiennent des pointeurs
+    unsafe fn token_information(token: HANDLE, class: TOKEN_INFORMATION_CLASS) -> Option<Vec<u64>> {
+        let mut size = 0u32;
+        GetTokenInformation(token, class, null_mut(), 0, &mut size);
+        if size == 0 {
+            return None;
+        }
+        let mut buffer = vec![0u64; (size as usize).div_ceil(8)];
+        if GetTokenInformation(token, class, buffer.as_mut_ptr() as *mut _, size, &mut size) == 0 {
+            return None;
+        }
+        Some(buffer)
+    }
+
+    /// "S-1-5-21-..."
+    unsafe fn sid_string(sid: PSID) -> Option<String> {
+        let mut string = null_mut();
+        if ConvertSidToStringSidW(sid, &mut string) == 0 || string.is_null() {
+            return None;
+        }
+        let length = (0..).take_while(|&index| *string.add(index) != 0).count();
+        let value = String::from_utf16_lossy(std::slice::from_raw_parts(string, length));
+        LocalFree(string as *mut _);
+        Some(value)
+    }
+
+    /// "DOMAINE\\nom" (les comptes de domaine hors ligne restent sans nom)
+    unsafe fn account_name(sid: PSID) -> Option<String> {
+        let mut name = [0u16; 256];
+        let mut domain = [0u16; 256];
+        let mut name_length = name.len() as u32;
+        let mut domain_length = domain.len() as u32;
+        let mut use_type: SID_NAME_USE = 0;
+        if LookupAccountSidW(
+            null_mut(),
+            sid,
+            name.as_mut_ptr(),
+            &mut name_length,
+            domain.as_mut_ptr(),
+            &mut domain_length,
+            &mut use_type,
+        ) == 0
+        {
+            return None;
+        }
+        let name = String::from_utf16_lossy(&name[..name_length as usize]);
+        let domain = String::from_utf16_lossy(&domain[..domain_length as usize]);
+        Some(if domain.is_empty() { name } else { format!("{}\\{}", domain, name) })
+    }
+
+    /// Niveau d'intégrité : dernière sous-autorité du SID de l'étiquette obligatoire
+    unsafe fn integrity_rid(sid: PSID) -> u32 {
+        let count = *GetSidSubAuthorityCount(sid) as u32;
+        if count == 0 {
+            return 0;
+        }
+        *GetSidSubAuthority(sid, count - 1)
+    }
+
+    fn integrity_name(rid: u32) -> &'static str {
+        if rid < SECURITY_MANDATORY_LOW_RID {
+            "untrusted"
+        } else if rid < SECURITY_MANDATORY_MEDIUM_RID {
+            "low"
+        } else if rid < SECURITY_MANDATORY_HIGH_RID {
+            "medium"
+        } else if rid < SECURITY_MANDATORY_SYSTEM_RID {
+            "high"
+        } else {
+            "system"
+        }
+    }
+}
@@ -1,29 +1,50 @@
 use crate::models::{
-    ProcessMetadata, WindowInfo, WindowRect, ThreadInfo, ModuleInfo, 
-    MemoryInfo, CpuInfo, MetadataOptions
+    ProcessMetadata, WindowInfo, ThreadInfo, ModuleInfo, 
+    MemoryInfo, CpuInfo, IoInfo, MetadataOptions
 };
+#[cfg(windows)]
+use crate::models::WindowRect;
+use crate::binfmt::executable_info;
+use crate::metadata::browser_tab::BrowserFamily;
+use crate::metadata::command_line::analyze_command_line;
+use crate::metadata::handles::enumerate_handles;
+use crate::metadata::memory_map::collect_memory_map;
+use crate::metadata::network::collect_connections;
+use crate::metadata::owner::owner_info;
+#[cfg(target_os = "linux")]
+use crate::metadata::command_line::join_command_line;
+#[cfg(windows)]
+use crate::metadata::command_line::split_windows_command_line;
+use crate::native_messaging::BrowserTabStore;
+use crate::system_snapshot::{SnapshotCache, SystemSnapshot};
 use anyhow::Result;
 use std::collections::HashMap;
+use std::sync::Arc;
+#[cfg(windows)]
 use std::ptr::null_mut;
+#[cfg(windows)]
 use std::mem;
+#[cfg(windows)]
 use std::ffi::OsString;
+#[cfg(windows)]
 use std::os::windows::ffi::OsStringExt;
+#[cfg(windows)]
+use crate::metadata::process_parameters::ProcessParameters;
+#[cfg(windows)]
 use winapi::{
     shared::{
-        minwindef::{BOOL, DWORD, LPARAM, MAX_PATH, FILETIME},
+        minwindef::{DWORD, MAX_PATH, FILETIME},
         windef::{HWND, RECT},
         ntdef::HANDLE,
     },
     um::{
         handleapi::CloseHandle,
         tlhelp32::{
-            CreateToolhelp32Snapshot, Thread32First, Thread32Next, THREADENTRY32,
-            TH32CS_SNAPTHREAD, Module32First, Module32Next, MODULEENTRY32,
-            TH32CS_SNAPMODULE, Process32First, Process32Next, PROCESSENTRY32,
-            TH32CS_SNAPPROCESS,
+            CreateToolhelp32Snapshot, Module32First, Module32Next, MODULEENTRY32,
+            TH32CS_SNAPMODULE,
         },
         winuser::{
-            EnumWindows, GetWindowTextW, GetWindowThreadProcessId, IsWindowVisible,
+            GetWindowTextW, GetWindowThreadProcessId, IsWindowVisible,
             GetClassNameW, GetWindowRect,
         },
         processthreadsapi::{
@@ -32,19 +53,33 @@ use winapi::{
         psapi::{
             GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS,
         },
-        winbase::QueryFullProcessImageNameW,
-        winnt::{PROCESS_QUERY_INFORMATION, PROCESS_VM_READ},
+        winbase::{GetProcessIoCounters, QueryFullProcessImageNameW},
+        winnt::{IO_COUNTERS, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ},
     },
 };
 
-pub struct ProcessMetadataCollector;
+pub struct ProcessMetadataCollector {
+    snapshots: Arc<SnapshotCache>,
+}
 
 impl ProcessMetadataCollector {
     pub fn new() -> Self {
-        Self
+        Self::with_snapshot_cache(SnapshotCache::shared())
+    }
+
+    /// Lire les processus, threads et fenêtres depuis un cache donné (celui du moniteur par exemple)
+    pub fn with_snapshot_cache(snapshots: Arc<SnapshotCache>) -> Self {
+        Self { snapshots }
     }
 
+    fn snapshot(&self) -> Result<Arc<SystemSnapshot>> {
+        self.snapshots.get()
+    }
+
+    #[cfg(windows)]
     pub fn collect_all_metadata(&self, pid: u32, options: &MetadataOptions) -> Result<ProcessMetadata> {
+        let snapshot = self.snapshot()?;
+
         unsafe {
             let process_handle = OpenProcess(
                 PROCESS_QUERY_INFORMATION | PROCESS_VM_READ,
@@ -56,42 +91,27 @@ impl ProcessMetadataCollector {
                 return Err(anyhow::anyhow!("Impossible d'ouvrir le processus PID: {}", pid));
             }
 
-            let mut metadata = ProcessMetadata {
-                pid,
-                parent_pid: 0,
-                name: String::new(),
-                executable_path: None,
-                command_line: None,
-                working_directory: None,
-                window_title: None,
-                creation_time: None,
-                exit_time: None,
-                memory_info: None,
-                cpu_info: None,
-                thread_count: 0,
-                priority_class: None,
-                handle_count: 0,
-                page_fault_count: 0,
-                peak_working_set_size: 0,
-                working_set_size: 0,
-                quota_peak_paged_pool_usage: 0,
-                quota_paged_pool_usage: 0,
-                quota_peak_non_paged_pool_usage: 0,
-                quota_non_paged_pool_usage: 0,
-                pagefile_usage: 0,
-                peak_pagefile_usage: 0,
-                windows: Vec::new(),
-                threads: Vec::new(),
-                modules: Vec::new(),
-                media_sessions: Vec::new(),
-                handles: Vec::new(),
-                environment_variables: HashMap::new(),
-                raw_data: HashMap::new(),
-            };
+            let mut metadata = self.empty_metadata(pid);
+
+            // Nom, parent et threads viennent du snapshot partagé
+            if let Some(entry) = snapshot.process(pid) {
+                metadata.parent_pid = entry.parent_pid;
+                metadata.name = entry.name.clone();
+            }
+            metadata.thread_count = snapshot.threads.iter().filter(|t| t.process_id == pid).count() as u32;
 
             // Récupérer les informations selon les options
             if options.basic_info {
                 metadata.executable_path = self.get_executable_path(process_handle);
+                self.fill_command_line(&mut metadata, process_handle);
+            }
+
+            if options.executable_info {
+                let path = metadata
+                    .executable_path
+                    .clone()
+                    .or_else(|| self.get_executable_path(process_handle));
+                self.fill_executable_info(&mut metadata, path);
             }
             
             if options.memory_info {
@@ -101,6 +121,10 @@ impl ProcessMetadataCollector {
             if options.cpu_info {
                 metadata.cpu_info = self.get_cpu_info(process_handle);
             }
+
+            if options.io_info {
+                metadata.io_info = self.get_io_info(process_handle);
+            }
             
             if options.window_info {
                 metadata.windows = self.get_windows_for_process(pid)?;
@@ -115,7 +139,8 @@ impl ProcessMetadataCollector {
             }
             
             if options.environment_vars {
-                metadata.environment_variables = self.get_environment_variables(pid)?;
+                let variables = self.get_environment_variables(process_handle, &options.environment_var_names);
+                self.apply_environment(&mut metadata, variables);
             }
 
             // Récupérer le nombre de handles si demandé
@@ -123,6 +148,19 @@ impl ProcessMetadataCollector {
                 let mut handle_count = 0u32;
                 GetProcessHandleCount(process_handle, &mut handle_count);
                 metadata.handle_count = handle_count;
+                self.fill_handles(&mut metadata, pid, options);
+            }
+
+            if options.network_info {
+                self.fill_network(&mut metadata, pid);
+            }
+
+            if options.owner_info {
+                self.fill_owner(&mut metadata, pid);
+            }
+
+            if options.memory_map {
+                self.fill_memory_map(&mut metadata, pid, options);
             }
 
             // Récupérer les informations de mémoire détaillées si demandé
@@ -141,6 +179,193 @@ impl ProcessMetadataCollector {
         }
     }
 
+    fn empty_metadata(&self, pid: u32) -> ProcessMetadata {
+        ProcessMetadata {
+            pid,
+            parent_pid: 0,
+            name: String::new(),
+            executable_path: None,
+            command_line: None,
+            command_line_args: Vec::new(),
+            command_line_info: None,
+            working_directory: None,
+            executable_info: None,
+            window_title: None,
+            creation_time: None,
+            exit_time: None,
+            memory_info: None,
+            cpu_info: None,
+            io_info: None,
+            container_info: None,
+            owner_info: None,
+            memory_map: None,
+            thread_count: 0,
+            priority_class: None,
+            handle_count: 0,
+            page_fault_count: 0,
+            peak_working_set_size: 0,
+            working_set_size: 0,
+            quota_peak_paged_pool_usage: 0,
+            quota_paged_pool_usage: 0,
+            quota_peak_non_paged_pool_usage: 0,
+            quota_non_paged_pool_usage: 0,
+            pagefile_usage: 0,
+            peak_pagefile_usage: 0,
+            windows: Vec::new(),
+            threads: Vec::new(),
+            modules: Vec::new(),
+            media_sessions: Vec::new(),
+            handles: Vec::new(),
+            network_connections: Vec::new(),
+            environment_variables: HashMap::new(),
+            raw_data: HashMap::new(),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn collect_all_metadata(&self, pid: u32, options: &MetadataOptions) -> Result<ProcessMetadata> {
+        use crate::linux::procfs;
+
+        let stat = procfs::read_stat(pid)
+            .ok_or_else(|| anyhow::anyhow!("Impossible d'ouvrir le processus PID: {}", pid))?;
+
+        let mut metadata = self.empty_metadata(pid);
+        metadata.parent_pid = stat.ppid;
+        metadata.name = procfs::process_name(&stat);
+        metadata.thread_count = stat.num_threads;
+
+        if options.basic_info {
+            metadata.executable_path = procfs::exe_path(pid);
+            self.fill_command_line(&mut metadata, pid);
+        }
+
+        if options.executable_info {
+            let path = metadata.executable_path.clone().or_else(|| procfs::exe_path(pid));
+            self.fill_executable_info(&mut metadata, path);
+        }
+
+        if options.memory_info {
+            metadata.memory_info = self.get_memory_info(pid);
+        }
+
+        if options.cpu_info {
+            metadata.cpu_info = Some(self.get_cpu_info(&stat));
+        }
+
+        if options.io_info {
+            metadata.io_info = self.get_io_info(pid);
+        }
+
+        if options.window_info {
+            metadata.windows = self.get_windows_for_process(pid)?;
+        }
+
+        if options.thread_info {
+            metadata.threads = self.get_threads_for_process(pid)?;
+        }
+
+        if options.module_info {
+            metadata.modules = self.get_modules_for_process(pid)?;
+        }
+
+        if options.environment_vars {
+            let variables = self.get_environment_variables(pid, &options.environment_var_names);
+            self.apply_environment(&mut metadata, variables);
+        }
+
+        // Équivalent Linux du nombre de handles : les descripteurs ouverts
+        if options.handle_info {
+            metadata.handle_count = std::fs::read_dir(format!("/proc/{}/fd", pid))
+                .map(|entries| entries.count() as u32)
+                .unwrap_or(0);
+            self.fill_handles(&mut metadata, pid, options);
+        }
+
+        if options.network_info {
+            self.fill_network(&mut metadata, pid);
+        }
+
+        if options.owner_info {
+            self.fill_owner(&mut metadata, pid);
+        }
+
+        if options.memory_map {
+            self.fill_memory_map(&mut metadata, pid, options);
+        }
+
+        if options.container_info {
+            match crate::linux::cgroup::container_info(pid) {
+                Ok(info) => metadata.container_info = Some(info),
+                Err(e) => {
+                    metadata
+                        .raw_data
+                        .insert("container_error".to_string(), serde_json::json!(e.to_string()));
+                }
+            }
+        }
+
+        if options.memory_info {
+            if let Some(mem_info) = &metadata.memory_info {
+                metadata.peak_working_set_size = mem_info.peak_working_set_size;
+                metadata.working_set_size = mem_info.working_set_size;
+                metadata.pagefile_usage = mem_info.pagefile_usage;
+                metadata.peak_pagefile_usage = mem_info.peak_pagefile_usage;
+            }
+        }
+
+        Ok(metadata)
+    }
+
+    /// Ligne de commande et répertoire de travail lus dans le PEB ; les processus protégés
+    /// (services système, anti-triche) les refusent et gardent des champs vides
+    #[cfg(windows)]
+    fn fill_command_line(&self, metadata: &mut ProcessMetadata, process_handle: HANDLE) {
+        let Ok(parameters) = (unsafe { ProcessParameters::open(process_handle) }) else {
+            return;
+        };
+
+        if let Ok(command_line) = unsafe { parameters.command_line() } {
+            if !command_line.is_empty() {
+                metadata.command_line_args = split_windows_command_line(&command_line);
+                metadata.command_line = Some(command_line);
+            }
+        }
+
+        if let Ok(directory) = unsafe { parameters.current_directory() } {
+            if !directory.is_empty() {
+                // "C:\dossier\" -> "C:\dossier", mais "C:\" reste tel quel
+                let trimmed = if directory.ends_with(":\\") {
+                    directory.as_str()
+                } else {
+                    directory.trim_end_matches('\\')
+                };
+                metadata.working_directory = Some(trimmed.to_string());
+            }
+        }
+
+        self.fill_command_line_info(metadata);
+    }
+
+    #[cfg(target_os = "linux")]
+    fn fill_command_line(&self, metadata: &mut ProcessMetadata, pid: u32) {
+        use crate::linux::procfs;
+
+        if let Some(args) = procfs::read_cmdline(pid).filter(|args| !args.is_empty()) {
+            metadata.command_line = Some(join_command_line(&args));
+            metadata.command_line_args = args;
+        }
+        metadata.working_directory = procfs::cwd(pid);
+
+        self.fill_command_line_info(metadata);
+    }
+
+    fn fill_command_line_info(&self, metadata: &mut ProcessMetadata) {
+        if !metadata.command_line_args.is_empty() {
+            metadata.command_line_info = Some(analyze_command_line(&metadata.name, &metadata.command_line_args));
+        }
+    }
+
+    #[cfg(windows)]
     fn get_executable_path(&self, process_handle: HANDLE) -> Option<String> {
         unsafe {
             let mut buffer: [u16; MAX_PATH] = [0; MAX_PATH];
@@ -155,6 +380,7 @@ impl ProcessMetadataCollector {
         }
     }
 
+    #[cfg(windows)]
     fn get_memory_info(&self, process_handle: HANDLE) -> Option<MemoryInfo> {
         unsafe {
             let mut pmc: PROCESS_MEMORY_COUNTERS = mem::zeroed();
@@ -173,6 +399,24 @@ impl ProcessMetadataCollector {
         }
     }
 
+    // VmRSS/VmHWM pour le working set, VmSize/VmPeak pour la mémoire engagée
+    #[cfg(target_os = "linux")]
+    fn get_memory_info(&self, pid: u32) -> Option<MemoryInfo> {
+        use crate::linux::procfs::{parse_kb, read_status};
+
+        let status = read_status(pid)?;
+        let field = |key: &str| status.get(key).and_then(|value| parse_kb(value)).unwrap_or(0);
+
+        Some(MemoryInfo {
+            working_set_size: field("VmRSS"),
+            peak_working_set_size: field("VmHWM"),
+            pagefile_usage: field("VmSize"),
+            peak_pagefile_usage: field("VmPeak"),
+            private_usage: field("RssAnon") + field("VmSwap"),
+        })
+    }
+
+    #[cfg(windows)]
     fn get_cpu_info(&self, process_handle: HANDLE) -> Option<CpuInfo> {
         unsafe {
             let mut creation_time: FILETIME = mem::zeroed();
@@ -199,21 +443,63 @@ impl ProcessMetadataCollector {
         }
     }
 
-    fn get_windows_for_process(&self, pid: u32) -> Result<Vec<WindowInfo>> {
-        let mut windows = Vec::new();
-        let mut enum_data = WindowEnumData {
-            target_pid: pid,
-            windows: &mut windows,
-        };
+    // Temps convertis en unités de 100 ns comme les FILETIME Windows (création : depuis le démarrage)
+    #[cfg(target_os = "linux")]
+    fn get_cpu_info(&self, stat: &crate::linux::procfs::ProcStat) -> CpuInfo {
+        use crate::linux::procfs::ticks_to_100ns;
 
+        CpuInfo {
+            kernel_time: ticks_to_100ns(stat.stime_ticks),
+            user_time: ticks_to_100ns(stat.utime_ticks),
+            creation_time: ticks_to_100ns(stat.start_time_ticks),
+            exit_time: 0,
+        }
+    }
+
+    #[cfg(windows)]
+    fn get_io_info(&self, process_handle: HANDLE) -> Option<IoInfo> {
         unsafe {
-            EnumWindows(
-                Some(enum_windows_proc),
-                &mut enum_data as *mut _ as isize,
-            );
+            let mut counters: IO_COUNTERS = mem::zeroed();
+            if GetProcessIoCounters(process_handle, &mut counters) == 0 {
+                return None;
+            }
+
+            Some(IoInfo {
+                read_bytes: counters.ReadTransferCount,
+                write_bytes: counters.WriteTransferCount,
+                read_operations: counters.ReadOperationCount,
+                write_operations: counters.WriteOperationCount,
+                other_bytes: counters.OtherTransferCount,
+                other_operations: counters.OtherOperationCount,
+                disk_read_bytes: None,
+                disk_write_bytes: None,
+            })
         }
+    }
+
+    // rchar/wchar comptent aussi les lectures servies par le cache, read_bytes/write_bytes le stockage
+    #[cfg(target_os = "linux")]
+    fn get_io_info(&self, pid: u32) -> Option<IoInfo> {
+        let io = crate::linux::procfs::read_io(pid)?;
+        let value = |key: &str| io.get(key).copied().unwrap_or(0);
+
+        Some(IoInfo {
+            read_bytes: value("rchar"),
+            write_bytes: value("wchar"),
+            read_operations: value("syscr"),
+            write_operations: value("syscw"),
+            other_bytes: 0,
+            other_operations: 0,
+            disk_read_bytes: io.get("read_bytes").copied(),
+            // Les écritures annulées (fichier tronqué avant écriture) ne touchent pas le disque
+            disk_write_bytes: io
+                .get("write_bytes")
+                .map(|bytes| bytes.saturating_sub(value("cancelled_write_bytes"))),
+        })
+    }
 
-        Ok(windows)
+    fn get_windows_for_process(&self, pid: u32) -> Result<Vec<WindowInfo>> {
+        Ok(self.snapshot()?.windows_for(pid))
     }
 
     // NOUVELLE FONCTION : Détecter l'onglet actif d'un navigateur
@@ -223,10 +509,10 @@ impl ProcessMetadataCollector {
         // 1. D'abord, essayer de trouver la fenêtre au premier plan
         if let Some(foreground_window) = self.get_foreground_window() {
             for window in &windows {
-                if window.hwnd == foreground_window as u64 {
+                if window.hwnd == foreground_window {
                     // Vérifier si c'est une fenêtre de navigateur avec un titre
                     if self.is_browser_content_window(window) && !window.window_title.is_empty() {
-                        return Ok(Some(window.clone()));
+                        return Ok(Some(self.with_extension_tab(window.clone())));
                     }
                 }
             }
@@ -235,75 +521,99 @@ impl ProcessMetadataCollector {
         // 2. Sinon, prendre la première fenêtre visible avec un titre
         for window in &windows {
             if window.is_visible && self.is_browser_content_window(window) && !window.window_title.is_empty() {
-                return Ok(Some(window.clone()));
+                return Ok(Some(self.with_extension_tab(window.clone())));
             }
         }
         
         Ok(None)
     }
 
-    fn get_foreground_window(&self) -> Option<HWND> {
+    // Compléter avec l'URL exacte si l'extension navigateur est connectée
+    fn with_extension_tab(&self, mut window: WindowInfo) -> WindowInfo {
+        if let Some(state) = BrowserTabStore::new().load(window.process_id) {
+            window.browser_tab = state.active_tab;
+        }
+        window
+    }
+
+    #[cfg(windows)]
+    fn get_foreground_window(&self) -> Option<u64> {
         unsafe {
             let hwnd = winapi::um::winuser::GetForegroundWindow();
             if hwnd != null_mut() {
-                Some(hwnd)
+                Some(hwnd as u64)
             } else {
                 None
             }
         }
     }
 
-    fn is_browser_content_window(&self, window: &WindowInfo) -> bool {
-        // Détecter les fenêtres de contenu de navigateur
-        match window.class_name.as_str() {
-            "MozillaWindowClass" |           // Firefox
-            "Chrome_WidgetWin_1" |           // Chrome
-            "Chrome_WidgetWin_0" |           // Chrome (ancien)
-            "EdgeUiInputTopWndClass" |       // Edge
-            "ApplicationFrameWindow" |       // Edge (UWP)
-            "MozillaDropShadowWindowClass" => true, // Firefox (ombres)
-            _ => false,
-        }
+    #[cfg(target_os = "linux")]
+    fn get_foreground_window(&self) -> Option<u64> {
+        use crate::linux::x11::X11WindowBackend;
+
+        X11WindowBackend::shared()?
+            .active_window()
+            .ok()
+            .flatten()
+            .map(|window| window as u64)
     }
 
-    fn get_threads_for_process(&self, pid: u32) -> Result<Vec<ThreadInfo>> {
-        let mut threads = Vec::new();
-        
+    /// PID du processus propriétaire de la fenêtre au premier plan
+    #[cfg(windows)]
+    pub fn get_foreground_process_id(&self) -> Option<u32> {
+        let hwnd = self.get_foreground_window()? as HWND;
+        let mut process_id = 0;
         unsafe {
-            let snapshot: HANDLE = CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0);
-            if snapshot != null_mut() {
-                let mut te32: THREADENTRY32 = mem::zeroed();
-                te32.dwSize = mem::size_of::<THREADENTRY32>() as u32;
-                
-                if Thread32First(snapshot, &mut te32) != 0 {
-                    loop {
-                        if te32.th32OwnerProcessID == pid {
-                            let thread_info = ThreadInfo {
-                                thread_id: te32.th32ThreadID,
-                                process_id: te32.th32OwnerProcessID,
-                                creation_time: None,
-                                exit_time: None,
-                                kernel_time: 0,
-                                user_time: 0,
-                                priority: 0,
-                                base_priority: 0,
-                                context_switches: te32.dwFlags,
-                            };
-                            threads.push(thread_info);
-                        }
-
-                        if Thread32Next(snapshot, &mut te32) == 0 {
-                            break;
-                        }
-                    }
-                }
-                CloseHandle(snapshot);
-            }
+            GetWindowThreadProcessId(hwnd, &mut process_id);
         }
+        Some(process_id).filter(|pid| *pid != 0)
+    }
 
-        Ok(threads)
+    #[cfg(target_os = "linux")]
+    pub fn get_foreground_process_id(&self) -> Option<u32> {
+        use crate::linux::x11::X11WindowBackend;
+
+        let window = self.get_foreground_window()? as u32;
+        X11WindowBackend::shared()?
+            .window_pid(window)
+            .ok()
+            .filter(|pid| *pid != 0)
     }
 
+    fn is_browser_content_window(&self, window: &WindowInfo) -> bool {
+        // Détecter les fenêtres de contenu de navigateur (Firefox, Chrome, Edge...)
+        BrowserFamily::from_class_name(&window.class_name).is_some()
+    }
+
+    #[cfg(windows)]
+    fn get_threads_for_process(&self, pid: u32) -> Result<Vec<ThreadInfo>> {
+        Ok(self.snapshot()?.threads_for(pid))
+    }
+
+    // Pas de liste globale des threads sous Linux : lecture de /proc/<pid>/task à la demande
+    #[cfg(target_os = "linux")]
+    fn get_threads_for_process(&self, pid: u32) -> Result<Vec<ThreadInfo>> {
+        use crate::linux::procfs::{read_task_stat, thread_ids, ticks_to_100ns};
+
+        Ok(thread_ids(pid)
+            .into_iter()
+            .filter_map(|tid| read_task_stat(pid, tid))
+            .map(|stat| ThreadInfo {
+                thread_id: stat.pid,
+                process_id: pid,
+                creation_time: None,
+                exit_time: None,
+                kernel_time: ticks_to_100ns(stat.stime_ticks),
+                user_time: ticks_to_100ns(stat.utime_ticks),
+                priority: stat.priority,
+                base_priority: stat.nice,
+                context_switches: 0,
+            })
+            .collect())
+    }
+
+    #[cfg(windows)]
     fn get_modules_for_process(&self, pid: u32) -> Result<Vec<ModuleInfo>> {
         let mut modules = Vec::new();
         
@@ -336,52 +646,163 @@ impl ProcessMetadataCollector {
         Ok(modules)
     }
 
-    fn get_environment_variables(&self, _pid: u32) -> Result<HashMap<String, String>> {
-        let env_vars = HashMap::new();
-        
-        // Pour l'instant, on retourne une HashMap vide
-        // TODO: Implémenter la récupération des variables d'environnement du processus
-        // Cela nécessite des privilèges élevés et des APIs spécialisées
-        
-        Ok(env_vars)
-    }
+    // Modules = fichiers mappés exécutables (binaire principal et bibliothèques partagées)
+    #[cfg(target_os = "linux")]
+    fn get_modules_for_process(&self, pid: u32) -> Result<Vec<ModuleInfo>> {
+        let maps = std::fs::read_to_string(format!("/proc/{}/maps", pid))?;
+        let mut modules: Vec<ModuleInfo> = Vec::new();
 
-    pub fn get_process_name_by_pid(&self, pid: u32) -> String {
-        unsafe {
-            let snapshot: HANDLE = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
-            if snapshot != null_mut() {
-                let mut pe32: PROCESSENTRY32 = mem::zeroed();
-                pe32.dwSize = mem::size_of::<PROCESSENTRY32>() as u32;
-                
-                if Process32First(snapshot, &mut pe32) != 0 {
-                    loop {
-                        if pe32.th32ProcessID == pid {
-                            let name = self.c_string_to_string_260(&pe32.szExeFile);
-                            CloseHandle(snapshot);
-                            return name.to_lowercase();
-                        }
+        for line in maps.lines() {
+            let mut fields = line.split_whitespace();
+            let (Some(range), Some(permissions)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            let path = match fields.nth(3) {
+                Some(path) if path.starts_with('/') => path,
+                _ => continue,
+            };
+            let Some((start, end)) = range.split_once('-') else {
+                continue;
+            };
+            let (Ok(start), Ok(end)) = (u64::from_str_radix(start, 16), u64::from_str_radix(end, 16)) else {
+                continue;
+            };
 
-                        if Process32Next(snapshot, &mut pe32) == 0 {
-                            break;
-                        }
+            match modules.iter_mut().find(|module| module.module_path == path) {
+                Some(module) => {
+                    let module_end = (module.base_address + module.module_size as u64).max(end);
+                    module.base_address = module.base_address.min(start);
+                    module.module_size = (module_end - module.base_address) as u32;
+                    if permissions.contains('x') && module.entry_point == 0 {
+                        module.entry_point = start;
                     }
                 }
-                CloseHandle(snapshot);
+                None => modules.push(ModuleInfo {
+                    module_name: path.rsplit('/').next().unwrap_or(path).to_string(),
+                    module_path: path.to_string(),
+                    base_address: start,
+                    module_size: (end - start) as u32,
+                    entry_point: if permissions.contains('x') { start } else { 0 },
+                }),
             }
         }
-        String::new()
+
+        Ok(modules)
     }
 
+    #[cfg(windows)]
+    fn get_environment_variables(&self, process_handle: HANDLE, names: &[String]) -> Result<HashMap<String, String>> {
+        // Lecture du bloc d'environnement dans le PEB du processus (32 ou 64 bits)
+        let variables = unsafe { ProcessParameters::open(process_handle)?.environment()? };
+        Ok(filter_environment(variables, names))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn get_environment_variables(&self, pid: u32, names: &[String]) -> Result<HashMap<String, String>> {
+        // Environnement initial du processus (les modifications faites après exec n'y figurent pas)
+        let variables = crate::linux::procfs::read_environ(pid).map_err(|e| match e.kind() {
+            std::io::ErrorKind::PermissionDenied => {
+                anyhow::anyhow!("Accès refusé aux variables d'environnement du PID {}", pid)
+            }
+            _ => anyhow::anyhow!("Impossible de lire /proc/{}/environ: {}", pid, e),
+        })?;
+        Ok(filter_environment(variables, names))
+    }
+
+    /// Un accès refusé (autre utilisateur, processus protégé) ne fait pas échouer la collecte
+    fn apply_environment(&self, metadata: &mut ProcessMetadata, variables: Result<HashMap<String, String>>) {
+        match variables {
+            Ok(variables) => metadata.environment_variables = variables,
+            Err(e) => {
+                metadata
+                    .raw_data
+                    .insert("environment_error".to_string(), serde_json::json!(e.to_string()));
+            }
+        }
+    }
+
+    fn fill_executable_info(&self, metadata: &mut ProcessMetadata, path: Option<String>) {
+        let Some(path) = path else {
+            return;
+        };
+        match executable_info(std::path::Path::new(&path)) {
+            Ok(info) => metadata.executable_info = Some(info),
+            Err(e) => {
+                metadata
+                    .raw_data
+                    .insert("executable_error".to_string(), serde_json::json!(e.to_string()));
+            }
+        }
+    }
+
+    fn fill_handles(&self, metadata: &mut ProcessMetadata, pid: u32, options: &MetadataOptions) {
+        match enumerate_handles(pid, &options.handle_types, options.max_handles) {
+            Ok(handles) => metadata.handles = handles,
+            Err(e) => {
+                metadata
+                    .raw_data
+                    .insert("handles_error".to_string(), serde_json::json!(e.to_string()));
+            }
+        }
+    }
+
+    fn fill_owner(&self, metadata: &mut ProcessMetadata, pid: u32) {
+        match owner_info(pid) {
+            Ok(info) => metadata.owner_info = Some(info),
+            Err(e) => {
+                metadata
+                    .raw_data
+                    .insert("owner_error".to_string(), serde_json::json!(e.to_string()));
+            }
+        }
+    }
+
+    fn fill_memory_map(&self, metadata: &mut ProcessMetadata, pid: u32, options: &MetadataOptions) {
+        match collect_memory_map(pid, options.max_memory_regions) {
+            Ok(map) => metadata.memory_map = Some(map),
+            Err(e) => {
+                metadata
+                    .raw_data
+                    .insert("memory_map_error".to_string(), serde_json::json!(e.to_string()));
+            }
+        }
+    }
+
+    fn fill_network(&self, metadata: &mut ProcessMetadata, pid: u32) {
+        match collect_connections(pid) {
+            Ok(connections) => metadata.network_connections = connections,
+            Err(e) => {
+                metadata
+                    .raw_data
+                    .insert("network_error".to_string(), serde_json::json!(e.to_string()));
+            }
+        }
+    }
+
+    pub fn get_process_name_by_pid(&self, pid: u32) -> String {
+        let lookup = |snapshot: Arc<SystemSnapshot>| snapshot.process_name(pid).map(str::to_lowercase);
+
+        // Processus plus récent que le snapshot : une nouvelle capture avant d'abandonner
+        self.snapshot()
+            .ok()
+            .and_then(lookup)
+            .or_else(|| self.snapshots.refresh().ok().and_then(lookup))
+            .unwrap_or_default()
+    }
+
+    #[cfg(windows)]
     fn filetime_to_u64(&self, ft: FILETIME) -> u64 {
         ((ft.dwHighDateTime as u64) << 32) | (ft.dwLowDateTime as u64)
     }
 
+    #[cfg(windows)]
     fn c_string_to_string_256(&self, c_str: &[i8; 256]) -> String {
         let end = c_str.iter().position(|&x| x == 0).unwrap_or(c_str.len());
         let bytes: Vec<u8> = c_str[..end].iter().map(|&x| x as u8).collect();
         String::from_utf8_lossy(&bytes).to_string()
     }
 
+    #[cfg(windows)]
     fn c_string_to_string_260(&self, c_str: &[i8; 260]) -> String {
         let end = c_str.iter().position(|&x| x == 0).unwrap_or(c_str.len());
         let bytes: Vec<u8> = c_str[..end].iter().map(|&x| x as u8).collect();
@@ -389,65 +810,70 @@ impl ProcessMetadataCollector {
     }
 }
 
-struct WindowEnumData<'a> {
-    target_pid: u32,
-    windows: &'a mut Vec<WindowInfo>,
-}
-
-unsafe extern "system" fn enum_windows_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
-    let enum_data = &mut *(lparam as *mut WindowEnumData);
+// Construire un WindowInfo à partir d'un HWND (énumération et événements de premier plan)
+#[cfg(windows)]
+pub(crate) unsafe fn window_info_from_hwnd(hwnd: HWND) -> WindowInfo {
+    let mut window_text: [u16; MAX_PATH] = [0; MAX_PATH];
+    let text_len = GetWindowTextW(hwnd, window_text.as_mut_ptr(), MAX_PATH as i32);
+    
+    let mut class_name: [u16; MAX_PATH] = [0; MAX_PATH];
+    let class_len = GetClassNameW(hwnd, class_name.as_mut_ptr(), MAX_PATH as i32);
     
+    let mut window_rect = RECT { left: 0, top: 0, right: 0, bottom: 0 };
+    let has_rect = GetWindowRect(hwnd, &mut window_rect) != 0;
+    
+    let title = if text_len > 0 {
+        let os_string = OsString::from_wide(&window_text[..text_len as usize]);
+        os_string.into_string().unwrap_or_default()
+    } else {
+        String::new()
+    };
+    
+    let class = if class_len > 0 {
+        let os_string = OsString::from_wide(&class_name[..class_len as usize]);
+        os_string.into_string().unwrap_or_default()
+    } else {
+        String::new()
+    };
+    
+    // GetWindowThreadProcessId retourne le thread et écrit le PID
     let mut process_id: DWORD = 0;
-    GetWindowThreadProcessId(hwnd, &mut process_id);
+    let thread_id = GetWindowThreadProcessId(hwnd, &mut process_id);
     
-    if process_id == enum_data.target_pid {
-        let mut window_text: [u16; MAX_PATH] = [0; MAX_PATH];
-        let text_len = GetWindowTextW(hwnd, window_text.as_mut_ptr(), MAX_PATH as i32);
-        
-        let mut class_name: [u16; MAX_PATH] = [0; MAX_PATH];
-        let class_len = GetClassNameW(hwnd, class_name.as_mut_ptr(), MAX_PATH as i32);
-        
-        let mut window_rect = RECT { left: 0, top: 0, right: 0, bottom: 0 };
-        let has_rect = GetWindowRect(hwnd, &mut window_rect) != 0;
-        
-        let title = if text_len > 0 {
-            let os_string = OsString::from_wide(&window_text[..text_len as usize]);
-            os_string.into_string().unwrap_or_default()
-        } else {
-            String::new()
-        };
-        
-        let class = if class_len > 0 {
-            let os_string = OsString::from_wide(&class_name[..class_len as usize]);
-            os_string.into_string().unwrap_or_default()
+    WindowInfo {
+        hwnd: hwnd as u64,
+        class_name: class,
+        window_title: title,
+        process_id,
+        thread_id,
+        is_visible: IsWindowVisible(hwnd) != 0,
+        window_rect: if has_rect {
+            Some(WindowRect {
+                left: window_rect.left,
+                top: window_rect.top,
+                right: window_rect.right,
+                bottom: window_rect.bottom,
+            })
         } else {
-            String::new()
-        };
-        
-        let mut thread_id: DWORD = 0;
-        GetWindowThreadProcessId(hwnd, &mut thread_id);
-        
-        let window_info = WindowInfo {
-            hwnd: hwnd as u64,
-            class_name: class,
-            window_title: title,
-            process_id: process_id,
-            thread_id: thread_id,
-            is_visible: IsWindowVisible(hwnd) != 0,
-            window_rect: if has_rect {
-                Some(WindowRect {
-                    left: window_rect.left,
-                    top: window_rect.top,
-                    right: window_rect.right,
-                    bottom: window_rect.bottom,
-                })
-            } else {
-                None
-            },
-        };
-        
-        enum_data.windows.push(window_info);
+            None
+        },
+        browser_tab: None,
+    }
+}
+
+/// Garder les variables demandées (toutes si la liste est vide)
+fn filter_environment(variables: Vec<(String, String)>, names: &[String]) -> HashMap<String, String> {
+    variables
+        .into_iter()
+        .filter(|(name, _)| names.is_empty() || names.iter().any(|wanted| same_variable_name(wanted, name)))
+        .collect()
+}
+
+/// Noms insensibles à la casse sous Windows, sensibles sous Linux
+fn same_variable_name(wanted: &str, name: &str) -> bool {
+    if cfg!(windows) {
+        wanted.eq_ignore_ascii_case(name)
+    } else {
+        wanted == name
     }
-    
-    1 // Continue enumeration
 }
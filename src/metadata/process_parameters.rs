@@ -0,0 +1,219 @@
+use anyhow::{bail, Result};
+use std::mem;
+use std::ptr::null_mut;
+use winapi::shared::ntdef::{HANDLE, PVOID};
+use winapi::um::memoryapi::ReadProcessMemory;
+use windows::Wdk::System::Threading::{NtQueryInformationProcess, ProcessBasicInformation};
+#[cfg(target_pointer_width = "64")]
+use windows::Wdk::System::Threading::ProcessWow64Information;
+use windows::Win32::Foundation::HANDLE as WinHandle;
+use windows::Win32::System::Threading::PROCESS_BASIC_INFORMATION;
+
+// Limite de lecture d'un bloc d'environnement dont la taille n'est pas renseignée
+const MAX_ENVIRONMENT_SIZE: usize = 1024 * 1024;
+const ENVIRONMENT_CHUNK_SIZE: usize = 4096;
+
+/// Offsets dans le PEB et RTL_USER_PROCESS_PARAMETERS (structures non documentées, stables depuis Vista)
+struct PebLayout {
+    pointer_size: usize,
+    process_parameters: usize,
+    current_directory: usize,
+    command_line: usize,
+    environment: usize,
+    environment_size: usize,
+}
+
+const LAYOUT_64: PebLayout = PebLayout {
+    pointer_size: 8,
+    process_parameters: 0x20,
+    current_directory: 0x38,
+    command_line: 0x70,
+    environment: 0x80,
+    environment_size: 0x3F0,
+};
+
+// Processus 32 bits (WOW64 vu depuis un build 64 bits, ou build 32 bits)
+const LAYOUT_32: PebLayout = PebLayout {
+    pointer_size: 4,
+    process_parameters: 0x10,
+    current_directory: 0x24,
+    command_line: 0x40,
+    environment: 0x48,
+    environment_size: 0x290,
+};
+
+/// Paramètres d'un autre processus lus dans son PEB.
+/// Un build 32 bits ne peut lire que des processus 32 bits.
+pub struct ProcessParameters {
+    handle: HANDLE,
+    layout: &'static PebLayout,
+    /// Adresse de RTL_USER_PROCESS_PARAMETERS dans le processus cible
+    address: u64,
+}
+
+impl ProcessParameters {
+    /// `handle` doit avoir les droits PROCESS_QUERY_INFORMATION et PROCESS_VM_READ
+    pub unsafe fn open(handle: HANDLE) -> Result<Self> {
+        let (peb, layout) = peb_address(handle)?;
+        let address = read_pointer(handle, peb + layout.process_parameters as u64, layout)?;
+        if address == 0 {
+            bail!("Paramètres du processus indisponibles (processus en cours de création ?)");
+        }
+
+        Ok(Self {
+            handle,
+            layout,
+            address,
+        })
+    }
+
+    /// Ligne de commande complète, telle que reçue par le processus
+    pub unsafe fn command_line(&self) -> Result<String> {
+        self.read_unicode_string(self.address + self.layout.command_line as u64)
+    }
+
+    /// Répertoire de travail courant (CurrentDirectory.DosPath)
+    pub unsafe fn current_directory(&self) -> Result<String> {
+        self.read_unicode_string(self.address + self.layout.current_directory as u64)
+    }
+
+    /// Variables d'environnement courantes du processus
+    pub unsafe fn environment(&self) -> Result<Vec<(String, String)>> {
+        let block = read_pointer(self.handle, self.address + self.layout.environment as u64, self.layout)?;
+        if block == 0 {
+            return Ok(Vec::new());
+        }
+
+        let size = read_pointer(self.handle, self.address + self.layout.environment_size as u64, self.layout)? as usize;
+        let bytes = if size > 0 && size <= MAX_ENVIRONMENT_SIZE {
+            read_memory(self.handle, block, size)?
+        } else {
+            read_until_double_nul(self.handle, block)?
+        };
+
+        Ok(parse_environment_block(&to_utf16(&bytes)))
+    }
+
+    /// UNICODE_STRING : Length (u16), MaximumLength (u16), puis le pointeur Buffer aligné
+    unsafe fn read_unicode_string(&self, address: u64) -> Result<String> {
+        let header = read_memory(self.handle, address, 2 * self.layout.pointer_size)?;
+        if header.len() < 2 * self.layout.pointer_size {
+            bail!("UNICODE_STRING tronquée");
+        }
+        let length = u16::from_le_bytes([header[0], header[1]]) as usize;
+        let buffer = read_pointer(self.handle, address + self.layout.pointer_size as u64, self.layout)?;
+        if length == 0 || buffer == 0 {
+            return Ok(String::new());
+        }
+
+        let bytes = read_memory(self.handle, buffer, length)?;
+        Ok(String::from_utf16_lossy(&to_utf16(&bytes)))
+    }
+}
+
+unsafe fn peb_address(handle: HANDLE) -> Result<(u64, &'static PebLayout)> {
+    // Processus WOW64 : on lit le PEB 32 bits, celui que le programme utilise réellement
+    #[cfg(target_pointer_width = "64")]
+    {
+        let mut wow64_peb: usize = 0;
+        let status = NtQueryInformationProcess(
+            WinHandle(handle as *mut core::ffi::c_void),
+            ProcessWow64Information,
+            &mut wow64_peb as *mut usize as PVOID,
+            mem::size_of::<usize>() as u32,
+            null_mut(),
+        );
+        if status.0 >= 0 && wow64_peb != 0 {
+            return Ok((wow64_peb as u64, &LAYOUT_32));
+        }
+    }
+
+    let mut info: PROCESS_BASIC_INFORMATION = mem::zeroed();
+    let status = NtQueryInformationProcess(
+        WinHandle(handle as *mut core::ffi::c_void),
+        ProcessBasicInformation,
+        &mut info as *mut PROCESS_BASIC_INFORMATION as PVOID,
+        mem::size_of::<PROCESS_BASIC_INFORMATION>() as u32,
+        null_mut(),
+    );
+    if status.0 < 0 {
+        bail!("NtQueryInformationProcess a échoué (statut 0x{:08X})", status.0 as u32);
+    }
+    if info.PebBaseAddress.is_null() {
+        bail!("Adresse du PEB indisponible");
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    let layout = &LAYOUT_64;
+    #[cfg(target_pointer_width = "32")]
+    let layout = &LAYOUT_32;
+    Ok((info.PebBaseAddress as u64, layout))
+}
+
+unsafe fn read_memory(handle: HANDLE, address: u64, size: usize) -> Result<Vec<u8>> {
+    let mut buffer = vec![0u8; size];
+    let mut read = 0usize;
+    let ok = ReadProcessMemory(
+        handle,
+        address as usize as PVOID,
+        buffer.as_mut_ptr() as PVOID,
+        size,
+        &mut read,
+    );
+    if ok == 0 {
+        let error = std::io::Error::last_os_error();
+        // ERROR_ACCESS_DENIED / ERROR_PARTIAL_COPY : processus protégé ou d'une autre architecture
+        return match error.raw_os_error() {
+            Some(5) | Some(299) => Err(anyhow::anyhow!("Accès refusé à la mémoire du processus")),
+            _ => Err(anyhow::anyhow!("ReadProcessMemory a échoué: {}", error)),
+        };
+    }
+    buffer.truncate(read);
+    Ok(buffer)
+}
+
+unsafe fn read_pointer(handle: HANDLE, address: u64, layout: &PebLayout) -> Result<u64> {
+    let bytes = read_memory(handle, address, layout.pointer_size)?;
+    let mut value = [0u8; 8];
+    value[..bytes.len().min(8)].copy_from_slice(&bytes[..bytes.len().min(8)]);
+    Ok(u64::from_le_bytes(value))
+}
+
+/// Lire par blocs jusqu'au double caractère nul qui termine le bloc d'environnement
+unsafe fn read_until_double_nul(handle: HANDLE, address: u64) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    while bytes.len() < MAX_ENVIRONMENT_SIZE {
+        match read_memory(handle, address + bytes.len() as u64, ENVIRONMENT_CHUNK_SIZE) {
+            Ok(chunk) if !chunk.is_empty() => bytes.extend_from_slice(&chunk),
+            // Fin de la zone mappée : on garde ce qui a été lu
+            Ok(_) => break,
+            Err(_) if !bytes.is_empty() => break,
+            Err(e) => return Err(e),
+        }
+        if to_utf16(&bytes).windows(2).any(|pair| pair == [0, 0]) {
+            break;
+        }
+    }
+    Ok(bytes)
+}
+
+fn to_utf16(bytes: &[u8]) -> Vec<u16> {
+    bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect()
+}
+
+/// "NOM=valeur\0NOM=valeur\0\0" ; les entrées "=C:=C:\..." (répertoire par lecteur) sont ignorées
+fn parse_environment_block(words: &[u16]) -> Vec<(String, String)> {
+    words
+        .split(|word| *word == 0)
+        .take_while(|entry| !entry.is_empty())
+        .map(String::from_utf16_lossy)
+        .filter(|entry| !entry.starts_with('='))
+        .filter_map(|entry| {
+            let (name, value) = entry.split_once('=')?;
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
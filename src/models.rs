@@ -1,18 +1,29 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct MetadataOptions {
     // Métadonnées de base (toujours utiles)
     pub basic_info: bool,           // PID, nom, chemin, titre fenêtre
+    pub executable_info: bool,      // Taille, date, SHA-256 et version de l'exécutable
     pub memory_info: bool,          // Utilisation mémoire
     pub window_info: bool,          // Fenêtres du processus
     
     // Métadonnées détaillées (optionnelles)
     pub cpu_info: bool,             // Temps CPU (gourmand)
+    pub io_info: bool,              // Compteurs d'entrées/sorties
     pub thread_info: bool,          // Threads (gourmand)
     pub module_info: bool,          // Modules chargés (gourmand)
     pub handle_info: bool,          // Handles (gourmand)
+    pub handle_types: Vec<String>,  // Types de handles à lister (ex: File, socket) ; vide = tous
+    pub max_handles: usize,         // Nombre maximal de handles listés (0 = compter seulement)
     pub environment_vars: bool,     // Variables d'environnement (gourmand)
+    pub environment_var_names: Vec<String>, // Variables à garder (ex: PATH, LANG) ; vide = toutes
+    pub network_info: bool,         // Connexions TCP/UDP du processus
+    pub container_info: bool,       // Cgroup, unité systemd, conteneur et namespaces (Linux)
+    pub owner_info: bool,           // Utilisateur, session et privilèges
+    pub memory_map: bool,           // Carte mémoire : régions et répartition (gourmand)
+    pub max_memory_regions: usize,  // Régions détaillées, les plus grosses (0 = répartition seulement)
     
     // Media Control
     pub media_control: bool,        // Sessions média
@@ -24,15 +35,25 @@ impl Default for MetadataOptions {
         Self {
             // Par défaut : seulement les infos utiles
             basic_info: true,
+            executable_info: false,
             memory_info: true,
             window_info: true,
             
             // Par défaut : désactivé (gourmand)
             cpu_info: false,
+            io_info: false,
             thread_info: false,
             module_info: false,
             handle_info: false,
+            handle_types: Vec::new(),
+            max_handles: 1024,
             environment_vars: false,
+            environment_var_names: Vec::new(),
+            network_info: false,
+            container_info: false,
+            owner_info: false,
+            memory_map: false,
+            max_memory_regions: 256,
             
             // Media Control
             media_control: true,
@@ -49,12 +70,68 @@ pub struct ProcessInfo {
     pub window_title: Option<String>,
     pub executable_path: Option<String>,
     pub subprocesses: Vec<ProcessInfo>,
+    // Rôle dans une application Chromium/Electron (None pour les autres applications)
+    #[serde(default)]
+    pub role: Option<ProcessRole>,
+    #[serde(default)]
+    pub utility_sub_type: Option<String>, // --utility-sub-type= (ex: "network.mojom.NetworkService")
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApplicationInfo {
     pub main_process: ProcessInfo,
     pub total_processes: usize,
+    // Nom présentable à l'utilisateur (plutôt que "Code.exe")
+    #[serde(default)]
+    pub identity: Option<AppIdentity>,
+    // Répartition des processus par rôle (applications Chromium/Electron uniquement)
+    #[serde(default)]
+    pub roles: Vec<ProcessRoleSummary>,
+    // Conteneur ou unité systemd du processus principal (regroupements correspondants)
+    #[serde(default)]
+    pub container_info: Option<ContainerInfo>,
+}
+
+// Critère de regroupement des processus en applications par le scanner
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApplicationGrouping {
+    #[default]
+    Name,        // Processus avec fenêtre, par nom d'exécutable
+    Container,   // Un groupe par conteneur (avec ou sans fenêtre), le reste par nom
+    SystemdUnit, // Un groupe par unité systemd .service/.scope, le reste par nom
+}
+
+// Rôle d'un processus Chromium/Electron, déduit de --type=
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessRole {
+    Browser,         // Processus principal (sans --type), propriétaire des fenêtres
+    Renderer,        // Pages web / interface Electron
+    Gpu,             // --type=gpu-process
+    Utility,         // Réseau, audio, stockage... (voir utility_sub_type)
+    CrashpadHandler, // Collecte des plantages
+    Zygote,          // Lanceur des renderers (Linux)
+    Other,           // Autre --type (ppapi, broker...)
+}
+
+// Agrégat des processus d'un même rôle dans une application
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessRoleSummary {
+    pub role: ProcessRole,
+    pub process_count: usize,
+    pub pids: Vec<u32>,
+    pub utility_sub_types: Vec<String>, // Sous-types distincts (rôle Utility)
+}
+
+// Identité d'une application : nom d'affichage, éditeur et icône
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppIdentity {
+    pub display_name: String,
+    pub vendor: Option<String>,
+    pub icon: Option<String>,   // Nom d'icône du thème ou chemin (.desktop), binaire contenant l'icône (Windows)
+    pub app_id: Option<String>, // AUMID (Windows, applications empaquetées) ou identifiant .desktop (Linux)
+    pub source: String,         // "override", "version_info", "desktop_entry" ou "executable"
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,7 +151,14 @@ pub struct ProcessMetadata {
     // Informations de base
     pub executable_path: Option<String>,
     pub command_line: Option<String>,
+    // Arguments séparés (argv), le premier étant le programme
+    #[serde(default)]
+    pub command_line_args: Vec<String>,
+    #[serde(default)]
+    pub command_line_info: Option<CommandLineInfo>,
     pub working_directory: Option<String>,
+    #[serde(default)]
+    pub executable_info: Option<ExecutableInfo>,
     pub window_title: Option<String>,
     
     // Informations temporelles
@@ -83,9 +167,23 @@ pub struct ProcessMetadata {
     
     // Informations mémoire
     pub memory_info: Option<MemoryInfo>,
+    #[serde(default)]
+    pub memory_map: Option<MemoryMap>,
     
     // Informations CPU
     pub cpu_info: Option<CpuInfo>,
+
+    // Compteurs d'entrées/sorties
+    #[serde(default)]
+    pub io_info: Option<IoInfo>,
+
+    // Cgroup, unité systemd et conteneur (Linux)
+    #[serde(default)]
+    pub container_info: Option<ContainerInfo>,
+
+    // Propriétaire, session et privilèges
+    #[serde(default)]
+    pub owner_info: Option<OwnerInfo>,
     
     // Informations système
     pub thread_count: u32,
@@ -107,6 +205,8 @@ pub struct ProcessMetadata {
     pub modules: Vec<ModuleInfo>,
     pub media_sessions: Vec<MediaSessionInfo>,
     pub handles: Vec<HandleInfo>,
+    #[serde(default)]
+    pub network_connections: Vec<NetworkConnection>,
     pub environment_variables: std::collections::HashMap<String, String>,
     pub raw_data: std::collections::HashMap<String, serde_json::Value>,
 }
@@ -120,6 +220,9 @@ pub struct WindowInfo {
     pub thread_id: u32,
     pub is_visible: bool,
     pub window_rect: Option<WindowRect>,
+    // Renseigné par l'extension navigateur quand elle est connectée
+    #[serde(default)]
+    pub browser_tab: Option<BrowserTabInfo>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -152,18 +255,85 @@ pub struct ModuleInfo {
     pub entry_point: u64,
 }
 
+// Carte mémoire d'un processus : totaux, répartition par catégorie et plus grosses régions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryMap {
+    pub total: MemoryUsage,
+    pub breakdown: Vec<MemoryCategory>, // Par catégorie : heap, stack, image, anonymous, file, other
+    pub region_count: usize,            // Nombre total de régions (avant limite)
+    pub regions: Vec<MemoryRegion>,     // Plus grosses régions (max_memory_regions), par adresse
+}
+
+// Occupation mémoire en octets ; PSS et swap ne sont connus que sous Linux
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryUsage {
+    pub size: u64,         // Taille projetée (Linux) ou engagée (Windows)
+    pub rss: Option<u64>,
+    pub pss: Option<u64>,  // Part proportionnelle des pages partagées
+    pub private: u64,      // Pages privées résidentes
+    pub shared: u64,       // Pages partagées résidentes (plusieurs processus)
+    pub swap: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryRegion {
+    pub start_address: u64,
+    pub end_address: u64,
+    pub permissions: String,  // "r-xp" : lecture, écriture, exécution, p(rivée)/s(partagée)
+    pub kind: String,         // "heap", "stack", "image", "anonymous", "file" ou "other"
+    pub path: Option<String>, // Fichier projeté, ou pseudo-chemin ("[heap]", "[vdso]")
+    pub usage: MemoryUsage,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryCategory {
+    pub kind: String,
+    pub region_count: usize,
+    pub usage: MemoryUsage,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MediaSessionInfo {
     pub session_id: String,
     pub source_app_user_model_id: Option<String>,
     pub app_user_model_id: Option<String>,
     pub media_type: Option<String>,
-    pub playback_status: Option<String>,
+    pub playback_status: Option<String>, // Valeur WinRT brute (Debug), conservée pour les consommateurs existants
+    #[serde(default)]
+    pub playback_state: Option<MediaPlaybackStatus>, // Même statut, typé
     pub title: Option<String>,
     pub artist: Option<String>,
     pub album: Option<String>,
 }
 
+// Statut de lecture d'une session média (GlobalSystemMediaTransportControlsSessionPlaybackStatus)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaPlaybackStatus {
+    Closed,
+    Opened,
+    Changing,
+    Stopped,
+    Playing,
+    Paused,
+    Unknown(i32),
+}
+
+impl MediaPlaybackStatus {
+    /// Depuis la valeur numérique de l'énumération WinRT
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            0 => Self::Closed,
+            1 => Self::Opened,
+            2 => Self::Changing,
+            3 => Self::Stopped,
+            4 => Self::Playing,
+            5 => Self::Paused,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HandleInfo {
     pub handle_type: String,
@@ -172,6 +342,19 @@ pub struct HandleInfo {
     pub access_mask: u32,
 }
 
+// Socket TCP/UDP ouvert par un processus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkConnection {
+    pub protocol: String,               // "tcp", "tcp6", "udp", "udp6"
+    pub local_address: String,
+    pub local_port: u16,
+    pub remote_address: Option<String>, // None : socket en écoute ou non connecté
+    pub remote_port: Option<u16>,
+    pub state: Option<String>,          // État TCP (ESTABLISHED, LISTEN...), None pour UDP
+    pub inode: Option<u64>,             // Inode du socket (Linux)
+    pub pid: u32,                       // Processus propriétaire
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryInfo {
     pub working_set_size: u64,
@@ -188,3 +371,124 @@ pub struct CpuInfo {
     pub creation_time: u64,
     pub exit_time: u64,
 }
+
+// Identité du fichier exécutable, lue directement dans le binaire
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutableInfo {
+    pub path: String,
+    pub size: u64,
+    pub modified: Option<u64>,        // Millisecondes depuis l'epoch
+    pub sha256: String,
+    pub format: String,               // "pe", "elf" ou "unknown"
+    pub architecture: Option<String>, // "x86", "x86_64", "aarch64"...
+    pub version: Option<VersionInfo>, // Ressource VS_VERSIONINFO (PE)
+    pub build_id: Option<String>,     // Note NT_GNU_BUILD_ID (ELF)
+    pub soname: Option<String>,       // DT_SONAME (bibliothèques ELF)
+}
+
+// Ressource de version d'un binaire PE
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VersionInfo {
+    pub product_name: Option<String>,
+    pub company_name: Option<String>,
+    pub file_description: Option<String>,
+    pub file_version: Option<String>,    // Chaîne telle que déclarée (ex: "1.85.2")
+    pub product_version: Option<String>,
+    pub original_filename: Option<String>,
+    pub fixed_file_version: Option<String>, // VS_FIXEDFILEINFO, toujours au format "a.b.c.d"
+}
+
+// Compteurs cumulés depuis le démarrage du processus
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IoInfo {
+    pub read_bytes: u64,               // Octets lus (cache compris)
+    pub write_bytes: u64,              // Octets écrits (cache compris)
+    pub read_operations: u64,
+    pub write_operations: u64,
+    pub other_bytes: u64,              // Autres opérations (Windows : DeviceIoControl...)
+    pub other_operations: u64,
+    pub disk_read_bytes: Option<u64>,  // Octets réellement lus sur le stockage (Linux)
+    pub disk_write_bytes: Option<u64>, // Octets réellement envoyés au stockage (Linux)
+}
+
+// Rattachement d'un processus Linux : cgroup, unité systemd, conteneur et namespaces
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerInfo {
+    pub cgroup_path: String,               // Chemin cgroup v2 (ou hiérarchie name=systemd en v1)
+    pub cgroup_version: u8,                // 1 ou 2
+    pub systemd_unit: Option<String>,      // Unité .service ou .scope la plus proche (ex: "docker-<id>.scope")
+    pub systemd_slice: Option<String>,     // Slice parente (ex: "system.slice", "user-1000.slice")
+    pub runtime: Option<String>,           // "docker", "podman", "containerd", "cri-o", "lxc"
+    pub container_id: Option<String>,
+    pub kubernetes_pod: Option<String>,    // UID du pod (cgroups kubepods)
+    pub pid_namespace: Option<u64>,        // Inode de /proc/<pid>/ns/pid
+    pub mount_namespace: Option<u64>,      // Inode de /proc/<pid>/ns/mnt
+}
+
+// Propriétaire d'un processus et niveau de privilège
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OwnerInfo {
+    pub user_id: Option<String>,          // UID effectif (Linux) ou SID du jeton (Windows)
+    pub user_name: Option<String>,        // "alice" (Linux), "DOMAINE\\alice" (Windows)
+    pub real_user_id: Option<String>,     // UID réel, différent de user_id pour un binaire setuid (Linux)
+    pub group_id: Option<String>,         // GID effectif ou SID du groupe principal
+    pub group_name: Option<String>,
+    pub session_id: Option<u32>,          // Session de connexion (audit Linux, session Terminal Services)
+    pub is_elevated: bool,                // Jeton élevé (UAC), root ou capacités effectives
+    pub integrity_level: Option<String>,  // "untrusted", "low", "medium", "high", "system" (Windows)
+    pub capabilities: Vec<String>,        // Capacités effectives (Linux, ex: "cap_net_admin")
+    pub is_setuid: bool,                  // Binaire setuid/setgid ou identités réelle et effective différentes (Linux)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsedTabTitle {
+    pub raw_title: String,
+    pub browser: Option<String>,
+    pub page_title: String,
+    pub site_name: Option<String>,
+    pub category: Option<String>,
+}
+
+// Rôle d'un processus déduit de ses arguments
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommandLineInfo {
+    pub process_type: Option<String>, // --type= des enfants Chromium/Electron (renderer, gpu-process...), None = principal
+    #[serde(default)]
+    pub utility_sub_type: Option<String>, // --utility-sub-type= des processus utilitaires Chromium
+    pub runtime: Option<String>,      // "java", "python", "node"
+    pub script: Option<String>,       // Script, archive, module ou classe principale lancé par l'interpréteur
+}
+
+// Onglet rapporté par l'extension navigateur (native messaging)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserTabInfo {
+    pub browser_pid: u32,
+    pub tab_id: i64,
+    pub window_id: Option<i64>,
+    pub url: String,
+    pub title: String,
+    pub is_incognito: bool,
+    pub is_audible: bool,
+    pub updated_at: u64, // millisecondes depuis l'epoch
+}
+
+// Entrée brute de la liste des processus (pour les diffs et la sélection de cibles)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessEntry {
+    pub pid: u32,
+    pub parent_pid: u32,
+    pub name: String,
+    pub start_time: Option<u64>, // millisecondes depuis l'epoch
+}
+
+// Choix du processus à suivre quand plusieurs instances correspondent (Chrome, Discord, Teams...)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessSelectionPolicy {
+    Oldest,           // Instance démarrée en premier
+    Newest,           // Instance démarrée en dernier
+    ForegroundWindow, // Instance propriétaire de la fenêtre au premier plan
+    MediaSession,     // Instance qui émet du son / possède un onglet audible
+    #[default]
+    TreeRoot,         // Racine de l'arbre (processus principal du navigateur)
+}
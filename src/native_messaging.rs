@@ -0,0 +1,579 @@
+use crate::models::BrowserTabInfo;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Taille maximale d'un message envoyé par l'extension (les navigateurs limitent à 1 Mo vers l'extension)
+pub const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// Messages envoyés par l'extension compagnon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExtensionMessage {
+    /// L'onglet actif a changé (activation, navigation ou changement de titre)
+    ActiveTab {
+        tab_id: i64,
+        window_id: Option<i64>,
+        url: String,
+        title: String,
+        #[serde(default)]
+        incognito: bool,
+        #[serde(default)]
+        audible: bool,
+    },
+    /// Un onglet commence ou arrête d'émettre du son
+    AudibleChanged {
+        tab_id: i64,
+        audible: bool,
+        url: Option<String>,
+        title: Option<String>,
+        #[serde(default)]
+        incognito: bool,
+    },
+    /// Un onglet a été fermé
+    TabClosed { tab_id: i64 },
+    Ping,
+}
+
+/// Réponses renvoyées à l'extension
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HostResponse {
+    Ack,
+    Pong { version: String },
+    Error { message: String },
+}
+
+/// État des onglets d'un navigateur, partagé entre l'hôte et le moniteur
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BrowserTabState {
+    pub browser_pid: u32,
+    pub active_tab: Option<BrowserTabInfo>,
+    pub audible_tabs: Vec<BrowserTabInfo>,
+    pub updated_at: u64,
+}
+
+/// Lire un message (longueur u32 en ordre natif + JSON). Retourne None quand le flux est fermé.
+pub fn read_message<R: Read>(reader: &mut R) -> Result<Option<ExtensionMessage>> {
+    let mut length_bytes = [0u8; 4];
+    let mut filled = 0;
+    while filled < length_bytes.len() {
+        match reader.read(&mut length_bytes[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(anyhow::anyhow!("Préfixe de longueur tronqué ({} octets sur 4)", filled)),
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let length = u32::from_ne_bytes(length_bytes) as usize;
+    if length > MAX_MESSAGE_SIZE {
+        return Err(anyhow::anyhow!("Message trop volumineux: {} octets", length));
+    }
+
+    let mut payload = vec![0u8; length];
+    reader
+        .read_exact(&mut payload)
+        .map_err(|e| anyhow::anyhow!("Message tronqué ({} octets attendus): {}", length, e))?;
+    Ok(Some(serde_json::from_slice(&payload)?))
+}
+
+/// Écrire un message au format native messaging
+pub fn write_message<W: Write, T: Serialize>(writer: &mut W, message: &T) -> Result<()> {
+    let payload = serde_json::to_vec(message)?;
+    if payload.len() > MAX_MESSAGE_SIZE {
+        return Err(anyhow::anyhow!("Message trop volumineux: {} octets", payload.len()));
+    }
+
+    writer.write_all(&(payload.len() as u32).to_ne_bytes())?;
+    writer.write_all(&payload)?;
+    writer.flush()?;
+    Ok(())
+}
+
+// Profondeur maximale remontée depuis un processus enfant (renderer, gpu...) jusqu'au navigateur
+const MAX_ANCESTOR_DEPTH: usize = 8;
+
+/// Stockage des états d'onglets (un fichier JSON par PID de navigateur)
+pub struct BrowserTabStore {
+    directory: PathBuf,
+}
+
+impl Default for BrowserTabStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BrowserTabStore {
+    /// Répertoire propre à l'utilisateur : les URL et l'état privé ne doivent pas être lisibles par les autres comptes
+    pub fn new() -> Self {
+        Self::with_directory(default_directory())
+    }
+
+    pub fn with_directory(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    pub fn load(&self, browser_pid: u32) -> Option<BrowserTabState> {
+        let content = std::fs::read(self.path_for(browser_pid)).ok()?;
+        serde_json::from_slice(&content).ok()
+    }
+
+    /// État du navigateur auquel appartient un processus : l'hôte enregistre le PID du processus
+    /// principal, le processus surveillé peut être un de ses enfants
+    pub fn load_for_process(&self, pid: u32, parent_of: impl Fn(u32) -> Option<u32>) -> Option<BrowserTabState> {
+        let mut current = pid;
+        for _ in 0..MAX_ANCESTOR_DEPTH {
+            if let Some(state) = self.load(current) {
+                return Some(state);
+            }
+            match parent_of(current) {
+                Some(parent) if parent != current && parent != 0 => current = parent,
+                _ => break,
+            }
+        }
+        None
+    }
+
+    /// États de tous les navigateurs connectés
+    pub fn load_all(&self) -> Vec<BrowserTabState> {
+        let Ok(entries) = std::fs::read_dir(&self.directory) else {
+            return Vec::new();
+        };
+
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().map(|ext| ext == "json").unwrap_or(false))
+            .filter_map(|entry| std::fs::read(entry.path()).ok())
+            .filter_map(|content| serde_json::from_slice(&content).ok())
+            .collect()
+    }
+
+    pub fn save(&self, state: &BrowserTabState) -> Result<()> {
+        create_private_dir(&self.directory)?;
+
+        // Écriture atomique pour ne jamais exposer un fichier à moitié écrit au moniteur
+        let path = self.path_for(state.browser_pid);
+        let temp_path = path.with_extension("json.tmp");
+        write_private_file(&temp_path, &serde_json::to_vec(state)?)?;
+        std::fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+
+    pub fn remove(&self, browser_pid: u32) {
+        let _ = std::fs::remove_file(self.path_for(browser_pid));
+    }
+
+    fn path_for(&self, browser_pid: u32) -> PathBuf {
+        self.directory.join(format!("{}.json", browser_pid))
+    }
+}
+
+/// Hôte native messaging : reçoit les événements de l'extension et met à jour le store
+pub struct NativeMessagingHost {
+    store: BrowserTabStore,
+    state: BrowserTabState,
+}
+
+impl NativeMessagingHost {
+    pub fn new(browser_pid: u32, store: BrowserTabStore) -> Self {
+        Self {
+            store,
+            state: BrowserTabState {
+                browser_pid,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Boucle principale : lit les messages jusqu'à la fermeture du flux par le navigateur
+    pub fn run<R: Read, W: Write>(&mut self, reader: &mut R, writer: &mut W) -> Result<()> {
+        let result = self.process_messages(reader, writer);
+
+        // Le navigateur ferme stdin quand l'extension se déconnecte : l'état n'est plus fiable
+        self.store.remove(self.state.browser_pid);
+        result
+    }
+
+    fn process_messages<R: Read, W: Write>(&mut self, reader: &mut R, writer: &mut W) -> Result<()> {
+        while let Some(message) = read_message(reader)? {
+            let response = match self.handle_message(message) {
+                Ok(response) => response,
+                Err(e) => HostResponse::Error {
+                    message: e.to_string(),
+                },
+            };
+            write_message(writer, &response)?;
+        }
+        Ok(())
+    }
+
+    pub fn handle_message(&mut self, message: ExtensionMessage) -> Result<HostResponse> {
+        let now = current_millis();
+
+        match message {
+            ExtensionMessage::Ping => {
+                return Ok(HostResponse::Pong {
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                });
+            }
+            ExtensionMessage::ActiveTab { tab_id, window_id, url, title, incognito, audible } => {
+                let tab = BrowserTabInfo {
+                    browser_pid: self.state.browser_pid,
+                    tab_id,
+                    window_id,
+                    url,
+                    title,
+                    is_incognito: incognito,
+                    is_audible: audible,
+                    updated_at: now,
+                };
+                self.update_audible(&tab);
+                self.state.active_tab = Some(tab);
+            }
+            ExtensionMessage::AudibleChanged { tab_id, audible, url, title, incognito } => {
+                let active_tab = self.state.active_tab.as_mut().filter(|tab| tab.tab_id == tab_id);
+                let tab = match active_tab {
+                    Some(active) => {
+                        active.is_audible = audible;
+                        active.updated_at = now;
+                        active.clone()
+                    }
+                    None => BrowserTabInfo {
+                        browser_pid: self.state.browser_pid,
+                        tab_id,
+                        window_id: None,
+                        url: url.unwrap_or_default(),
+                        title: title.unwrap_or_default(),
+                        is_incognito: incognito,
+                        is_audible: audible,
+                        updated_at: now,
+                    },
+                };
+                self.update_audible(&tab);
+            }
+            ExtensionMessage::TabClosed { tab_id } => {
+                self.state.audible_tabs.retain(|tab| tab.tab_id != tab_id);
+                if self.state.active_tab.as_ref().map(|tab| tab.tab_id) == Some(tab_id) {
+                    self.state.active_tab = None;
+                }
+            }
+        }
+
+        self.state.updated_at = now;
+        self.store.save(&self.state)?;
+        Ok(HostResponse::Ack)
+    }
+
+    pub fn state(&self) -> &BrowserTabState {
+        &self.state
+    }
+
+    fn update_audible(&mut self, tab: &BrowserTabInfo) {
+        self.state.audible_tabs.retain(|existing| existing.tab_id != tab.tab_id);
+        if tab.is_audible {
+            self.state.audible_tabs.push(tab.clone());
+        }
+    }
+}
+
+/// $XDG_RUNTIME_DIR (tmpfs 0700 de la session) sous Linux, %LOCALAPPDATA% sous Windows
+fn default_directory() -> PathBuf {
+    #[cfg(windows)]
+    let directory = std::env::var_os("LOCALAPPDATA")
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("sup_mtracker")
+        .join("browser_tabs");
+    #[cfg(unix)]
+    let directory = match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(runtime_dir) => PathBuf::from(runtime_dir).join("sup_mtracker").join("browser_tabs"),
+        // Sans session systemd : répertoire suffixé par l'UID directement dans /tmp, vérifié à la création
+        None => std::env::temp_dir().join(format!(
+            "sup_mtracker-{}-browser_tabs",
+            crate::metadata::owner::current_user_id().unwrap_or_default()
+        )),
+    };
+
+    directory
+}
+
+/// Créer le répertoire en 0700 et refuser un répertoire préparé par un autre utilisateur
+#[cfg(unix)]
+fn create_private_dir(directory: &Path) -> Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+    std::fs::DirBuilder::new().recursive(true).mode(0o700).create(directory)?;
+
+    let metadata = std::fs::symlink_metadata(directory)?;
+    if !metadata.is_dir() || Some(metadata.uid().to_string()) != crate::metadata::owner::current_user_id() {
+        return Err(anyhow::anyhow!("{} n'appartient pas à l'utilisateur courant", directory.display()));
+    }
+    if metadata.mode() & 0o077 != 0 {
+        std::fs::set_permissions(directory, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn create_private_dir(directory: &Path) -> Result<()> {
+    // %LOCALAPPDATA% n'est accessible qu'à son propriétaire (ACL héritées du profil)
+    std::fs::create_dir_all(directory)?;
+    Ok(())
+}
+
+/// Fichier créé en 0600 (indépendamment de l'umask)
+fn write_private_file(path: &Path, content: &[u8]) -> Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+    // Un fichier préexistant conserve ses droits : les restreindre explicitement
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(content)?;
+    Ok(())
+}
+
+/// Retrouver le PID du navigateur qui a lancé l'hôte.
+/// Sous Windows, Chrome passe par cmd.exe : on remonte tant que le parent est un shell.
+pub fn resolve_browser_pid() -> Option<u32> {
+    #[cfg(windows)]
+    {
+        let parents = windows_parent_map();
+        let mut pid = parents.get(&std::process::id())?.0;
+        while let Some((parent, name)) = parents.get(&pid) {
+            if name != "cmd.exe" && name != "conhost.exe" {
+                break;
+            }
+            pid = *parent;
+        }
+        Some(pid)
+    }
+
+    #[cfg(unix)]
+    {
+        Some(std::os::unix::process::parent_id())
+    }
+}
+
+#[cfg(windows)]
+fn windows_parent_map() -> std::collections::HashMap<u32, (u32, String)> {
+    use std::mem;
+    use winapi::um::{
+        handleapi::{CloseHandle, INVALID_HANDLE_VALUE},
+        tlhelp32::{CreateToolhelp32Snapshot, Process32First, Process32Next, PROCESSENTRY32, TH32CS_SNAPPROCESS},
+    };
+
+    let mut parents = std::collections::HashMap::new();
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return parents;
+        }
+
+        let mut pe32: PROCESSENTRY32 = mem::zeroed();
+        pe32.dwSize = mem::size_of::<PROCESSENTRY32>() as u32;
+        if Process32First(snapshot, &mut pe32) != 0 {
+            loop {
+                let name = std::ffi::CStr::from_ptr(pe32.szExeFile.as_ptr())
+                    .to_string_lossy()
+                    .to_lowercase();
+                parents.insert(pe32.th32ProcessID, (pe32.th32ParentProcessID, name));
+
+                if Process32Next(snapshot, &mut pe32) == 0 {
+                    break;
+                }
+            }
+        }
+        CloseHandle(snapshot);
+    }
+    parents
+}
+
+fn current_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn test_store(name: &str) -> BrowserTabStore {
+        let directory = std::env::temp_dir().join(format!("sup_mtracker_test_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        BrowserTabStore::with_directory(directory)
+    }
+
+    fn framed(payload: &[u8]) -> Vec<u8> {
+        let mut buffer = (payload.len() as u32).to_ne_bytes().to_vec();
+        buffer.extend_from_slice(payload);
+        buffer
+    }
+
+    fn active_tab(tab_id: i64, url: &str, audible: bool) -> ExtensionMessage {
+        ExtensionMessage::ActiveTab {
+            tab_id,
+            window_id: Some(1),
+            url: url.to_string(),
+            title: format!("Onglet {}", tab_id),
+            incognito: false,
+            audible,
+        }
+    }
+
+    /// Réponses de l'hôte, relues avec le même découpage que l'extension
+    fn responses(output: &[u8]) -> Vec<HostResponse> {
+        let mut reader = Cursor::new(output);
+        let mut responses = Vec::new();
+        let mut length = [0u8; 4];
+        while reader.read_exact(&mut length).is_ok() {
+            let mut payload = vec![0u8; u32::from_ne_bytes(length) as usize];
+            reader.read_exact(&mut payload).unwrap();
+            responses.push(serde_json::from_slice(&payload).unwrap());
+        }
+        responses
+    }
+
+    #[test]
+    fn length_prefix_round_trip() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &ExtensionMessage::Ping).unwrap();
+
+        let length = u32::from_ne_bytes(buffer[..4].try_into().unwrap()) as usize;
+        assert_eq!(length, buffer.len() - 4);
+        assert_eq!(&buffer[4..], br#"{"type":"ping"}"#);
+
+        let mut reader = Cursor::new(buffer);
+        assert!(matches!(read_message(&mut reader).unwrap(), Some(ExtensionMessage::Ping)));
+        assert!(read_message(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn consecutive_messages() {
+        let mut buffer = framed(br#"{"type":"tab_closed","tab_id":7}"#);
+        buffer.extend(framed(br#"{"type":"ping"}"#));
+
+        let mut reader = Cursor::new(buffer);
+        assert!(matches!(
+            read_message(&mut reader).unwrap(),
+            Some(ExtensionMessage::TabClosed { tab_id: 7 })
+        ));
+        assert!(matches!(read_message(&mut reader).unwrap(), Some(ExtensionMessage::Ping)));
+        assert!(read_message(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn oversized_messages_are_rejected() {
+        // Le préfixe suffit : la charge utile n'est jamais allouée
+        let mut reader = Cursor::new(((MAX_MESSAGE_SIZE + 1) as u32).to_ne_bytes().to_vec());
+        assert!(read_message(&mut reader).is_err());
+
+        let message = active_tab(1, &"a".repeat(MAX_MESSAGE_SIZE), false);
+        let mut buffer = Vec::new();
+        assert!(write_message(&mut buffer, &message).is_err());
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn truncated_messages_are_errors() {
+        let mut payload = framed(br#"{"type":"ping"}"#);
+        payload.truncate(payload.len() - 3);
+        assert!(read_message(&mut Cursor::new(payload)).is_err());
+
+        assert!(read_message(&mut Cursor::new(vec![15u8, 0])).is_err());
+        assert!(read_message(&mut Cursor::new(Vec::new())).unwrap().is_none());
+    }
+
+    #[test]
+    fn invalid_json_is_an_error() {
+        assert!(read_message(&mut Cursor::new(framed(b"{\"type\":\"unknown\"}"))).is_err());
+        assert!(read_message(&mut Cursor::new(framed(b"not json"))).is_err());
+    }
+
+    #[test]
+    fn scripted_extension_session() {
+        let store = test_store("session");
+        let directory = store.directory.clone();
+
+        let script = [
+            ExtensionMessage::Ping,
+            active_tab(1, "https://github.com/SUP2Ak/sup_mtracker", false),
+            ExtensionMessage::AudibleChanged {
+                tab_id: 2,
+                audible: true,
+                url: Some("https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string()),
+                title: Some("Never Gonna Give You Up - YouTube".to_string()),
+                incognito: false,
+            },
+            ExtensionMessage::TabClosed { tab_id: 2 },
+        ];
+        let mut input = Vec::new();
+        for message in &script {
+            write_message(&mut input, message).unwrap();
+        }
+
+        let mut host = NativeMessagingHost::new(4242, store);
+        let mut output = Vec::new();
+        host.run(&mut Cursor::new(input), &mut output).unwrap();
+
+        let responses = responses(&output);
+        assert_eq!(responses.len(), script.len());
+        assert!(matches!(responses[0], HostResponse::Pong { .. }));
+        assert!(responses[1..].iter().all(|response| matches!(response, HostResponse::Ack)));
+
+        let state = host.state();
+        assert_eq!(state.active_tab.as_ref().map(|tab| tab.tab_id), Some(1));
+        assert!(state.audible_tabs.is_empty());
+
+        // Fin du flux : l'état n'est plus publié
+        assert!(BrowserTabStore::with_directory(directory.clone()).load(4242).is_none());
+        let _ = std::fs::remove_dir_all(directory);
+    }
+
+    #[test]
+    fn state_is_shared_through_the_store() {
+        let store = test_store("shared");
+        let directory = store.directory.clone();
+        let mut host = NativeMessagingHost::new(100, store);
+        host.handle_message(active_tab(3, "https://example.com", true)).unwrap();
+
+        let reader = BrowserTabStore::with_directory(directory.clone());
+        let state = reader.load(100).unwrap();
+        assert_eq!(state.audible_tabs.len(), 1);
+
+        // Un renderer (101) dont le parent est le navigateur (100) retrouve le même état
+        let parents = |pid: u32| match pid {
+            101 => Some(100),
+            100 => Some(1),
+            _ => None,
+        };
+        assert_eq!(reader.load_for_process(101, parents).map(|state| state.browser_pid), Some(100));
+        assert!(reader.load_for_process(200, parents).is_none());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&directory), 0o700);
+            assert_eq!(mode(&directory.join("100.json")), 0o600);
+        }
+        let _ = std::fs::remove_dir_all(directory);
+    }
+}
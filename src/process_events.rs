@@ -0,0 +1,719 @@
+use crate::models::ProcessEntry;
+use crate::ProcessScanner;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sup_common::{debug_eprintln, debug_println};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// Événement de cycle de vie d'un processus (timestamp en millisecondes depuis l'epoch)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ProcessEvent {
+    Started {
+        pid: u32,
+        ppid: u32,
+        exe: String,
+        /// Heure de création du processus : distingue un PID réutilisé
+        #[serde(default)]
+        start_time: Option<u64>,
+        timestamp: u64,
+    },
+    Exited {
+        pid: u32,
+        exit_code: Option<i32>,
+        timestamp: u64,
+    },
+}
+
+impl ProcessEvent {
+    pub fn pid(&self) -> u32 {
+        match self {
+            Self::Started { pid, .. } | Self::Exited { pid, .. } => *pid,
+        }
+    }
+}
+
+/// Backend effectivement utilisé par la source d'événements
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProcessEventBackend {
+    /// Connecteur proc netlink (Linux, nécessite CAP_NET_ADMIN)
+    NetlinkConnector,
+    /// Diff de la liste des processus à intervalle régulier (portable)
+    Polling,
+}
+
+/// Source d'événements start/exit des processus
+pub struct ProcessEventSource {
+    backend: ProcessEventBackend,
+    stop_requested: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ProcessEventSource {
+    /// Démarrer avec le meilleur backend disponible, le polling servant de repli
+    pub fn start(poll_interval: Duration) -> Result<(Self, UnboundedReceiver<ProcessEvent>)> {
+        #[cfg(target_os = "linux")]
+        {
+            match netlink::ProcConnector::open() {
+                Ok(connector) => return Self::spawn(ProcessEventBackend::NetlinkConnector, move |sender, stop| {
+                    if let Err(e) = connector.run(sender, stop) {
+                        debug_eprintln!("❌ Erreur du connecteur proc netlink: {}", e);
+                    }
+                }),
+                Err(e) => {
+                    debug_println!("⚠️ Connecteur proc netlink indisponible ({}), repli sur le polling", e);
+                }
+            }
+        }
+
+        Self::start_polling(poll_interval)
+    }
+
+    /// Démarrer explicitement le backend par polling
+    pub fn start_polling(poll_interval: Duration) -> Result<(Self, UnboundedReceiver<ProcessEvent>)> {
+        Self::spawn(ProcessEventBackend::Polling, move |sender, stop| {
+            run_polling(poll_interval, sender, stop)
+        })
+    }
+
+    pub fn backend(&self) -> ProcessEventBackend {
+        self.backend
+    }
+
+    pub fn stop(&mut self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+
+    fn spawn<F>(backend: ProcessEventBackend, run: F) -> Result<(Self, UnboundedReceiver<ProcessEvent>)>
+    where
+        F: FnOnce(UnboundedSender<ProcessEvent>, Arc<AtomicBool>) + Send + 'static,
+    {
+        let (sender, receiver) = unbounded_channel();
+        let stop_requested = Arc::new(AtomicBool::new(false));
+        let stop_clone = Arc::clone(&stop_requested);
+
+        let thread = std::thread::Builder::new()
+            .name("sup_mtracker-process-events".to_string())
+            .spawn(move || run(sender, stop_clone))?;
+
+        Ok((
+            Self {
+                backend,
+                stop_requested,
+                thread: Some(thread),
+            },
+            receiver,
+        ))
+    }
+}
+
+impl Drop for ProcessEventSource {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Diff entre deux listes de processus. Un PID réutilisé (heure de démarrage différente)
+/// produit un Exited suivi d'un Started.
+pub fn diff_process_lists(
+    previous: &HashMap<u32, ProcessEntry>,
+    current: &HashMap<u32, ProcessEntry>,
+    timestamp: u64,
+) -> Vec<ProcessEvent> {
+    let mut events = Vec::new();
+
+    for (pid, entry) in previous {
+        let still_running = current
+            .get(pid)
+            .map(|other| other.start_time == entry.start_time)
+            .unwrap_or(false);
+        if !still_running {
+            events.push(ProcessEvent::Exited {
+                pid: *pid,
+                exit_code: None, // Inconnu en polling
+                timestamp,
+            });
+        }
+    }
+
+    for (pid, entry) in current {
+        let is_new = previous
+            .get(pid)
+            .map(|other| other.start_time != entry.start_time)
+            .unwrap_or(true);
+        if is_new {
+            events.push(ProcessEvent::Started {
+                pid: *pid,
+                ppid: entry.parent_pid,
+                exe: executable_for(entry),
+                start_time: entry.start_time,
+                timestamp,
+            });
+        }
+    }
+
+    events
+}
+
+/// Liste courante indexée par PID. Toolhelp ne fournit pas l'heure de création : elle n'est demandée
+/// (OpenProcess + GetProcessTimes) que pour les PID nouveaux ou dont le parent ou le nom a changé,
+/// les autres la reprennent du tour précédent.
+fn with_start_times(
+    previous: &HashMap<u32, ProcessEntry>,
+    entries: Vec<ProcessEntry>,
+    start_time_of: impl Fn(u32) -> Option<u64>,
+) -> HashMap<u32, ProcessEntry> {
+    entries
+        .into_iter()
+        .map(|mut entry| {
+            if entry.start_time.is_none() {
+                entry.start_time = match previous.get(&entry.pid) {
+                    Some(known) if known.parent_pid == entry.parent_pid && known.name == entry.name => known.start_time,
+                    _ => start_time_of(entry.pid),
+                };
+            }
+            (entry.pid, entry)
+        })
+        .collect()
+}
+
+fn run_polling(poll_interval: Duration, sender: UnboundedSender<ProcessEvent>, stop_requested: Arc<AtomicBool>) {
+    let scanner = ProcessScanner::new();
+    let snapshot = |previous: &HashMap<u32, ProcessEntry>| -> HashMap<u32, ProcessEntry> {
+        scanner
+            .list_process_entries()
+            .map(|entries| with_start_times(previous, entries, process_start_time))
+            .unwrap_or_default()
+    };
+
+    // L'état initial sert de référence : pas d'événements pour les processus déjà lancés
+    let mut previous = snapshot(&HashMap::new());
+
+    while !stop_requested.load(Ordering::SeqCst) {
+        std::thread::sleep(poll_interval);
+
+        let current = snapshot(&previous);
+        if current.is_empty() {
+            continue; // Échec du snapshot, on garde la référence
+        }
+
+        for event in diff_process_lists(&previous, &current, current_millis()) {
+            if sender.send(event).is_err() {
+                return; // Plus personne n'écoute
+            }
+        }
+        previous = current;
+    }
+}
+
+// Chemin complet si accessible, sinon le nom de l'entrée
+fn executable_for(entry: &ProcessEntry) -> String {
+    #[cfg(target_os = "linux")]
+    {
+        crate::linux::procfs::exe_path(entry.pid).unwrap_or_else(|| entry.name.clone())
+    }
+
+    #[cfg(windows)]
+    {
+        process_image_path(entry.pid).unwrap_or_else(|| entry.name.clone())
+    }
+}
+
+/// Heure de création d'un processus en millisecondes depuis l'epoch
+pub fn process_start_time(pid: u32) -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        use crate::linux::procfs;
+        let stat = procfs::read_stat(pid)?;
+        Some(procfs::boot_time_millis()? + procfs::ticks_to_millis(stat.start_time_ticks))
+    }
+
+    #[cfg(windows)]
+    unsafe {
+        use std::mem;
+        use winapi::shared::minwindef::FILETIME;
+        use winapi::um::handleapi::CloseHandle;
+        use winapi::um::processthreadsapi::{GetProcessTimes, OpenProcess};
+        use winapi::um::winnt::PROCESS_QUERY_LIMITED_INFORMATION;
+
+        // Écart entre l'epoch FILETIME (1601) et l'epoch Unix, en millisecondes
+        const FILETIME_UNIX_OFFSET_MS: u64 = 11_644_473_600_000;
+
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return None;
+        }
+
+        let mut creation: FILETIME = mem::zeroed();
+        let mut exit: FILETIME = mem::zeroed();
+        let mut kernel: FILETIME = mem::zeroed();
+        let mut user: FILETIME = mem::zeroed();
+        let ok = GetProcessTimes(handle, &mut creation, &mut exit, &mut kernel, &mut user) != 0;
+        CloseHandle(handle);
+
+        if !ok {
+            return None;
+        }
+        let ticks = ((creation.dwHighDateTime as u64) << 32) | creation.dwLowDateTime as u64;
+        (ticks / 10_000).checked_sub(FILETIME_UNIX_OFFSET_MS)
+    }
+}
+
+#[cfg(windows)]
+fn process_image_path(pid: u32) -> Option<String> {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::OsStringExt;
+    use winapi::shared::minwindef::MAX_PATH;
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::processthreadsapi::OpenProcess;
+    use winapi::um::winbase::QueryFullProcessImageNameW;
+    use winapi::um::winnt::PROCESS_QUERY_LIMITED_INFORMATION;
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return None;
+        }
+
+        let mut buffer: [u16; MAX_PATH] = [0; MAX_PATH];
+        let mut size = MAX_PATH as u32;
+        let result = if QueryFullProcessImageNameW(handle, 0, buffer.as_mut_ptr(), &mut size) != 0 {
+            OsString::from_wide(&buffer[..size as usize]).into_string().ok()
+        } else {
+            None
+        };
+        CloseHandle(handle);
+        result
+    }
+}
+
+fn current_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(target_os = "linux")]
+mod netlink {
+    use super::{current_millis, process_start_time, ProcessEvent};
+    use crate::linux::procfs;
+    use anyhow::Result;
+    use std::collections::HashMap;
+    use std::io::Error;
+    use std::mem;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+    use tokio::sync::mpsc::UnboundedSender;
+
+    // Constantes de linux/connector.h et linux/cn_proc.h
+    const NETLINK_CONNECTOR: libc::c_int = 11;
+    const CN_IDX_PROC: u32 = 1;
+    const CN_VAL_PROC: u32 = 1;
+    const PROC_CN_MCAST_LISTEN: u32 = 1;
+    const PROC_CN_MCAST_IGNORE: u32 = 2;
+    const PROC_EVENT_FORK: u32 = 0x0000_0001;
+    const PROC_EVENT_EXEC: u32 = 0x0000_0002;
+    const PROC_EVENT_EXIT: u32 = 0x8000_0000;
+
+    const NLMSG_HEADER_LEN: usize = 16;
+    const CN_MSG_HEADER_LEN: usize = 20;
+    // what, cpu, timestamp_ns
+    const PROC_EVENT_HEADER_LEN: usize = 16;
+
+    // Un fork suivi d'un exec n'est annoncé qu'à l'exec ; sans exec dans ce délai
+    // (zygote, workers préforkés), l'enfant est annoncé avec l'image du parent
+    const FORK_EXEC_GRACE: Duration = Duration::from_millis(200);
+
+    /// Événements bruts du connecteur, pour les processus uniquement (pid == tgid)
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum ConnectorEvent {
+        Fork { pid: u32, ppid: u32 },
+        Exec { pid: u32 },
+        Exit { pid: u32, exit_code: i32 },
+    }
+
+    /// Un seul Started par fork + exec. Un nouvel exec du même processus (`sh -c "exec app"`)
+    /// n'est réannoncé que si l'exécutable change ; l'heure de création écarte les PID réutilisés.
+    #[derive(Default)]
+    struct StartTracker {
+        pending_forks: HashMap<u32, (u32, Instant)>,
+        announced: HashMap<u32, (Option<u64>, String)>,
+    }
+
+    impl StartTracker {
+        fn handle(&mut self, event: ConnectorEvent) -> Option<ProcessEvent> {
+            match event {
+                ConnectorEvent::Fork { pid, ppid } => {
+                    self.pending_forks.insert(pid, (ppid, Instant::now()));
+                    None
+                }
+                ConnectorEvent::Exec { pid } => {
+                    let ppid = self.pending_forks.remove(&pid).map(|(ppid, _)| ppid);
+                    self.started(pid, ppid)
+                }
+                ConnectorEvent::Exit { pid, exit_code } => {
+                    self.pending_forks.remove(&pid);
+                    self.announced.remove(&pid);
+                    Some(ProcessEvent::Exited {
+                        pid,
+                        exit_code: Some(exit_code),
+                        timestamp: current_millis(),
+                    })
+                }
+            }
+        }
+
+        /// Forks sans exec après le délai de grâce
+        fn expired_forks(&mut self) -> Vec<ProcessEvent> {
+            let expired: Vec<(u32, u32)> = self
+                .pending_forks
+                .iter()
+                .filter(|(_, (_, forked_at))| forked_at.elapsed() >= FORK_EXEC_GRACE)
+                .map(|(pid, (ppid, _))| (*pid, *ppid))
+                .collect();
+
+            expired
+                .into_iter()
+                .filter_map(|(pid, ppid)| {
+                    self.pending_forks.remove(&pid);
+                    self.started(pid, Some(ppid))
+                })
+                .collect()
+        }
+
+        fn started(&mut self, pid: u32, ppid: Option<u32>) -> Option<ProcessEvent> {
+            let stat = procfs::read_stat(pid);
+            let start_time = process_start_time(pid);
+            let ppid = ppid.or_else(|| stat.as_ref().map(|stat| stat.ppid)).unwrap_or(0);
+            let exe = procfs::exe_path(pid)
+                .or_else(|| stat.map(|stat| stat.comm))
+                .unwrap_or_default();
+
+            let announced = (start_time, exe.clone());
+            if self.announced.get(&pid) == Some(&announced) {
+                return None;
+            }
+            self.announced.insert(pid, announced);
+
+            Some(ProcessEvent::Started {
+                pid,
+                ppid,
+                exe,
+                start_time,
+                timestamp: current_millis(),
+            })
+        }
+    }
+
+    pub struct ProcConnector {
+        fd: libc::c_int,
+    }
+
+    impl ProcConnector {
+        pub fn open() -> Result<Self> {
+            unsafe {
+                let fd = libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, NETLINK_CONNECTOR);
+                if fd < 0 {
+                    return Err(Error::last_os_error().into());
+                }
+                let connector = Self { fd };
+
+                let mut address: libc::sockaddr_nl = mem::zeroed();
+                address.nl_family = libc::AF_NETLINK as u16;
+                address.nl_pid = 0; // Attribué par le noyau
+                address.nl_groups = CN_IDX_PROC;
+                if libc::bind(
+                    fd,
+                    &address as *const _ as *const libc::sockaddr,
+                    mem::size_of::<libc::sockaddr_nl>() as u32,
+                ) < 0
+                {
+                    return Err(Error::last_os_error().into());
+                }
+
+                // Réveil périodique pour vérifier la demande d'arrêt
+                let timeout = libc::timeval { tv_sec: 0, tv_usec: 500_000 };
+                libc::setsockopt(
+                    fd,
+                    libc::SOL_SOCKET,
+                    libc::SO_RCVTIMEO,
+                    &timeout as *const _ as *const libc::c_void,
+                    mem::size_of::<libc::timeval>() as u32,
+                );
+
+                // Échoue sans CAP_NET_ADMIN : le repli sur le polling prend le relais
+                connector.send_control(PROC_CN_MCAST_LISTEN)?;
+                Ok(connector)
+            }
+        }
+
+        pub fn run(&self, sender: UnboundedSender<ProcessEvent>, stop_requested: Arc<AtomicBool>) -> Result<()> {
+            let mut buffer = [0u8; 4096];
+            let mut tracker = StartTracker::default();
+
+            while !stop_requested.load(Ordering::SeqCst) {
+                let received = unsafe { libc::recv(self.fd, buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), 0) };
+                let mut events = Vec::new();
+                if received < 0 {
+                    let error = Error::last_os_error();
+                    match error.raw_os_error() {
+                        // Tampon saturé (ENOBUFS) : des événements ont été perdus mais on continue
+                        Some(libc::EAGAIN) | Some(libc::EINTR) | Some(libc::ENOBUFS) => {}
+                        _ => return Err(error.into()),
+                    }
+                } else if let Some(event) = parse_message(&buffer[..received as usize]) {
+                    events.extend(tracker.handle(event));
+                }
+                events.extend(tracker.expired_forks());
+
+                for event in events {
+                    if sender.send(event).is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+
+            Ok(())
+        }
+
+        fn send_control(&self, operation: u32) -> Result<()> {
+            let total_len = NLMSG_HEADER_LEN + CN_MSG_HEADER_LEN + 4;
+            let mut message = Vec::with_capacity(total_len);
+
+            // nlmsghdr
+            message.extend_from_slice(&(total_len as u32).to_ne_bytes());
+            message.extend_from_slice(&(libc::NLMSG_DONE as u16).to_ne_bytes());
+            message.extend_from_slice(&0u16.to_ne_bytes()); // flags
+            message.extend_from_slice(&0u32.to_ne_bytes()); // seq
+            message.extend_from_slice(&std::process::id().to_ne_bytes());
+            // cn_msg
+            message.extend_from_slice(&CN_IDX_PROC.to_ne_bytes());
+            message.extend_from_slice(&CN_VAL_PROC.to_ne_bytes());
+            message.extend_from_slice(&0u32.to_ne_bytes()); // seq
+            message.extend_from_slice(&0u32.to_ne_bytes()); // ack
+            message.extend_from_slice(&4u16.to_ne_bytes()); // len
+            message.extend_from_slice(&0u16.to_ne_bytes()); // flags
+            // enum proc_cn_mcast_op
+            message.extend_from_slice(&operation.to_ne_bytes());
+
+            let sent = unsafe { libc::send(self.fd, message.as_ptr() as *const libc::c_void, message.len(), 0) };
+            if sent < 0 {
+                return Err(Error::last_os_error().into());
+            }
+            Ok(())
+        }
+    }
+
+    impl Drop for ProcConnector {
+        fn drop(&mut self) {
+            let _ = self.send_control(PROC_CN_MCAST_IGNORE);
+            unsafe {
+                libc::close(self.fd);
+            }
+        }
+    }
+
+    fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+        data.get(offset..offset + 4).map(|bytes| u32::from_ne_bytes(bytes.try_into().unwrap()))
+    }
+
+    // Seuls les processus (pid == tgid) nous intéressent, pas les threads
+    fn parse_message(data: &[u8]) -> Option<ConnectorEvent> {
+        let event = data.get(NLMSG_HEADER_LEN + CN_MSG_HEADER_LEN..)?;
+        let what = read_u32(event, 0)?;
+        let payload = event.get(PROC_EVENT_HEADER_LEN..)?;
+
+        match what {
+            PROC_EVENT_FORK => {
+                let parent_tgid = read_u32(payload, 4)?;
+                let child_pid = read_u32(payload, 8)?;
+                let child_tgid = read_u32(payload, 12)?;
+                (child_pid == child_tgid).then_some(ConnectorEvent::Fork {
+                    pid: child_tgid,
+                    ppid: parent_tgid,
+                })
+            }
+            PROC_EVENT_EXEC => {
+                let pid = read_u32(payload, 0)?;
+                let tgid = read_u32(payload, 4)?;
+                (pid == tgid).then_some(ConnectorEvent::Exec { pid: tgid })
+            }
+            PROC_EVENT_EXIT => {
+                let pid = read_u32(payload, 0)?;
+                let tgid = read_u32(payload, 4)?;
+                let exit_code = decode_wait_status(read_u32(payload, 8)?);
+                (pid == tgid).then_some(ConnectorEvent::Exit { pid: tgid, exit_code })
+            }
+            _ => None,
+        }
+    }
+
+    // Statut wait(2) : code de sortie si terminaison normale, sinon -signal
+    fn decode_wait_status(status: u32) -> i32 {
+        let signal = (status & 0x7f) as i32;
+        if signal == 0 {
+            ((status >> 8) & 0xff) as i32
+        } else {
+            -signal
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        // nlmsghdr + cn_msg + en-tête proc_event + charge utile
+        fn message(what: u32, payload: &[u32]) -> Vec<u8> {
+            let mut data = vec![0u8; NLMSG_HEADER_LEN + CN_MSG_HEADER_LEN];
+            data.extend_from_slice(&what.to_ne_bytes());
+            data.extend_from_slice(&0u32.to_ne_bytes()); // cpu
+            data.extend_from_slice(&123_456_789u64.to_ne_bytes()); // timestamp_ns
+            for value in payload {
+                data.extend_from_slice(&value.to_ne_bytes());
+            }
+            data
+        }
+
+        #[test]
+        fn exit_status_is_decoded() {
+            // exit(3) : code dans l'octet haut du statut wait(2)
+            let exited = message(PROC_EVENT_EXIT, &[4242, 4242, 3 << 8, 17]);
+            assert_eq!(parse_message(&exited), Some(ConnectorEvent::Exit { pid: 4242, exit_code: 3 }));
+
+            let killed = message(PROC_EVENT_EXIT, &[4242, 4242, libc::SIGKILL as u32, 17]);
+            assert_eq!(parse_message(&killed), Some(ConnectorEvent::Exit { pid: 4242, exit_code: -libc::SIGKILL }));
+
+            // SIGSEGV avec core dump (bit 0x80)
+            let dumped = message(PROC_EVENT_EXIT, &[4242, 4242, 0x80 | libc::SIGSEGV as u32, 17]);
+            assert_eq!(parse_message(&dumped), Some(ConnectorEvent::Exit { pid: 4242, exit_code: -libc::SIGSEGV }));
+
+            // Fin d'un thread : ignorée
+            assert_eq!(parse_message(&message(PROC_EVENT_EXIT, &[4243, 4242, 0, 0])), None);
+        }
+
+        #[test]
+        fn fork_and_exec_keep_processes_only() {
+            // parent_pid, parent_tgid, child_pid, child_tgid
+            let fork = message(PROC_EVENT_FORK, &[100, 100, 200, 200]);
+            assert_eq!(parse_message(&fork), Some(ConnectorEvent::Fork { pid: 200, ppid: 100 }));
+            let thread = message(PROC_EVENT_FORK, &[100, 100, 201, 200]);
+            assert_eq!(parse_message(&thread), None);
+
+            assert_eq!(parse_message(&message(PROC_EVENT_EXEC, &[200, 200])), Some(ConnectorEvent::Exec { pid: 200 }));
+            // Message tronqué ou d'un autre type
+            assert_eq!(parse_message(&message(PROC_EVENT_EXEC, &[200])), None);
+            assert_eq!(parse_message(&message(0x0000_0004, &[200, 200, 0, 0])), None);
+            assert_eq!(parse_message(&[0u8; 8]), None);
+        }
+
+        #[test]
+        fn fork_then_exec_is_announced_once() {
+            let pid = std::process::id();
+            let mut tracker = StartTracker::default();
+
+            assert!(tracker.handle(ConnectorEvent::Fork { pid, ppid: 1 }).is_none());
+            let started = tracker.handle(ConnectorEvent::Exec { pid });
+            assert!(matches!(started, Some(ProcessEvent::Started { pid: started_pid, ppid: 1, .. }) if started_pid == pid));
+
+            // Le fork consommé par l'exec n'est pas réannoncé à l'expiration du délai
+            std::thread::sleep(FORK_EXEC_GRACE);
+            assert!(tracker.expired_forks().is_empty());
+            // Nouvel exec du même exécutable (`sh -c "exec ..."` sur soi-même) : rien de neuf
+            assert!(tracker.handle(ConnectorEvent::Exec { pid }).is_none());
+
+            assert!(matches!(
+                tracker.handle(ConnectorEvent::Exit { pid, exit_code: 0 }),
+                Some(ProcessEvent::Exited { exit_code: Some(0), .. })
+            ));
+            assert!(tracker.announced.is_empty());
+        }
+
+        #[test]
+        fn fork_without_exec_is_announced_after_the_grace_period() {
+            let pid = std::process::id();
+            let mut tracker = StartTracker::default();
+
+            tracker.handle(ConnectorEvent::Fork { pid, ppid: 1 });
+            assert!(tracker.expired_forks().is_empty());
+            std::thread::sleep(FORK_EXEC_GRACE);
+            let expired = tracker.expired_forks();
+            assert_eq!(expired.len(), 1);
+            assert!(matches!(expired[0], ProcessEvent::Started { ppid: 1, .. }));
+            assert!(tracker.pending_forks.is_empty());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    // PID hors de la plage du noyau : pas de /proc/<pid>/exe, le nom sert d'exécutable
+    const PID: u32 = 4_000_000_001;
+
+    fn entry(pid: u32, parent_pid: u32, name: &str, start_time: Option<u64>) -> ProcessEntry {
+        ProcessEntry {
+            pid,
+            parent_pid,
+            name: name.to_string(),
+            start_time,
+        }
+    }
+
+    fn list(entries: &[ProcessEntry]) -> HashMap<u32, ProcessEntry> {
+        entries.iter().map(|entry| (entry.pid, entry.clone())).collect()
+    }
+
+    #[test]
+    fn reused_pid_is_reported_as_exit_then_start() {
+        let previous = list(&[entry(PID, 1, "old", Some(1_000))]);
+        let current = list(&[entry(PID, 1, "new", Some(2_000))]);
+
+        let events = diff_process_lists(&previous, &current, 42);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], ProcessEvent::Exited { pid: PID, exit_code: None, timestamp: 42 }));
+        match &events[1] {
+            ProcessEvent::Started { pid, ppid, exe, start_time, .. } => {
+                assert_eq!((*pid, *ppid, exe.as_str(), *start_time), (PID, 1, "new", Some(2_000)));
+            }
+            other => panic!("Started attendu, reçu {:?}", other),
+        }
+
+        // Même instance : aucun événement
+        assert!(diff_process_lists(&previous, &previous, 42).is_empty());
+    }
+
+    #[test]
+    fn start_times_are_only_queried_for_new_processes() {
+        let queried = Cell::new(0);
+        let start_time_of = |pid: u32| {
+            queried.set(queried.get() + 1);
+            Some(pid as u64 * 10)
+        };
+
+        let previous = with_start_times(&HashMap::new(), vec![entry(10, 1, "a", None), entry(11, 1, "b", None)], start_time_of);
+        assert_eq!(queried.get(), 2);
+
+        // 10 inchangé, 11 réutilisé par un autre programme, 12 nouveau
+        queried.set(0);
+        let current = with_start_times(
+            &previous,
+            vec![entry(10, 1, "a", None), entry(11, 5, "c", None), entry(12, 1, "d", None), entry(13, 1, "e", Some(7))],
+            start_time_of,
+        );
+        assert_eq!(queried.get(), 2);
+        assert_eq!(current[&10].start_time, Some(100));
+        assert_eq!(current[&11].start_time, Some(110));
+        assert_eq!(current[&13].start_time, Some(7));
+    }
+}
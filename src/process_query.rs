@@ -0,0 +1,827 @@
+use crate::models::{ProcessEntry, ProcessInfo, ProcessMetadata};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Valeur d'un champ évalué par une requête
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryValue {
+    Number(f64),
+    Text(String),
+    Bool(bool),
+}
+
+/// Objet interrogeable par une requête (processus, métadonnées...)
+pub trait QuerySubject: Sized {
+    /// Valeur d'un champ simple (`name`, `mem`, `has_window`...), None si inconnue
+    fn field(&self, name: &str) -> Option<QueryValue>;
+
+    /// Processus parent, pour les champs `parent.xxx`
+    fn parent(&self) -> Option<Self> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Eq,
+    NotEq,
+    Contains,
+    NotContains,
+    Greater,
+    GreaterOrEq,
+    Less,
+    LessOrEq,
+}
+
+impl Operator {
+    fn symbol(self) -> &'static str {
+        match self {
+            Operator::Eq => "=",
+            Operator::NotEq => "!=",
+            Operator::Contains => "~",
+            Operator::NotContains => "!~",
+            Operator::Greater => ">",
+            Operator::GreaterOrEq => ">=",
+            Operator::Less => "<",
+            Operator::LessOrEq => "<=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldType {
+    Number,
+    Text,
+    Bool,
+}
+
+// Champs reconnus par la requête (et leur type), vérifiés à l'analyse
+const FIELDS: [(&str, FieldType); 8] = [
+    ("pid", FieldType::Number),
+    ("ppid", FieldType::Number),
+    ("name", FieldType::Text),
+    ("path", FieldType::Text),
+    ("title", FieldType::Text),
+    ("mem", FieldType::Number),
+    ("handles", FieldType::Number),
+    ("has_window", FieldType::Bool),
+];
+
+fn field_type(name: &str) -> Option<FieldType> {
+    FIELDS.iter().find(|(field, _)| *field == name).map(|(_, field_type)| *field_type)
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare {
+        path: Vec<String>,
+        operator: Operator,
+        value: QueryValue,
+    },
+    /// Champ booléen seul (`has_window`)
+    Flag(Vec<String>),
+}
+
+/// Requête de sélection de processus.
+///
+/// Syntaxe : `name~"chrome" and mem>500MB and has_window and parent.name="explorer.exe"`
+/// - opérateurs : `=`, `!=`, `~` (contient), `!~`, `>`, `>=`, `<`, `<=`
+/// - combinaisons : `and`, `or`, `not` (ou `&&`, `||`, `!`) et parenthèses
+/// - tailles : `B`, `KB`, `MB`, `GB`, `TB` (puissances de 1024)
+/// - champs : `pid`, `ppid`, `name`, `path`, `title`, `mem`, `handles`, `has_window`,
+///   préfixables par `parent.`
+#[derive(Debug, Clone)]
+pub struct ProcessQuery {
+    source: String,
+    expr: Expr,
+}
+
+impl ProcessQuery {
+    pub fn parse(input: &str) -> Result<Self> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, position: 0 };
+        let expr = parser.parse_or()?;
+
+        if let Some((token, offset)) = parser.tokens.get(parser.position) {
+            return Err(anyhow::anyhow!(
+                "Erreur de syntaxe à la position {}: '{}' inattendu",
+                offset,
+                token
+            ));
+        }
+
+        Ok(Self {
+            source: input.to_string(),
+            expr,
+        })
+    }
+
+    pub fn matches<S: QuerySubject>(&self, subject: &S) -> bool {
+        evaluate(&self.expr, subject)
+    }
+
+    /// Le processus peut-il correspondre, sachant seulement son nom, son chemin et son titre ?
+    /// Les autres champs sont inconnus : seule une requête exclue à coup sûr retourne false.
+    pub fn may_match(&self, name: &str, path: Option<&str>, title: Option<&str>) -> bool {
+        evaluate_partial(&self.expr, &PartialSubject { name, path, title }).unwrap_or(true)
+    }
+
+    /// La requête utilise-t-elle ce champ (directement ou via `parent.`) ?
+    pub fn uses_field(&self, field: &str) -> bool {
+        fn visit(expr: &Expr, field: &str) -> bool {
+            match expr {
+                Expr::And(left, right) | Expr::Or(left, right) => visit(left, field) || visit(right, field),
+                Expr::Not(inner) => visit(inner, field),
+                Expr::Compare { path, .. } | Expr::Flag(path) => path.last().map(|f| f == field).unwrap_or(false),
+            }
+        }
+        visit(&self.expr, field)
+    }
+
+    /// Nombre maximal de `parent.` enchaînés (0 si la requête ne porte que sur le processus)
+    pub fn parent_depth(&self) -> usize {
+        fn visit(expr: &Expr) -> usize {
+            match expr {
+                Expr::And(left, right) | Expr::Or(left, right) => visit(left).max(visit(right)),
+                Expr::Not(inner) => visit(inner),
+                Expr::Compare { path, .. } | Expr::Flag(path) => path.len().saturating_sub(1),
+            }
+        }
+        visit(&self.expr)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+impl FromStr for ProcessQuery {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for ProcessQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn resolve<S: QuerySubject>(subject: &S, path: &[String]) -> Option<QueryValue> {
+    match path {
+        [field] => subject.field(field),
+        [prefix, rest @ ..] if prefix == "parent" => resolve(&subject.parent()?, rest),
+        _ => None,
+    }
+}
+
+fn evaluate<S: QuerySubject>(expr: &Expr, subject: &S) -> bool {
+    match expr {
+        Expr::And(left, right) => evaluate(left, subject) && evaluate(right, subject),
+        Expr::Or(left, right) => evaluate(left, subject) || evaluate(right, subject),
+        Expr::Not(inner) => !evaluate(inner, subject),
+        Expr::Flag(path) => matches!(resolve(subject, path), Some(QueryValue::Bool(true))),
+        Expr::Compare { path, operator, value } => match resolve(subject, path) {
+            Some(actual) => compare(&actual, *operator, value),
+            // Champ inconnu pour ce processus : la comparaison échoue
+            None => false,
+        },
+    }
+}
+
+/// Évaluation à trois valeurs (None = inconnu) avec le nom et le chemin seuls
+/// Champs connus avant d'ouvrir le processus (None = inconnu)
+struct PartialSubject<'a> {
+    name: &'a str,
+    path: Option<&'a str>,
+    title: Option<&'a str>,
+}
+
+fn evaluate_partial(expr: &Expr, subject: &PartialSubject) -> Option<bool> {
+    match expr {
+        Expr::And(left, right) => match (evaluate_partial(left, subject), evaluate_partial(right, subject)) {
+            (Some(false), _) | (_, Some(false)) => Some(false),
+            (Some(true), Some(true)) => Some(true),
+            _ => None,
+        },
+        Expr::Or(left, right) => match (evaluate_partial(left, subject), evaluate_partial(right, subject)) {
+            (Some(true), _) | (_, Some(true)) => Some(true),
+            (Some(false), Some(false)) => Some(false),
+            _ => None,
+        },
+        Expr::Not(inner) => evaluate_partial(inner, subject).map(|value| !value),
+        Expr::Compare { path: field, operator, value } => {
+            let actual = match field.as_slice() {
+                [field] if field == "name" => QueryValue::Text(subject.name.to_string()),
+                [field] if field == "path" => QueryValue::Text(subject.path?.to_string()),
+                [field] if field == "title" => QueryValue::Text(subject.title?.to_string()),
+                _ => return None,
+            };
+            Some(compare(&actual, *operator, value))
+        }
+        Expr::Flag(_) => None,
+    }
+}
+
+fn compare(actual: &QueryValue, operator: Operator, expected: &QueryValue) -> bool {
+    match (actual, expected) {
+        (QueryValue::Number(a), QueryValue::Number(b)) => match operator {
+            Operator::Eq => a == b,
+            Operator::NotEq => a != b,
+            Operator::Greater => a > b,
+            Operator::GreaterOrEq => a >= b,
+            Operator::Less => a < b,
+            Operator::LessOrEq => a <= b,
+            Operator::Contains | Operator::NotContains => {
+                let contains = a.to_string().contains(&b.to_string());
+                contains == (operator == Operator::Contains)
+            }
+        },
+        (QueryValue::Bool(a), QueryValue::Bool(b)) => match operator {
+            Operator::Eq => a == b,
+            Operator::NotEq => a != b,
+            _ => false,
+        },
+        // Comparaisons textuelles insensibles à la casse
+        (actual, expected) => {
+            let a = value_to_text(actual).to_lowercase();
+            let b = value_to_text(expected).to_lowercase();
+            match operator {
+                Operator::Eq => a == b,
+                Operator::NotEq => a != b,
+                Operator::Contains => a.contains(&b),
+                Operator::NotContains => !a.contains(&b),
+                Operator::Greater => a > b,
+                Operator::GreaterOrEq => a >= b,
+                Operator::Less => a < b,
+                Operator::LessOrEq => a <= b,
+            }
+        }
+    }
+}
+
+fn value_to_text(value: &QueryValue) -> String {
+    match value {
+        QueryValue::Number(n) => n.to_string(),
+        QueryValue::Text(t) => t.clone(),
+        QueryValue::Bool(b) => b.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Text(String),
+    Number(f64),
+    Op(Operator),
+    And,
+    Or,
+    Not,
+    OpenParen,
+    CloseParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(f, "{}", ident),
+            Token::Text(text) => write!(f, "\"{}\"", text),
+            Token::Number(number) => write!(f, "{}", number),
+            Token::Op(operator) => write!(f, "{:?}", operator),
+            Token::And => write!(f, "and"),
+            Token::Or => write!(f, "or"),
+            Token::Not => write!(f, "not"),
+            Token::OpenParen => write!(f, "("),
+            Token::CloseParen => write!(f, ")"),
+        }
+    }
+}
+
+fn size_multiplier(unit: &str) -> Option<f64> {
+    match unit.to_lowercase().as_str() {
+        "" | "b" => Some(1.0),
+        "k" | "kb" | "kib" => Some(1024.0),
+        "m" | "mb" | "mib" => Some(1024.0 * 1024.0),
+        "g" | "gb" | "gib" => Some(1024.0 * 1024.0 * 1024.0),
+        "t" | "tb" | "tib" => Some(1024.0 * 1024.0 * 1024.0 * 1024.0),
+        _ => None,
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>> {
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (offset, c) = chars[i];
+        let next = chars.get(i + 1).map(|(_, c)| *c);
+
+        let (token, consumed) = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => (Token::OpenParen, 1),
+            ')' => (Token::CloseParen, 1),
+            '=' if next == Some('=') => (Token::Op(Operator::Eq), 2),
+            '=' => (Token::Op(Operator::Eq), 1),
+            '~' => (Token::Op(Operator::Contains), 1),
+            '!' if next == Some('=') => (Token::Op(Operator::NotEq), 2),
+            '!' if next == Some('~') => (Token::Op(Operator::NotContains), 2),
+            '!' => (Token::Not, 1),
+            '>' if next == Some('=') => (Token::Op(Operator::GreaterOrEq), 2),
+            '>' => (Token::Op(Operator::Greater), 1),
+            '<' if next == Some('=') => (Token::Op(Operator::LessOrEq), 2),
+            '<' => (Token::Op(Operator::Less), 1),
+            '&' if next == Some('&') => (Token::And, 2),
+            '|' if next == Some('|') => (Token::Or, 2),
+            '"' | '\'' => {
+                let mut text = String::new();
+                let mut j = i + 1;
+                loop {
+                    match chars.get(j) {
+                        Some((_, ch)) if *ch == c => break,
+                        Some((_, '\\')) if j + 1 < chars.len() => {
+                            text.push(chars[j + 1].1);
+                            j += 2;
+                        }
+                        Some((_, ch)) => {
+                            text.push(*ch);
+                            j += 1;
+                        }
+                        None => {
+                            return Err(anyhow::anyhow!(
+                                "Erreur de syntaxe à la position {}: chaîne non terminée",
+                                offset
+                            ))
+                        }
+                    }
+                }
+                (Token::Text(text), j + 1 - i)
+            }
+            c if c.is_ascii_digit() => {
+                let mut j = i;
+                while j < chars.len() && (chars[j].1.is_ascii_digit() || chars[j].1 == '.') {
+                    j += 1;
+                }
+                let number_end = j;
+                while j < chars.len() && chars[j].1.is_ascii_alphabetic() {
+                    j += 1;
+                }
+
+                let number: String = chars[i..number_end].iter().map(|(_, c)| c).collect();
+                let unit: String = chars[number_end..j].iter().map(|(_, c)| c).collect();
+                let number: f64 = number.parse().map_err(|_| {
+                    anyhow::anyhow!("Erreur de syntaxe à la position {}: nombre invalide '{}'", offset, number)
+                })?;
+                let multiplier = size_multiplier(&unit).ok_or_else(|| {
+                    anyhow::anyhow!("Erreur de syntaxe à la position {}: unité inconnue '{}'", offset, unit)
+                })?;
+                (Token::Number(number * multiplier), j - i)
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut j = i;
+                while j < chars.len() && (chars[j].1.is_alphanumeric() || matches!(chars[j].1, '_' | '.' | '-')) {
+                    j += 1;
+                }
+                let word: String = chars[i..j].iter().map(|(_, c)| c).collect();
+                let token = match word.to_lowercase().as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Ident(word),
+                };
+                (token, j - i)
+            }
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Erreur de syntaxe à la position {}: caractère '{}' inattendu",
+                    offset,
+                    c
+                ))
+            }
+        };
+
+        tokens.push((token, offset));
+        i += consumed;
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Option<(Token, usize)> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn end_offset(&self) -> usize {
+        self.tokens.last().map(|(_, offset)| *offset + 1).unwrap_or(0)
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut left = self.parse_unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            left = Expr::And(Box::new(left), Box::new(self.parse_unary()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        if self.peek() == Some(&Token::Not) {
+            self.position += 1;
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some((Token::OpenParen, offset)) => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some((Token::CloseParen, _)) => Ok(expr),
+                    _ => Err(anyhow::anyhow!(
+                        "Erreur de syntaxe à la position {}: parenthèse non fermée",
+                        offset
+                    )),
+                }
+            }
+            Some((Token::Ident(field), field_offset)) => {
+                let path: Vec<String> = field.to_lowercase().split('.').map(String::from).collect();
+                let field_type = Self::validate_path(&path, &field, field_offset)?;
+
+                let (operator, operator_offset) = match self.peek() {
+                    Some(Token::Op(operator)) => (*operator, self.tokens[self.position].1),
+                    _ if field_type == FieldType::Bool => return Ok(Expr::Flag(path)),
+                    _ => {
+                        return Err(anyhow::anyhow!(
+                            "Erreur de syntaxe à la position {}: '{}' n'est pas booléen, une comparaison est attendue",
+                            field_offset,
+                            field
+                        ))
+                    }
+                };
+                self.position += 1;
+
+                let allowed = match field_type {
+                    FieldType::Text => true,
+                    FieldType::Number => !matches!(operator, Operator::Contains | Operator::NotContains),
+                    FieldType::Bool => matches!(operator, Operator::Eq | Operator::NotEq),
+                };
+                if !allowed {
+                    return Err(anyhow::anyhow!(
+                        "Erreur de syntaxe à la position {}: opérateur '{}' inapplicable au champ '{}'",
+                        operator_offset,
+                        operator.symbol(),
+                        field
+                    ));
+                }
+
+                let value = match (field_type, self.next()) {
+                    (FieldType::Text, Some((Token::Text(text), _))) => QueryValue::Text(text),
+                    (FieldType::Text, Some((Token::Ident(word), _))) => QueryValue::Text(word),
+                    (FieldType::Number, Some((Token::Number(number), _))) => QueryValue::Number(number),
+                    (FieldType::Bool, Some((Token::Ident(word), offset))) => match word.to_lowercase().as_str() {
+                        "true" => QueryValue::Bool(true),
+                        "false" => QueryValue::Bool(false),
+                        _ => {
+                            return Err(anyhow::anyhow!(
+                                "Erreur de syntaxe à la position {}: true ou false attendu pour '{}', '{}' trouvé",
+                                offset,
+                                field,
+                                word
+                            ))
+                        }
+                    },
+                    (field_type, Some((token @ (Token::Text(_) | Token::Number(_) | Token::Ident(_)), offset))) => {
+                        let expected = match field_type {
+                            FieldType::Number => "un nombre",
+                            FieldType::Text => "un texte entre guillemets",
+                            FieldType::Bool => "true ou false",
+                        };
+                        return Err(anyhow::anyhow!(
+                            "Erreur de syntaxe à la position {}: '{}' attend {}, '{}' trouvé",
+                            offset,
+                            field,
+                            expected,
+                            token
+                        ));
+                    }
+                    (_, Some((token, offset))) => {
+                        return Err(anyhow::anyhow!(
+                            "Erreur de syntaxe à la position {}: valeur attendue après '{}', '{}' trouvé",
+                            offset,
+                            field,
+                            token
+                        ))
+                    }
+                    (_, None) => {
+                        return Err(anyhow::anyhow!(
+                            "Erreur de syntaxe à la position {}: valeur attendue après '{}'",
+                            self.end_offset(),
+                            field
+                        ))
+                    }
+                };
+
+                Ok(Expr::Compare { path, operator, value })
+            }
+            Some((token, offset)) => Err(anyhow::anyhow!(
+                "Erreur de syntaxe à la position {}: champ attendu, '{}' trouvé",
+                offset,
+                token
+            )),
+            None => Err(anyhow::anyhow!(
+                "Erreur de syntaxe à la position {}: expression incomplète",
+                self.end_offset()
+            )),
+        }
+    }
+}
+
+impl Parser {
+    /// `champ` ou `parent.[parent.]champ`, avec un champ connu
+    fn validate_path(path: &[String], field: &str, offset: usize) -> Result<FieldType> {
+        let (name, prefixes) = path.split_last().expect("split retourne au moins un élément");
+        match field_type(name) {
+            Some(field_type) if prefixes.iter().all(|prefix| prefix == "parent") => Ok(field_type),
+            _ => Err(anyhow::anyhow!(
+                "Erreur de syntaxe à la position {}: champ inconnu '{}' (champs : {}, préfixables par parent.)",
+                offset,
+                field,
+                FIELDS.iter().map(|(field, _)| *field).collect::<Vec<_>>().join(", ")
+            )),
+        }
+    }
+}
+
+impl QuerySubject for &ProcessMetadata {
+    fn field(&self, name: &str) -> Option<QueryValue> {
+        match name {
+            "pid" => Some(QueryValue::Number(self.pid as f64)),
+            "ppid" => Some(QueryValue::Number(self.parent_pid as f64)),
+            "name" => Some(QueryValue::Text(self.name.clone())),
+            "path" => self.executable_path.clone().map(QueryValue::Text),
+            "title" => self
+                .window_title
+                .clone()
+                .or_else(|| self.windows.iter().find(|w| w.is_visible).map(|w| w.window_title.clone()))
+                .map(QueryValue::Text),
+            "mem" => self.memory_info.as_ref().map(|m| QueryValue::Number(m.working_set_size as f64)),
+            "handles" => Some(QueryValue::Number(self.handle_count as f64)),
+            "has_window" => Some(QueryValue::Bool(self.windows.iter().any(|w| w.is_visible))),
+            _ => None,
+        }
+    }
+}
+
+impl QuerySubject for &ProcessInfo {
+    fn field(&self, name: &str) -> Option<QueryValue> {
+        match name {
+            "pid" => Some(QueryValue::Number(self.pid as f64)),
+            "name" => Some(QueryValue::Text(self.name.clone())),
+            "path" => self.executable_path.clone().map(QueryValue::Text),
+            "title" => self.window_title.clone().map(QueryValue::Text),
+            "has_window" => Some(QueryValue::Bool(self.window_title.is_some())),
+            _ => None,
+        }
+    }
+}
+
+/// Données d'un processus candidat rassemblées par le scanner
+#[derive(Debug, Clone)]
+pub struct QueryCandidate {
+    pub entry: ProcessEntry,
+    pub window_title: Option<String>,
+    pub executable_path: Option<String>,
+    pub memory: Option<u64>,
+    pub handle_count: Option<u32>,
+}
+
+/// Ensemble des processus, pour résoudre les champs `parent.xxx`
+pub struct ProcessQueryContext {
+    candidates: HashMap<u32, QueryCandidate>,
+}
+
+impl ProcessQueryContext {
+    pub fn new(candidates: Vec<QueryCandidate>) -> Self {
+        Self {
+            candidates: candidates.into_iter().map(|c| (c.entry.pid, c)).collect(),
+        }
+    }
+
+    pub fn view(&self, pid: u32) -> Option<ProcessQueryView<'_>> {
+        self.candidates.get(&pid).map(|candidate| ProcessQueryView {
+            context: self,
+            candidate,
+        })
+    }
+
+    /// Tous les candidats qui satisfont la requête, triés par PID
+    pub fn filter(&self, query: &ProcessQuery) -> Vec<&QueryCandidate> {
+        let mut matches: Vec<&QueryCandidate> = self
+            .candidates
+            .values()
+            .filter(|candidate| {
+                query.matches(&ProcessQueryView {
+                    context: self,
+                    candidate,
+                })
+            })
+            .collect();
+        matches.sort_by_key(|candidate| candidate.entry.pid);
+        matches
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct ProcessQueryView<'a> {
+    context: &'a ProcessQueryContext,
+    candidate: &'a QueryCandidate,
+}
+
+impl QuerySubject for ProcessQueryView<'_> {
+    fn field(&self, name: &str) -> Option<QueryValue> {
+        let candidate = self.candidate;
+        match name {
+            "pid" => Some(QueryValue::Number(candidate.entry.pid as f64)),
+            "ppid" => Some(QueryValue::Number(candidate.entry.parent_pid as f64)),
+            "name" => Some(QueryValue::Text(candidate.entry.name.clone())),
+            "path" => candidate.executable_path.clone().map(QueryValue::Text),
+            "title" => candidate.window_title.clone().map(QueryValue::Text),
+            "mem" => candidate.memory.map(|m| QueryValue::Number(m as f64)),
+            "handles" => candidate.handle_count.map(|h| QueryValue::Number(h as f64)),
+            "has_window" => Some(QueryValue::Bool(candidate.window_title.is_some())),
+            _ => None,
+        }
+    }
+
+    fn parent(&self) -> Option<Self> {
+        let parent_pid = self.candidate.entry.parent_pid;
+        // PID 0 (Idle/System) est son propre parent sous Windows
+        if parent_pid == self.candidate.entry.pid {
+            return None;
+        }
+        self.context.view(parent_pid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone)]
+    struct TestProcess {
+        fields: Vec<(&'static str, QueryValue)>,
+        parent: Option<Box<TestProcess>>,
+    }
+
+    impl QuerySubject for &TestProcess {
+        fn field(&self, name: &str) -> Option<QueryValue> {
+            self.fields.iter().find(|(field, _)| *field == name).map(|(_, value)| value.clone())
+        }
+
+        fn parent(&self) -> Option<Self> {
+            self.parent.as_deref()
+        }
+    }
+
+    fn chrome() -> TestProcess {
+        TestProcess {
+            fields: vec![
+                ("pid", QueryValue::Number(4242.0)),
+                ("name", QueryValue::Text("chrome.exe".to_string())),
+                ("path", QueryValue::Text("C:\\Program Files\\Google\\Chrome\\chrome.exe".to_string())),
+                ("mem", QueryValue::Number(600.0 * 1024.0 * 1024.0)),
+                ("has_window", QueryValue::Bool(true)),
+            ],
+            parent: Some(Box::new(TestProcess {
+                fields: vec![("name", QueryValue::Text("explorer.exe".to_string()))],
+                parent: None,
+            })),
+        }
+    }
+
+    fn matches(query: &str) -> bool {
+        ProcessQuery::parse(query).unwrap().matches(&&chrome())
+    }
+
+    #[test]
+    fn comparisons_and_combinators() {
+        assert!(matches(r#"name~"chrome" and mem>500MB"#));
+        assert!(matches(r#"name="CHROME.EXE""#));
+        assert!(!matches("mem>1GB"));
+        assert!(matches("mem>=600MB && mem<=600mb"));
+        assert!(matches(r#"not name~"firefox""#));
+        assert!(matches(r#"name~"firefox" or (has_window and pid=4242)"#));
+        assert!(matches("has_window = true"));
+        assert!(!matches("!has_window"));
+        assert!(matches(r#"parent.name="explorer.exe""#));
+        assert!(matches(r#"path!~"Mozilla""#));
+    }
+
+    #[test]
+    fn missing_values_do_not_match() {
+        // Champ connu mais absent pour ce processus (titre, parent du parent)
+        assert!(!matches(r#"title~"YouTube""#));
+        assert!(!matches(r#"parent.parent.name="wininit.exe""#));
+        assert!(!matches("handles>10"));
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        for query in ["foo>1", "not foo>1", "nmae~\"chrome\"", "parent.foo=1", "child.name=\"a\"", "parent=1"] {
+            let error = ProcessQuery::parse(query).unwrap_err().to_string();
+            assert!(error.contains("champ inconnu"), "{}: {}", query, error);
+        }
+    }
+
+    #[test]
+    fn type_mismatches_are_rejected() {
+        for query in [
+            r#"mem>"big""#,
+            "mem>big",
+            "name>5",
+            "pid~12",
+            "has_window>1",
+            "has_window=yes",
+            "name",
+            "mem and has_window",
+        ] {
+            assert!(ProcessQuery::parse(query).is_err(), "{} devrait être refusée", query);
+        }
+        // Un mot sans guillemets reste un texte valide pour un champ texte
+        assert!(matches("name=chrome.exe"));
+        assert!(ProcessQuery::parse("title=true").is_ok());
+    }
+
+    #[test]
+    fn syntax_errors_report_positions() {
+        let error = |query: &str| ProcessQuery::parse(query).unwrap_err().to_string();
+        assert!(error(r#"name="chrome"#).contains("position 5"));
+        assert!(error("(mem>1MB").contains("position 0"));
+        assert!(error("mem>1XB").contains("unité inconnue"));
+        assert!(error("mem>").contains("valeur attendue"));
+        assert!(error("mem>1MB pid=1").contains("inattendu"));
+        assert!(error("").contains("expression incomplète"));
+    }
+
+    #[test]
+    fn uses_field_sees_parent_paths() {
+        let query = ProcessQuery::parse(r#"parent.path~"explorer" and mem>1MB"#).unwrap();
+        assert!(query.uses_field("path"));
+        assert!(query.uses_field("mem"));
+        assert!(!query.uses_field("title"));
+        assert_eq!(query.parent_depth(), 1);
+        assert_eq!(ProcessQuery::parse(r#"name="a" or parent.parent.pid=1"#).unwrap().parent_depth(), 2);
+        assert_eq!(ProcessQuery::parse("mem>1GB").unwrap().parent_depth(), 0);
+    }
+
+    #[test]
+    fn may_match_only_excludes_certain_mismatches() {
+        let query = ProcessQuery::parse(r#"name~"chrome" and mem>500MB"#).unwrap();
+        assert!(query.may_match("chrome.exe", None, None));
+        assert!(!query.may_match("notepad.exe", None, None));
+
+        let query = ProcessQuery::parse(r#"name="code" or title~"Visual Studio""#).unwrap();
+        assert!(query.may_match("electron", None, None));
+        assert!(query.may_match("electron", None, Some("main.rs - Visual Studio Code")));
+        assert!(!query.may_match("electron", None, Some("Slack")));
+
+        let query = ProcessQuery::parse(r#"not path~"/snap/""#).unwrap();
+        assert!(!query.may_match("firefox", Some("/snap/firefox/current/firefox"), None));
+        assert!(query.may_match("firefox", None, None));
+
+        let query = ProcessQuery::parse(r#"parent.name="explorer.exe""#).unwrap();
+        assert!(query.may_match("anything", None, None));
+    }
+}
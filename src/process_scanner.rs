@@ -1,145 +1,317 @@
-use crate::models::{ApplicationInfo, ProcessInfo, ScanResult, ProcessMetadata, MetadataOptions};
+use crate::app_identity::{AppIdentityResolver, IdentityHints};
+use crate::metadata::command_line::{analyze_command_line, chromium_role, read_process_args};
+use crate::metadata::owner::{current_user_id, process_user_id};
+use crate::models::{ApplicationGrouping, ApplicationInfo, ContainerInfo, ProcessEntry, ProcessInfo, ProcessRole, ProcessRoleSummary, ProcessSelectionPolicy, ScanResult, ProcessMetadata, MetadataOptions};
+use crate::process_query::{ProcessQuery, ProcessQueryContext, QueryCandidate};
+use crate::redaction::Redactor;
+use crate::system_snapshot::{SnapshotCache, SystemSnapshot};
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
-use std::ptr::null_mut;
-use std::ffi::OsString;
-use std::os::windows::ffi::OsStringExt;
+#[cfg(windows)]
 use std::mem;
+#[cfg(windows)]
 use winapi::{
-    shared::{
-        minwindef::{BOOL, DWORD, LPARAM, MAX_PATH},
-        windef::HWND,
-        ntdef::HANDLE,
-    },
+    shared::ntdef::HANDLE,
     um::{
         handleapi::CloseHandle,
         tlhelp32::{
             CreateToolhelp32Snapshot, Process32First, Process32Next, PROCESSENTRY32,
             TH32CS_SNAPPROCESS,
         },
-        winuser::{EnumWindows, GetWindowTextW, GetWindowThreadProcessId, IsWindowVisible},
     },
 };
 
-pub struct ProcessScanner;
+#[derive(Clone)]
+pub struct ProcessScanner {
+    snapshots: Arc<SnapshotCache>,
+    identities: Arc<AppIdentityResolver>,
+    grouping: ApplicationGrouping,
+    current_user_only: bool,
+    redactor: Option<Arc<Redactor>>,
+    containers: Arc<ContainerCache>,
+}
+
+// Rattachement cgroup par (PID, heure de création) : un PID réutilisé ne reprend pas l'ancien
+type ContainerCache = Mutex<HashMap<(u32, u64), Option<ContainerInfo>>>;
+
+// Un processus tout juste créé peut encore être déplacé dans son cgroup (runc, systemd-run)
+const CONTAINER_CACHE_MIN_AGE_MS: u64 = 2000;
 
 impl ProcessScanner {
     pub fn new() -> Self {
-        Self
+        Self::with_snapshot_cache(SnapshotCache::shared())
+    }
+
+    /// Partager un cache de snapshot avec les collecteurs (le moniteur en possède un par surveillance)
+    pub fn with_snapshot_cache(snapshots: Arc<SnapshotCache>) -> Self {
+        Self {
+            snapshots,
+            identities: AppIdentityResolver::shared(),
+            grouping: ApplicationGrouping::default(),
+            current_user_only: false,
+            redactor: None,
+            containers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Utiliser une table de correspondance propre (noms d'affichage personnalisés)
+    pub fn with_identity_resolver(mut self, identities: Arc<AppIdentityResolver>) -> Self {
+        self.identities = identities;
+        self
+    }
+
+    /// Regrouper les applications par conteneur ou unité systemd plutôt que par nom (Linux)
+    pub fn with_grouping(mut self, grouping: ApplicationGrouping) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    /// Ne scanner que les processus de l'utilisateur courant (UID effectif ou SID du jeton)
+    pub fn with_current_user_only(mut self, current_user_only: bool) -> Self {
+        self.current_user_only = current_user_only;
+        self
+    }
+
+    /// Masquer les résultats (scan, requêtes, métadonnées) avant de les rendre à l'appelant
+    pub fn with_redactor(mut self, redactor: Option<Arc<Redactor>>) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    pub fn snapshot_cache(&self) -> &Arc<SnapshotCache> {
+        &self.snapshots
     }
 
     // Fonction principale pour scanner les applications (comme avant)
-    pub fn scan_applications(&self) -> Result<ScanResult> {
-        let processes = self.get_all_processes()?;
-        let window_processes = self.get_processes_with_windows()?;
+    pub async fn scan_applications(&self) -> Result<ScanResult> {
+        let scanner = self.clone();
+        tokio::task::spawn_blocking(move || scanner.scan_applications_blocking()).await?
+    }
+
+    // Variante synchrone du scan, pour les appelants hors runtime tokio
+    pub fn scan_applications_blocking(&self) -> Result<ScanResult> {
+        // Un seul snapshot pour toute l'analyse (plus de Toolhelp par application)
+        let mut snapshot = self.snapshots.get()?;
+
+        // Propriétaire inconnu (accès refusé) : exclu, le filtre sert aux contrôles de conformité.
+        // Tout le snapshot est filtré : sous-processus, rôles et regroupements ne voient pas les autres utilisateurs
+        if self.current_user_only {
+            let current_user = current_user_id();
+            snapshot = Arc::new(
+                snapshot.filtered(|entry| current_user.is_some() && process_user_id(entry.pid) == current_user),
+            );
+        }
+
+        let processes = self.get_all_processes(&snapshot);
+        let window_processes = self.get_processes_with_windows(&snapshot);
         
         // Grouper les processus par application principale
-        let applications = self.group_processes_by_application(processes, window_processes);
+        let applications = self.group_processes_by_application(&snapshot, processes, window_processes);
         
         let total_applications = applications.len();
         let scan_timestamp = self.get_current_timestamp();
         
-        Ok(ScanResult {
+        let mut result = ScanResult {
             applications,
             scan_timestamp,
             total_applications,
-        })
+        };
+        if let Some(redactor) = &self.redactor {
+            redactor.redact_scan(&mut result);
+        }
+        Ok(result)
     }
 
     // NOUVELLE FONCTION : Récupérer toutes les métadonnées brutes d'un PID spécifique
-    pub fn get_process_metadata(&self, pid: u32, options: Option<MetadataOptions>) -> Result<ProcessMetadata> {
-        use crate::{ProcessMetadataCollector, MediaControlCollector};
-        
+    pub async fn get_process_metadata(&self, pid: u32, options: Option<MetadataOptions>) -> Result<ProcessMetadata> {
         // Utiliser les options par défaut si aucune n'est fournie
         let options = options.unwrap_or_default();
         
-        // Récupérer les métadonnées de base du processus
-        let mut metadata = ProcessMetadataCollector::new().collect_all_metadata(pid, &options)?;
+        // Les appels système bloquants partent sur le pool bloquant de tokio
+        let collector = self.metadata_collector();
+        let collect_options = options.clone();
+        let mut metadata = tokio::task::spawn_blocking(move || {
+            collector.collect_all_metadata(pid, &collect_options)
+        }).await??;
         
-        // Ajouter les sessions média si demandé (approche hybride : seulement Media Control en async)
+        // Media Control passe par le thread média dédié (objets WinRT non Send).
+        // Sans thread média, les métadonnées restent utilisables : l'erreur est consignée
+        #[cfg(windows)]
         if options.media_control {
-            let media_collector = MediaControlCollector::new();
-            
-            // Utiliser spawn_blocking SEULEMENT pour Media Control (problème Send trait)
-            let (media_sessions, raw_media_data) = std::thread::spawn(move || {
-                let rt = tokio::runtime::Runtime::new().unwrap();
-                let sessions = rt.block_on(media_collector.get_media_sessions_for_process(pid, &options))?;
-                let raw_data = rt.block_on(media_collector.get_all_raw_media_properties(pid, &options))?;
-                Ok::<(Vec<_>, HashMap<String, serde_json::Value>), anyhow::Error>((sessions, raw_data))
-            }).join().unwrap()?;
-            
-            metadata.media_sessions = media_sessions;
-            metadata.raw_data.extend(raw_media_data);
+            let media = match crate::MediaWorker::shared() {
+                Ok(worker) => worker.media_for_process(pid, options, Arc::clone(&self.snapshots)).await,
+                Err(e) => Err(e),
+            };
+            match media {
+                Ok((media_sessions, raw_media_data)) => {
+                    metadata.media_sessions = media_sessions;
+                    metadata.raw_data.extend(raw_media_data);
+                }
+                Err(e) => {
+                    metadata.raw_data.insert("media_error".to_string(), serde_json::json!(e.to_string()));
+                }
+            }
         }
         
+        if let Some(redactor) = &self.redactor {
+            redactor.redact_metadata(&mut metadata);
+        }
+        Ok(metadata)
+    }
+
+    // Variante synchrone, à ne pas appeler depuis une tâche async
+    pub fn get_process_metadata_blocking(&self, pid: u32, options: Option<MetadataOptions>) -> Result<ProcessMetadata> {
+        let options = options.unwrap_or_default();
+        
+        let mut metadata = self.metadata_collector().collect_all_metadata(pid, &options)?;
+        
+        #[cfg(windows)]
+        if options.media_control {
+            let media = crate::MediaWorker::shared()
+                .and_then(|worker| worker.media_for_process_blocking(pid, options, Arc::clone(&self.snapshots)));
+            match media {
+                Ok((media_sessions, raw_media_data)) => {
+                    metadata.media_sessions = media_sessions;
+                    metadata.raw_data.extend(raw_media_data);
+                }
+                Err(e) => {
+                    metadata.raw_data.insert("media_error".to_string(), serde_json::json!(e.to_string()));
+                }
+            }
+        }
+        
+        if let Some(redactor) = &self.redactor {
+            redactor.redact_metadata(&mut metadata);
+        }
         Ok(metadata)
     }
 
     // NOUVELLE FONCTION : Détecter l'onglet actif d'un navigateur
     pub fn get_active_browser_tab(&self, pid: u32) -> Result<Option<crate::models::WindowInfo>> {
-        use crate::ProcessMetadataCollector;
-        let collector = ProcessMetadataCollector::new();
-        collector.get_active_browser_tab(pid)
+        self.metadata_collector().get_active_browser_tab(pid)
+    }
+
+    // Analyser le titre de l'onglet actif (nom de page, site, catégorie)
+    pub fn get_active_browser_tab_title(&self, pid: u32, parser: &crate::BrowserTitleParser) -> Result<Option<crate::models::ParsedTabTitle>> {
+        Ok(self
+            .get_active_browser_tab(pid)?
+            .and_then(|tab| parser.parse_window(&tab)))
     }
 
     // NOUVELLE FONCTION : Trouver un PID par nom d'exécutable
     pub fn find_pid_by_executable_name(&self, executable_name: &str) -> Result<Option<u32>> {
-        let snapshot = unsafe { 
-            winapi::um::tlhelp32::CreateToolhelp32Snapshot(
-                winapi::um::tlhelp32::TH32CS_SNAPPROCESS, 
-                0
-            )
-        };
+        let executable_name = executable_name.to_lowercase();
+        Ok(self
+            .snapshots
+            .get()?
+            .processes
+            .iter()
+            .find(|entry| entry.name.to_lowercase() == executable_name)
+            .map(|entry| entry.pid))
+    }
+
+    // Trouver TOUS les processus d'un exécutable, avec leur parent et leur heure de création
+    pub fn find_pids(&self, executable_name: &str) -> Result<Vec<ProcessEntry>> {
+        let executable_name = executable_name.to_lowercase();
+        Ok(self
+            .snapshots
+            .get()?
+            .processes
+            .iter()
+            .filter(|entry| entry.name.to_lowercase() == executable_name)
+            .cloned()
+            .map(with_start_time)
+            .collect())
+    }
 
-        if snapshot == winapi::um::handleapi::INVALID_HANDLE_VALUE {
-            return Err(anyhow::anyhow!("Impossible de créer le snapshot des processus"));
+    // Choisir l'instance à suivre parmi plusieurs candidats
+    pub fn select_pid(&self, candidates: &[ProcessEntry], policy: ProcessSelectionPolicy) -> Option<u32> {
+        if candidates.len() <= 1 {
+            return candidates.first().map(|entry| entry.pid);
         }
 
-        let mut process_entry = winapi::um::tlhelp32::PROCESSENTRY32 {
-            dwSize: std::mem::size_of::<winapi::um::tlhelp32::PROCESSENTRY32>() as u32,
-            cntUsage: 0,
-            th32ProcessID: 0,
-            th32DefaultHeapID: 0,
-            th32ModuleID: 0,
-            cntThreads: 0,
-            th32ParentProcessID: 0,
-            pcPriClassBase: 0,
-            dwFlags: 0,
-            szExeFile: [0; 260],
-        };
+        // Heure inconnue : classée après les heures connues, départage par PID
+        let by_start_time = |entry: &&ProcessEntry| (entry.start_time.unwrap_or(u64::MAX), entry.pid);
 
-        let mut found_pid = None;
+        match policy {
+            ProcessSelectionPolicy::Oldest => candidates.iter().min_by_key(by_start_time).map(|entry| entry.pid),
+            ProcessSelectionPolicy::Newest => candidates
+                .iter()
+                .max_by_key(|entry| (entry.start_time.unwrap_or(0), entry.pid))
+                .map(|entry| entry.pid),
+            ProcessSelectionPolicy::TreeRoot => {
+                // Racine : candidat dont le parent n'est pas lui-même un candidat
+                candidates
+                    .iter()
+                    .filter(|entry| !candidates.iter().any(|other| other.pid == entry.parent_pid && other.pid != entry.pid))
+                    .min_by_key(by_start_time)
+                    .map(|entry| entry.pid)
+            }
+            ProcessSelectionPolicy::ForegroundWindow => {
+                let foreground_pid = self.metadata_collector().get_foreground_process_id();
+                let window_processes = self
+                    .snapshots
+                    .get()
+                    .map(|snapshot| self.get_processes_with_windows(&snapshot))
+                    .unwrap_or_default();
 
-        unsafe {
-            if winapi::um::tlhelp32::Process32First(snapshot, &mut process_entry) != 0 {
-                loop {
-                    let process_name = std::ffi::CStr::from_ptr(process_entry.szExeFile.as_ptr())
-                        .to_string_lossy()
-                        .to_lowercase();
-                    
-                    if process_name == executable_name.to_lowercase() {
-                        found_pid = Some(process_entry.th32ProcessID);
-                        break;
-                    }
-                    
-                    if winapi::um::tlhelp32::Process32Next(snapshot, &mut process_entry) == 0 {
-                        break;
-                    }
-                }
+                candidates
+                    .iter()
+                    .find(|entry| Some(entry.pid) == foreground_pid)
+                    .or_else(|| candidates.iter().filter(|entry| window_processes.contains_key(&entry.pid)).min_by_key(by_start_time))
+                    .map(|entry| entry.pid)
+                    .or_else(|| self.select_pid(candidates, ProcessSelectionPolicy::TreeRoot))
+            }
+            ProcessSelectionPolicy::MediaSession => {
+                let media_pids = self.get_media_process_ids();
+
+                candidates
+                    .iter()
+                    .filter(|entry| media_pids.contains(&entry.pid))
+                    .min_by_key(by_start_time)
+                    .map(|entry| entry.pid)
+                    .or_else(|| self.select_pid(candidates, ProcessSelectionPolicy::TreeRoot))
             }
-            
-            let _ = winapi::um::handleapi::CloseHandle(snapshot);
         }
+    }
+
+    // PIDs qui jouent du média : onglets audibles signalés par l'extension, sessions audio sous Windows
+    fn get_media_process_ids(&self) -> Vec<u32> {
+        #[allow(unused_mut)] // Complété par les sessions audio sous Windows uniquement
+        let mut pids: Vec<u32> = crate::native_messaging::BrowserTabStore::new()
+            .load_all()
+            .into_iter()
+            .filter(|state| !state.audible_tabs.is_empty())
+            .map(|state| state.browser_pid)
+            .collect();
 
-        Ok(found_pid)
+        #[cfg(windows)]
+        if let Ok(worker) = crate::MediaWorker::shared() {
+            pids.extend(worker.active_audio_process_ids_blocking(Arc::clone(&self.snapshots)));
+        }
+
+        pids
     }
 
     // NOUVELLE FONCTION : Surveiller un processus par nom d'exécutable
-    pub fn monitor_process_by_name(&self, executable_name: &str, options: Option<crate::models::MetadataOptions>) -> Result<Option<crate::models::ProcessMetadata>> {
-        if let Some(pid) = self.find_pid_by_executable_name(executable_name)? {
+    pub async fn monitor_process_by_name(&self, executable_name: &str, options: Option<crate::models::MetadataOptions>) -> Result<Option<crate::models::ProcessMetadata>> {
+        self.monitor_process_by_name_with_policy(executable_name, ProcessSelectionPolicy::default(), options).await
+    }
+
+    // Surveiller un processus par nom d'exécutable en choisissant l'instance selon la politique
+    pub async fn monitor_process_by_name_with_policy(&self, executable_name: &str, policy: ProcessSelectionPolicy, options: Option<MetadataOptions>) -> Result<Option<ProcessMetadata>> {
+        let scanner = self.clone();
+        let executable_name = executable_name.to_string();
+        let selected = tokio::task::spawn_blocking(move || -> Result<Option<u32>> {
+            let candidates = scanner.find_pids(&executable_name)?;
+            Ok(scanner.select_pid(&candidates, policy))
+        }).await??;
+
+        if let Some(pid) = selected {
             // Le processus existe, récupérer ses métadonnées
-            Ok(Some(self.get_process_metadata(pid, options)?))
+            Ok(Some(self.get_process_metadata(pid, options).await?))
         } else {
             // Le processus n'existe pas
             Ok(None)
@@ -147,88 +319,225 @@ impl ProcessScanner {
     }
 
 
-    // Fonctions pour le scan des applications (comme avant)
-    fn get_all_processes(&self) -> Result<Vec<ProcessInfo>> {
-        let mut processes = Vec::new();
+    // Liste brute des processus avec leur parent
+    #[cfg(windows)]
+    pub fn list_process_entries(&self) -> Result<Vec<ProcessEntry>> {
+        let mut entries = Vec::new();
         
         unsafe {
             let snapshot: HANDLE = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
-            if snapshot != null_mut() {
-                let mut pe32: PROCESSENTRY32 = mem::zeroed();
-                pe32.dwSize = mem::size_of::<PROCESSENTRY32>() as u32;
-                
-                if Process32First(snapshot, &mut pe32) != 0 {
-                    loop {
-                        let process_info = ProcessInfo {
-                            pid: pe32.th32ProcessID,
-                            name: self.c_string_to_string(&pe32.szExeFile),
-                            window_title: None,
-                            executable_path: None,
-                            subprocesses: Vec::new(),
-                        };
-                        processes.push(process_info);
-
-                        if Process32Next(snapshot, &mut pe32) == 0 {
-                            break;
-                        }
+            if snapshot == winapi::um::handleapi::INVALID_HANDLE_VALUE {
+                return Err(anyhow::anyhow!("Impossible de créer le snapshot des processus"));
+            }
+
+            let mut pe32: PROCESSENTRY32 = mem::zeroed();
+            pe32.dwSize = mem::size_of::<PROCESSENTRY32>() as u32;
+            
+            if Process32First(snapshot, &mut pe32) != 0 {
+                loop {
+                    entries.push(ProcessEntry {
+                        pid: pe32.th32ProcessID,
+                        parent_pid: pe32.th32ParentProcessID,
+                        name: self.c_string_to_string(&pe32.szExeFile),
+                        start_time: None, // Non fourni par Toolhelp
+                    });
+
+                    if Process32Next(snapshot, &mut pe32) == 0 {
+                        break;
                     }
                 }
-                CloseHandle(snapshot);
             }
+            CloseHandle(snapshot);
         }
 
-        Ok(processes)
+        Ok(entries)
     }
 
-    fn get_subprocesses(&self, parent_pid: u32) -> Vec<ProcessInfo> {
-        let mut subprocesses = Vec::new();
-        
-        unsafe {
-            let snapshot: HANDLE = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
-            if snapshot != null_mut() {
-                let mut pe32: PROCESSENTRY32 = mem::zeroed();
-                pe32.dwSize = mem::size_of::<PROCESSENTRY32>() as u32;
-                
-                if Process32First(snapshot, &mut pe32) != 0 {
-                    loop {
-                        if pe32.th32ParentProcessID == parent_pid {
-                            let subprocess_info = ProcessInfo {
-                                pid: pe32.th32ProcessID,
-                                name: self.c_string_to_string(&pe32.szExeFile),
-                                window_title: None,
-                                executable_path: None,
-                                subprocesses: Vec::new(),
-                            };
-                            subprocesses.push(subprocess_info);
-                        }
+    #[cfg(target_os = "linux")]
+    pub fn list_process_entries(&self) -> Result<Vec<ProcessEntry>> {
+        use crate::linux::procfs;
 
-                        if Process32Next(snapshot, &mut pe32) == 0 {
-                            break;
-                        }
+        let boot_time = procfs::boot_time_millis();
+        Ok(procfs::list_pids()
+            .into_iter()
+            .filter_map(procfs::read_stat)
+            .map(|stat| ProcessEntry {
+                pid: stat.pid,
+                parent_pid: stat.ppid,
+                name: procfs::process_name(&stat),
+                start_time: boot_time.map(|boot| boot + procfs::ticks_to_millis(stat.start_time_ticks)),
+            })
+            .collect())
+    }
+
+    // Rechercher tous les processus correspondant à une requête (ex: name~"chrome" and mem>500MB)
+    pub fn find_processes(&self, query: &ProcessQuery) -> Result<Vec<ProcessInfo>> {
+        let snapshot = self.snapshots.get()?;
+        let window_processes = self.get_processes_with_windows(&snapshot);
+
+        // Ouvrir chaque processus coûte cher : seulement si la requête utilise ces champs
+        let needs_path = query.uses_field("path");
+        let needs_memory = query.uses_field("mem");
+        let needs_handles = query.uses_field("handles");
+        let collector = self.metadata_collector();
+        let options = MetadataOptions {
+            basic_info: needs_path,
+            executable_info: false,
+            memory_info: needs_memory,
+            window_info: false,
+            cpu_info: false,
+            io_info: false,
+            thread_info: false,
+            module_info: false,
+            handle_info: needs_handles,
+            handle_types: Vec::new(),
+            max_handles: 0,
+            environment_vars: false,
+            environment_var_names: Vec::new(),
+            network_info: false,
+            container_info: false,
+            owner_info: false,
+            memory_map: false,
+            max_memory_regions: 0,
+            media_control: false,
+            media_control_by_name: None,
+        };
+
+        // Nom et titre suffisent souvent à écarter un processus sans l'ouvrir ; les ancêtres
+        // des processus retenus sont ouverts aussi pour les champs `parent.xxx`
+        let mut to_open: HashSet<u32> = HashSet::new();
+        if needs_path || needs_memory || needs_handles {
+            let parent_depth = query.parent_depth();
+            for entry in &snapshot.processes {
+                let window_title = window_processes.get(&entry.pid).map(String::as_str);
+                if !query.may_match(&entry.name, None, window_title) {
+                    continue;
+                }
+                let mut pid = entry.pid;
+                for _ in 0..=parent_depth {
+                    to_open.insert(pid);
+                    match snapshot.process(pid) {
+                        Some(process) if process.parent_pid != pid => pid = process.parent_pid,
+                        _ => break,
                     }
                 }
-                CloseHandle(snapshot);
             }
         }
 
-        subprocesses
+        let candidates = snapshot
+            .processes
+            .iter()
+            .cloned()
+            .map(|entry| {
+                let metadata = if to_open.contains(&entry.pid) {
+                    collector.collect_all_metadata(entry.pid, &options).ok()
+                } else {
+                    None
+                };
+
+                QueryCandidate {
+                    window_title: window_processes.get(&entry.pid).cloned(),
+                    executable_path: metadata.as_ref().and_then(|m| m.executable_path.clone()),
+                    memory: metadata.as_ref().and_then(|m| m.memory_info.as_ref()).map(|m| m.working_set_size),
+                    handle_count: metadata.as_ref().filter(|_| needs_handles).map(|m| m.handle_count),
+                    entry,
+                }
+            })
+            .collect();
+
+        // La requête porte sur les valeurs brutes, seul le résultat est masqué
+        let context = ProcessQueryContext::new(candidates);
+        let mut processes: Vec<ProcessInfo> = context
+            .filter(query)
+            .into_iter()
+            .map(|candidate| ProcessInfo {
+                pid: candidate.entry.pid,
+                name: candidate.entry.name.clone(),
+                window_title: candidate.window_title.clone(),
+                executable_path: candidate.executable_path.clone(),
+                subprocesses: Vec::new(),
+                role: None,
+                utility_sub_type: None,
+            })
+            .collect();
+        if let Some(redactor) = &self.redactor {
+            for process in &mut processes {
+                redactor.redact_process_info(process);
+            }
+        }
+        Ok(processes)
+    }
+
+    // Surveiller un processus correspondant à une requête, choisi selon la politique
+    pub async fn monitor_process_by_query(&self, query: &ProcessQuery, policy: ProcessSelectionPolicy, options: Option<MetadataOptions>) -> Result<Option<ProcessMetadata>> {
+        let scanner = self.clone();
+        let query = query.clone();
+        let selected = tokio::task::spawn_blocking(move || -> Result<Option<u32>> {
+            let matches: Vec<u32> = scanner.find_processes(&query)?.iter().map(|process| process.pid).collect();
+            let candidates: Vec<ProcessEntry> = scanner
+                .snapshots
+                .get()?
+                .processes
+                .iter()
+                .filter(|entry| matches.contains(&entry.pid))
+                .cloned()
+                .map(with_start_time)
+                .collect();
+            Ok(scanner.select_pid(&candidates, policy))
+        }).await??;
+
+        match selected {
+            Some(pid) => Ok(Some(self.get_process_metadata(pid, options).await?)),
+            None => Ok(None),
+        }
     }
 
-    fn get_processes_with_windows(&self) -> Result<HashMap<u32, String>> {
-        let mut window_processes = HashMap::new();
-        
-        unsafe {
-            EnumWindows(
-                Some(enum_windows_proc),
-                &mut window_processes as *mut _ as LPARAM,
-            );
+    // Fonctions pour le scan des applications (comme avant)
+    fn get_all_processes(&self, snapshot: &SystemSnapshot) -> Vec<ProcessInfo> {
+        snapshot.processes.iter().map(process_info_from_entry).collect()
+    }
+
+    fn get_subprocesses(&self, snapshot: &SystemSnapshot, parent_pid: u32) -> Vec<ProcessInfo> {
+        snapshot.children(parent_pid).map(process_info_from_entry).collect()
+    }
+
+    // Conteneur d'un processus : /proc n'est relu que pour un processus pas encore vu
+    fn container_for(&self, pid: u32, start_time: Option<u64>, now: u64) -> Option<ContainerInfo> {
+        let Some(start_time) = start_time else {
+            return read_container(pid);
+        };
+        if let Some(info) = self.containers.lock().unwrap().get(&(pid, start_time)) {
+            return info.clone();
         }
 
-        Ok(window_processes)
+        let info = read_container(pid);
+        if now.saturating_sub(start_time) >= CONTAINER_CACHE_MIN_AGE_MS {
+            self.containers.lock().unwrap().insert((pid, start_time), info.clone());
+        }
+        info
+    }
+
+    // Oublier les processus terminés et les PID réutilisés
+    fn prune_containers(&self, start_times: &HashMap<u32, Option<u64>>) {
+        self.containers
+            .lock()
+            .unwrap()
+            .retain(|(pid, start_time), _| start_times.get(pid) == Some(&Some(*start_time)));
+    }
+
+    // Processus possédant une fenêtre visible avec un titre
+    fn get_processes_with_windows(&self, snapshot: &SystemSnapshot) -> HashMap<u32, String> {
+        snapshot
+            .windows
+            .iter()
+            .filter(|window| window.is_visible && !window.window_title.is_empty() && window.process_id != 0)
+            .map(|window| (window.process_id, window.window_title.clone()))
+            .collect()
     }
 
     fn group_processes_by_application(
         &self,
+        snapshot: &SystemSnapshot,
         mut processes: Vec<ProcessInfo>,
         window_processes: HashMap<u32, String>,
     ) -> Vec<ApplicationInfo> {
@@ -239,6 +548,38 @@ impl ProcessScanner {
             }
         }
 
+        // Conteneur / unité systemd : un groupe par valeur, avec ou sans fenêtre (hôtes sans session graphique)
+        let mut containers: HashMap<u32, ContainerInfo> = HashMap::new();
+        let mut unit_groups: HashMap<String, Vec<ProcessInfo>> = HashMap::new();
+        if self.grouping != ApplicationGrouping::Name {
+            let start_times: HashMap<u32, Option<u64>> =
+                snapshot.processes.iter().map(|entry| (entry.pid, entry.start_time)).collect();
+            self.prune_containers(&start_times);
+            processes.retain(|process| {
+                let start_time = start_times.get(&process.pid).copied().flatten();
+                let Some(info) = self.container_for(process.pid, start_time, snapshot.captured_at) else {
+                    return true;
+                };
+                let key = match self.grouping {
+                    // Sans identifiant (vu de l'intérieur du conteneur) : runtime et namespace de montage
+                    ApplicationGrouping::Container => info
+                        .container_id
+                        .clone()
+                        .or_else(|| Some(format!("{}:{}", info.runtime.as_ref()?, info.mount_namespace?))),
+                    ApplicationGrouping::SystemdUnit => info.systemd_unit.clone(),
+                    ApplicationGrouping::Name => None,
+                };
+                containers.insert(process.pid, info);
+                match key {
+                    Some(key) => {
+                        unit_groups.entry(key).or_default().push(process.clone());
+                        false
+                    }
+                    None => true,
+                }
+            });
+        }
+
         // Filtrer pour ne garder que les applications (processus avec fenêtres visibles)
         let applications: Vec<ProcessInfo> = processes
             .into_iter()
@@ -252,24 +593,67 @@ impl ProcessScanner {
         }
 
         // Créer les ApplicationInfo avec détection des sous-processus
-        grouped
+        let mut applications: Vec<ApplicationInfo> = grouped
             .into_iter()
-            .map(|(_, mut processes)| {
+            .map(|(name, mut processes)| {
                 // Trier par PID pour avoir le processus principal en premier
                 processes.sort_by_key(|p| p.pid);
-                
-                let main_process = processes.remove(0);
+
+                // Chromium/Electron : le principal est le processus sans --type, pas le plus petit PID
+                let roles = self.chromium_roles(snapshot, &name);
+                let main_index = roles
+                    .as_ref()
+                    .and_then(|roles| {
+                        processes.iter().position(|p| roles.get(&p.pid).map(|(role, _)| *role) == Some(ProcessRole::Browser))
+                    })
+                    .unwrap_or(0);
+                let mut main_process = processes.remove(main_index);
                 
                 // Récupérer les sous-processus pour le processus principal
-                let subprocesses = self.get_subprocesses(main_process.pid);
+                let subprocesses = self.get_subprocesses(snapshot, main_process.pid);
                 
                 // Ajouter les autres processus du même nom comme sous-processus
                 let mut all_subprocesses = subprocesses;
                 for process in processes {
-                    all_subprocesses.push(process);
+                    if !all_subprocesses.iter().any(|existing| existing.pid == process.pid) {
+                        all_subprocesses.push(process);
+                    }
+                }
+
+                let mut role_summaries = Vec::new();
+                if let Some(roles) = roles {
+                    // Renderers et utilitaires ne sont pas toujours des enfants directs (zygote sous Linux)
+                    let mut missing: Vec<u32> = roles
+                        .keys()
+                        .copied()
+                        .filter(|pid| *pid != main_process.pid && !all_subprocesses.iter().any(|p| p.pid == *pid))
+                        .collect();
+                    missing.sort_unstable();
+                    all_subprocesses.extend(missing.into_iter().filter_map(|pid| snapshot.process(pid)).map(process_info_from_entry));
+
+                    for process in std::iter::once(&mut main_process).chain(all_subprocesses.iter_mut()) {
+                        if let Some((role, utility_sub_type)) = roles.get(&process.pid) {
+                            process.role = Some(*role);
+                            process.utility_sub_type = utility_sub_type.clone();
+                        }
+                    }
+                    role_summaries = summarize_roles(&roles);
                 }
                 
                 let total_processes = 1 + all_subprocesses.len();
+                let main_pid = main_process.pid;
+
+                let window_class = snapshot
+                    .windows
+                    .iter()
+                    .find(|window| window.process_id == main_process.pid && window.is_visible && !window.class_name.is_empty())
+                    .map(|window| window.class_name.as_str());
+                let identity = self.identities.resolve(&IdentityHints {
+                    pid: main_process.pid,
+                    process_name: &main_process.name,
+                    executable_path: main_process.executable_path.as_deref(),
+                    window_class,
+                });
 
                 ApplicationInfo {
                     main_process: ProcessInfo {
@@ -277,17 +661,80 @@ impl ProcessScanner {
                         ..main_process
                     },
                     total_processes,
+                    identity: Some(identity),
+                    roles: role_summaries,
+                    container_info: containers.get(&main_pid).cloned(),
                 }
             })
-            .collect()
+            .collect();
+
+        applications.extend(
+            unit_groups
+                .into_values()
+                .map(|processes| self.unit_application(snapshot, processes, &containers)),
+        );
+        applications
     }
 
+    /// Application formée par tous les processus d'un conteneur ou d'une unité systemd :
+    /// principal = premier processus avec fenêtre, sinon racine de l'arbre
+    fn unit_application(
+        &self,
+        snapshot: &SystemSnapshot,
+        mut processes: Vec<ProcessInfo>,
+        containers: &HashMap<u32, ContainerInfo>,
+    ) -> ApplicationInfo {
+        processes.sort_by_key(|p| p.pid);
+        let parent_of = |pid: u32| snapshot.process(pid).map(|entry| entry.parent_pid);
+
+        let main_index = processes
+            .iter()
+            .position(|p| p.window_title.is_some())
+            .or_else(|| {
+                processes
+                    .iter()
+                    .position(|p| !processes.iter().any(|other| Some(other.pid) == parent_of(p.pid) && other.pid != p.pid))
+            })
+            .unwrap_or(0);
+        let main_process = processes.remove(main_index);
+        let total_processes = 1 + processes.len();
+
+        let identity = self.identities.resolve(&IdentityHints {
+            pid: main_process.pid,
+            process_name: &main_process.name,
+            executable_path: main_process.executable_path.as_deref(),
+            window_class: None,
+        });
+        let container_info = containers.get(&main_process.pid).cloned();
+
+        ApplicationInfo {
+            main_process: ProcessInfo {
+                subprocesses: processes,
+                ..main_process
+            },
+            total_processes,
+            identity: Some(identity),
+            roles: Vec::new(),
+            container_info,
+        }
+    }
+
+    /// Rôles des processus d'une application Chromium/Electron (voir `chromium_family_roles`)
+    fn chromium_roles(&self, snapshot: &SystemSnapshot, name: &str) -> Option<HashMap<u32, (ProcessRole, Option<String>)>> {
+        chromium_family_roles(snapshot, name, read_process_args)
+    }
+
+    #[cfg(windows)]
     fn c_string_to_string(&self, c_str: &[i8; 260]) -> String {
         let end = c_str.iter().position(|&x| x == 0).unwrap_or(c_str.len());
         let bytes: Vec<u8> = c_str[..end].iter().map(|&x| x as u8).collect();
         String::from_utf8_lossy(&bytes).to_string()
     }
 
+    fn metadata_collector(&self) -> crate::ProcessMetadataCollector {
+        crate::ProcessMetadataCollector::with_snapshot_cache(Arc::clone(&self.snapshots))
+    }
+
     fn get_current_timestamp(&self) -> String {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -297,27 +744,261 @@ impl ProcessScanner {
     }
 }
 
-unsafe extern "system" fn enum_windows_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
-    let window_processes = &mut *(lparam as *mut HashMap<u32, String>);
-    
-    if IsWindowVisible(hwnd) != 0 {
-        let mut window_text: [u16; MAX_PATH] = [0; MAX_PATH];
-        let text_len = GetWindowTextW(hwnd, window_text.as_mut_ptr(), MAX_PATH as i32);
-        
-        if text_len > 0 {
-            let os_string = OsString::from_wide(&window_text[..text_len as usize]);
-            if let Ok(title) = os_string.into_string() {
-                if !title.is_empty() {
-                    let mut process_id: DWORD = 0;
-                    GetWindowThreadProcessId(hwnd, &mut process_id);
-                    
-                    if process_id != 0 {
-                        window_processes.insert(process_id, title);
-                    }
-                }
+fn process_info_from_entry(entry: &ProcessEntry) -> ProcessInfo {
+    ProcessInfo {
+        pid: entry.pid,
+        name: entry.name.clone(),
+        window_title: None,
+        executable_path: None,
+        subprocesses: Vec::new(),
+        role: None,
+        utility_sub_type: None,
+    }
+}
+
+/// Regrouper les rôles par type, dans l'ordre de l'énumération (principal en premier)
+fn summarize_roles(roles: &HashMap<u32, (ProcessRole, Option<String>)>) -> Vec<ProcessRoleSummary> {
+    let mut by_role: BTreeMap<ProcessRole, ProcessRoleSummary> = BTreeMap::new();
+    for (pid, (role, utility_sub_type)) in roles {
+        let summary = by_role.entry(*role).or_insert_with(|| ProcessRoleSummary {
+            role: *role,
+            process_count: 0,
+            pids: Vec::new(),
+            utility_sub_types: Vec::new(),
+        });
+        summary.process_count += 1;
+        summary.pids.push(*pid);
+        if let Some(sub_type) = utility_sub_type {
+            if !summary.utility_sub_types.contains(sub_type) {
+                summary.utility_sub_types.push(sub_type.clone());
             }
         }
     }
-    
-    1 // Continue enumeration
-}
\ No newline at end of file
+
+    by_role
+        .into_values()
+        .map(|mut summary| {
+            summary.pids.sort_unstable();
+            summary.utility_sub_types.sort();
+            summary
+        })
+        .collect()
+}
+
+/// Rôles des instances d'un exécutable Chromium/Electron et de leurs descendants. La descente suit les
+/// enfants du même exécutable ou lancés avec --type= et s'arrête aux autres programmes (shell du terminal
+/// intégré, navigateur lancé depuis un shell...). None si aucun processus ne porte de --type (autre application).
+fn chromium_family_roles(
+    snapshot: &SystemSnapshot,
+    name: &str,
+    args_of: impl Fn(u32) -> Option<Vec<String>>,
+) -> Option<HashMap<u32, (ProcessRole, Option<String>)>> {
+    let mut args: HashMap<u32, Option<Vec<String>>> = HashMap::new();
+    let mut args_for = |pid: u32| args.entry(pid).or_insert_with(|| args_of(pid)).clone();
+
+    let instances: Vec<u32> = snapshot
+        .processes
+        .iter()
+        .filter(|entry| entry.name.eq_ignore_ascii_case(name))
+        .map(|entry| entry.pid)
+        .collect();
+    // Chemins de l'exécutable principal : les enfants renommés (comm tronqué, helpers) restent reconnus
+    let programs: Vec<String> = instances
+        .iter()
+        .filter_map(|pid| args_for(*pid)?.into_iter().next())
+        .collect();
+
+    let mut roles = HashMap::new();
+    let mut visited: Vec<u32> = Vec::new();
+    let mut pending = instances;
+    while let Some(pid) = pending.pop() {
+        if visited.contains(&pid) {
+            continue;
+        }
+        visited.push(pid);
+        let Some(entry) = snapshot.process(pid) else {
+            continue;
+        };
+        let process_args = args_for(pid);
+        let info = process_args.as_ref().map(|process_args| analyze_command_line(&entry.name, process_args));
+        let is_same_executable = entry.name.eq_ignore_ascii_case(name)
+            || process_args
+                .as_ref()
+                .and_then(|process_args| process_args.first())
+                .is_some_and(|program| programs.contains(program));
+        let has_type = info.as_ref().is_some_and(|info| info.process_type.is_some());
+
+        if let Some(info) = info {
+            // chrome_crashpad_handler (Linux) n'a pas de --type mais un nom explicite
+            let role = if !has_type && entry.name.to_lowercase().contains("crashpad") {
+                Some(ProcessRole::CrashpadHandler)
+            } else if has_type || is_same_executable {
+                Some(chromium_role(&info))
+            } else {
+                None
+            };
+            if let Some(role) = role {
+                roles.insert(pid, (role, info.utility_sub_type));
+            }
+        }
+
+        // Autre programme sans --type : ses descendants ne font pas partie de l'application
+        if has_type || is_same_executable {
+            pending.extend(snapshot.children(pid).map(|child| child.pid));
+        }
+    }
+
+    roles
+        .values()
+        .any(|(role, _)| *role != ProcessRole::Browser)
+        .then_some(roles)
+}
+
+#[cfg(target_os = "linux")]
+fn read_container(pid: u32) -> Option<ContainerInfo> {
+    crate::linux::cgroup::container_info(pid).ok()
+}
+
+// Pas de cgroups : le regroupement par conteneur retombe sur le regroupement par nom
+#[cfg(windows)]
+fn read_container(_pid: u32) -> Option<ContainerInfo> {
+    None
+}
+
+// Toolhelp ne fournit pas l'heure de création : la compléter au besoin
+fn with_start_time(mut entry: ProcessEntry) -> ProcessEntry {
+    if entry.start_time.is_none() {
+        entry.start_time = crate::process_events::process_start_time(entry.pid);
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now_millis() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+    }
+
+    #[test]
+    fn container_lookups_are_cached_per_process_instance() {
+        let scanner = ProcessScanner::new();
+        let pid = std::process::id();
+        let now = now_millis();
+        let started = now - 60_000;
+
+        let info = scanner.container_for(pid, Some(started), now);
+        assert_eq!(scanner.containers.lock().unwrap().get(&(pid, started)), Some(&info));
+
+        // Valeur servie par le cache, même fausse : /proc n'est pas relu
+        let cached = ContainerInfo {
+            cgroup_path: "/cached".to_string(),
+            ..ContainerInfo::default()
+        };
+        scanner.containers.lock().unwrap().insert((pid, started), Some(cached.clone()));
+        assert_eq!(scanner.container_for(pid, Some(started), now), Some(cached));
+
+        // Même PID, autre heure de création : autre processus
+        let recent = now - 10;
+        assert_ne!(scanner.container_for(pid, Some(recent), now).map(|info| info.cgroup_path), Some("/cached".to_string()));
+        // Trop récent pour être mis en cache
+        assert!(!scanner.containers.lock().unwrap().contains_key(&(pid, recent)));
+
+        // Processus disparu ou PID réutilisé : entrée oubliée
+        scanner.prune_containers(&HashMap::from([(pid, Some(recent))]));
+        assert!(scanner.containers.lock().unwrap().is_empty());
+    }
+
+    fn entry(pid: u32, parent_pid: u32, name: &str) -> ProcessEntry {
+        ProcessEntry {
+            pid,
+            parent_pid,
+            name: name.to_string(),
+            start_time: None,
+        }
+    }
+
+    fn args(command_line: &str) -> Option<Vec<String>> {
+        Some(command_line.split(' ').map(str::to_string).collect())
+    }
+
+    fn role_pids(roles: &HashMap<u32, (ProcessRole, Option<String>)>) -> Vec<(u32, ProcessRole)> {
+        let mut pids: Vec<(u32, ProcessRole)> = roles.iter().map(|(pid, (role, _))| (*pid, *role)).collect();
+        pids.sort_unstable();
+        pids
+    }
+
+    #[test]
+    fn chromium_roles_stop_at_other_programs() {
+        // Un shell (10) lance chrome (20) ; le terminal intégré de code (30) lance aussi chrome (40)
+        let snapshot = SystemSnapshot::new(
+            vec![
+                entry(1, 0, "init"),
+                entry(10, 1, "bash"),
+                entry(20, 10, "chrome"),
+                entry(21, 20, "chrome"),
+                entry(22, 20, "chrome"),
+                entry(23, 20, "chrome_crashpad"),
+                entry(30, 1, "code"),
+                entry(31, 30, "code"),
+                entry(32, 30, "bash"),
+                entry(40, 32, "chrome"),
+                entry(41, 40, "chrome"),
+            ],
+            Vec::new(),
+            Vec::new(),
+        );
+        let args_of = |pid: u32| match pid {
+            10 | 32 => args("/bin/bash"),
+            20 | 40 => args("/opt/google/chrome/chrome"),
+            21 | 41 => args("/opt/google/chrome/chrome --type=renderer"),
+            22 => args("/opt/google/chrome/chrome --type=utility --utility-sub-type=network.mojom.NetworkService"),
+            23 => args("/opt/google/chrome/chrome_crashpad_handler --database=/tmp"),
+            30 => args("/usr/share/code/code"),
+            31 => args("/usr/share/code/code --type=gpu-process"),
+            _ => None,
+        };
+
+        // Le shell ne récupère ni chrome ni ses renderers
+        assert_eq!(chromium_family_roles(&snapshot, "bash", args_of), None);
+
+        let code = chromium_family_roles(&snapshot, "code", args_of).unwrap();
+        assert_eq!(role_pids(&code), [(30, ProcessRole::Browser), (31, ProcessRole::Gpu)]);
+
+        let chrome = chromium_family_roles(&snapshot, "chrome", args_of).unwrap();
+        assert_eq!(
+            role_pids(&chrome),
+            [
+                (20, ProcessRole::Browser),
+                (21, ProcessRole::Renderer),
+                (22, ProcessRole::Utility),
+                (23, ProcessRole::CrashpadHandler),
+                (40, ProcessRole::Browser),
+                (41, ProcessRole::Renderer),
+            ]
+        );
+        assert_eq!(chrome[&22].1.as_deref(), Some("network.mojom.NetworkService"));
+    }
+
+    #[test]
+    fn chromium_roles_follow_renamed_helpers_of_the_same_executable() {
+        // Helper renommé (comm tronqué) mais même exécutable, lui-même parent d'un renderer
+        let snapshot = SystemSnapshot::new(
+            vec![entry(1, 0, "init"), entry(50, 1, "slack"), entry(51, 50, "slack-zygote"), entry(52, 51, "slack")],
+            Vec::new(),
+            Vec::new(),
+        );
+        let args_of = |pid: u32| match pid {
+            50 | 51 => args("/usr/lib/slack/slack"),
+            52 => args("/usr/lib/slack/slack --type=renderer"),
+            _ => None,
+        };
+
+        let roles = chromium_family_roles(&snapshot, "slack", args_of).unwrap();
+        assert_eq!(
+            role_pids(&roles),
+            [(50, ProcessRole::Browser), (51, ProcessRole::Browser), (52, ProcessRole::Renderer)]
+        );
+    }
+}
@@ -1,21 +1,57 @@
 use crate::{
-    models::{MetadataOptions, ProcessMetadata, WindowInfo},
+    foreground_watcher::{ForegroundEvent, ForegroundEventKind, ForegroundWatcher},
+    metadata::{BrowserTitleParser, SiteCategories},
+    models::{
+        BrowserTabInfo, IoInfo, MediaPlaybackStatus, MetadataOptions, ParsedTabTitle, ProcessMetadata,
+        ProcessSelectionPolicy, WindowInfo,
+    },
+    native_messaging::BrowserTabStore,
+    process_events::{process_start_time, ProcessEvent, ProcessEventSource},
+    process_query::ProcessQuery,
+    redaction::Redactor,
+    system_snapshot::{SnapshotCache, DEFAULT_SNAPSHOT_TTL},
     ProcessScanner,
 };
 use anyhow::Result;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use sup_common::{debug_eprintln, debug_println};
-use tokio::time::interval;
+use tokio::sync::{watch, Notify};
+use tokio::task::JoinHandle;
+use tokio::time::Instant as TokioInstant;
 
 /// Configuration pour la surveillance en temps réel
 pub struct MonitorConfig {
     /// Nom de l'exécutable à surveiller
     pub executable_name: String,
+    /// Requête de sélection du processus (prioritaire sur `executable_name` si définie)
+    pub target_query: Option<ProcessQuery>,
+    /// Instance à suivre quand plusieurs processus correspondent
+    pub selection_policy: ProcessSelectionPolicy,
     /// Intervalle de vérification (en secondes)
     pub check_interval: u64,
+    /// Adapter l'intervalle à l'activité (processus absent, changements récents, média en lecture)
+    pub adaptive_polling: bool,
+    /// Intervalle rapide après un changement ou pendant une lecture média (en millisecondes)
+    pub fast_check_interval_ms: u64,
+    /// Intervalle lent tant que le processus est absent (en secondes)
+    pub idle_check_interval: u64,
+    /// Plafond du backoff exponentiel après des échecs consécutifs (en secondes)
+    pub max_backoff_interval: u64,
+    /// Variation aléatoire appliquée à chaque intervalle (0.1 = ±10 %), pour désynchroniser les moniteurs
+    pub jitter_ratio: f64,
     /// Options de métadonnées à collecter
     pub metadata_options: MetadataOptions,
+    /// Catégories utilisées pour classer les onglets actifs (work, social, video...)
+    pub site_categories: SiteCategories,
+    /// Suivre les changements de premier plan par événements (en plus du polling)
+    pub watch_foreground: bool,
+    /// Réagir immédiatement aux démarrages/arrêts de processus (en plus du polling)
+    pub process_events: bool,
+    /// Masquage appliqué aux données dès leur collecte (état, callback, événements)
+    pub redactor: Option<Arc<Redactor>>,
     /// Callback appelé quand les données changent
     pub on_data_change: Option<Box<dyn Fn(&ProcessMetadata) + Send + Sync>>,
 }
@@ -24,8 +60,19 @@ impl Clone for MonitorConfig {
     fn clone(&self) -> Self {
         Self {
             executable_name: self.executable_name.clone(),
+            target_query: self.target_query.clone(),
+            selection_policy: self.selection_policy,
             check_interval: self.check_interval,
+            adaptive_polling: self.adaptive_polling,
+            fast_check_interval_ms: self.fast_check_interval_ms,
+            idle_check_interval: self.idle_check_interval,
+            max_backoff_interval: self.max_backoff_interval,
+            jitter_ratio: self.jitter_ratio,
             metadata_options: self.metadata_options.clone(),
+            site_categories: self.site_categories.clone(),
+            watch_foreground: self.watch_foreground,
+            process_events: self.process_events,
+            redactor: self.redactor.clone(),
             on_data_change: None, // On ne peut pas cloner le callback
         }
     }
@@ -35,8 +82,19 @@ impl Default for MonitorConfig {
     fn default() -> Self {
         Self {
             executable_name: String::new(),
+            target_query: None,
+            selection_policy: ProcessSelectionPolicy::default(),
             check_interval: 3, // 3 secondes par défaut
+            adaptive_polling: true,
+            fast_check_interval_ms: 1000,
+            idle_check_interval: 15,
+            max_backoff_interval: 60,
+            jitter_ratio: 0.1,
             metadata_options: MetadataOptions::default(),
+            site_categories: SiteCategories::default(),
+            watch_foreground: true,
+            process_events: true,
+            redactor: None,
             on_data_change: None,
         }
     }
@@ -49,6 +107,24 @@ pub struct ProcessMonitorState {
     pub last_metadata: Option<ProcessMetadata>,
     /// Dernier onglet actif détecté
     pub last_active_tab: Option<WindowInfo>,
+    /// Titre analysé du dernier onglet actif (page, site, catégorie)
+    pub last_active_tab_title: Option<ParsedTabTitle>,
+    /// Onglets qui émettent du son (via l'extension navigateur)
+    pub audible_tabs: Vec<BrowserTabInfo>,
+    /// L'application surveillée est-elle au premier plan ?
+    pub is_foreground: bool,
+    /// Derniers événements de premier plan concernant l'application (les plus récents en dernier)
+    pub foreground_events: Vec<ForegroundEvent>,
+    /// Heure de création du processus suivi (millisecondes depuis l'epoch)
+    pub process_started_at: Option<u64>,
+    /// Dernière fin de processus observée
+    pub last_exit: Option<ProcessExitInfo>,
+    /// Débits d'entrées/sorties entre les deux dernières vérifications (métadonnée `io_info`)
+    #[serde(default)]
+    pub io_rates: Option<IoRates>,
+    /// Compteurs de la vérification précédente, base du calcul des débits
+    #[serde(skip)]
+    io_sample: Option<IoSample>,
     /// Timestamp de la dernière mise à jour (en millisecondes depuis l'epoch)
     #[serde(skip_serializing, skip_deserializing)]
     pub last_update: Option<Instant>,
@@ -61,90 +137,439 @@ impl Default for ProcessMonitorState {
         Self {
             last_metadata: None,
             last_active_tab: None,
+            last_active_tab_title: None,
+            audible_tabs: Vec::new(),
+            is_foreground: false,
+            foreground_events: Vec::new(),
+            process_started_at: None,
+            last_exit: None,
+            io_rates: None,
+            io_sample: None,
             last_update: None,
             is_active: false,
         }
     }
 }
 
+/// Fin d'un processus suivi
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ProcessExitInfo {
+    pub pid: u32,
+    /// Code de sortie (None quand la fin a été détectée par polling)
+    pub exit_code: Option<i32>,
+    /// Heure de fin (millisecondes depuis l'epoch)
+    pub exited_at: u64,
+    /// Durée de vie exacte si l'heure de création est connue
+    pub lifetime_ms: Option<u64>,
+}
+
+/// Débits d'entrées/sorties (par seconde)
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct IoRates {
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    pub read_operations_per_sec: f64,
+    pub write_operations_per_sec: f64,
+    /// Débits réellement vus par le stockage (Linux)
+    pub disk_read_bytes_per_sec: Option<f64>,
+    pub disk_write_bytes_per_sec: Option<f64>,
+    /// Durée sur laquelle les débits sont calculés (en millisecondes)
+    pub interval_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+struct IoSample {
+    pid: u32,
+    taken_at: Instant,
+    counters: IoInfo,
+}
+
+impl ProcessMonitorState {
+    /// Mettre à jour les débits à partir des compteurs cumulés de la vérification courante.
+    /// Retourne true si les débits publiés ont changé
+    fn update_io_rates(&mut self, pid: u32, counters: Option<&IoInfo>) -> bool {
+        let previous_rates = self.io_rates.clone();
+        let Some(counters) = counters else {
+            self.io_rates = None;
+            self.io_sample = None;
+            return previous_rates.is_some();
+        };
+
+        let now = Instant::now();
+        // Premier échantillon (ou nouveau processus) : pas encore de débit
+        if let Some(previous) = self.io_sample.as_ref().filter(|sample| sample.pid == pid) {
+            let elapsed = now.duration_since(previous.taken_at);
+            if !elapsed.is_zero() {
+                let seconds = elapsed.as_secs_f64();
+                let rate = |current: u64, last: u64| current.saturating_sub(last) as f64 / seconds;
+                let last = &previous.counters;
+
+                self.io_rates = Some(IoRates {
+                    read_bytes_per_sec: rate(counters.read_bytes, last.read_bytes),
+                    write_bytes_per_sec: rate(counters.write_bytes, last.write_bytes),
+                    read_operations_per_sec: rate(counters.read_operations, last.read_operations),
+                    write_operations_per_sec: rate(counters.write_operations, last.write_operations),
+                    disk_read_bytes_per_sec: counters
+                        .disk_read_bytes
+                        .zip(last.disk_read_bytes)
+                        .map(|(current, last)| rate(current, last)),
+                    disk_write_bytes_per_sec: counters
+                        .disk_write_bytes
+                        .zip(last.disk_write_bytes)
+                        .map(|(current, last)| rate(current, last)),
+                    interval_ms: elapsed.as_millis() as u64,
+                });
+            }
+        } else {
+            self.io_rates = None;
+        }
+
+        self.io_sample = Some(IoSample {
+            pid,
+            taken_at: now,
+            counters: counters.clone(),
+        });
+        // L'intervalle varie à chaque vérification : seuls les débits comptent
+        let rates = |rates: &Option<IoRates>| rates.clone().map(|rates| IoRates { interval_ms: 0, ..rates });
+        rates(&previous_rates) != rates(&self.io_rates)
+    }
+}
+
+// Intervalle du polling de repli pour les événements de processus
+const PROCESS_EVENT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Nombre d'événements de premier plan conservés dans l'état
+const MAX_FOREGROUND_EVENTS: usize = 100;
+
+/// Santé de la boucle de surveillance
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct MonitorHealth {
+    /// Dernière vérification réussie (millisecondes depuis l'epoch)
+    pub last_success: Option<u64>,
+    /// Dernier échec (erreur ou timeout)
+    pub last_failure: Option<u64>,
+    pub last_error: Option<String>,
+    /// Échecs consécutifs depuis la dernière réussite
+    pub consecutive_failures: u32,
+    pub total_checks: u64,
+    /// Délai retenu pour la prochaine vérification (en millisecondes)
+    pub next_check_delay_ms: u64,
+}
+
+/// Pilotage des tâches du moniteur (annulation, pause, intervalle)
+#[derive(Debug, Clone, PartialEq)]
+struct MonitorControl {
+    cancelled: bool,
+    paused: bool,
+    check_interval: Duration,
+}
+
+// Durée maximale d'une vérification avant de la compter en échec
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+// Durée pendant laquelle le polling reste rapide après un changement
+const RECENT_CHANGE_WINDOW: Duration = Duration::from_secs(30);
+
+/// Calcul de l'intervalle entre deux vérifications
+struct PollScheduler {
+    last_change: Option<Instant>,
+    /// Clés aléatoires propres au moniteur : le jitter diffère d'un moniteur à l'autre
+    random: RandomState,
+    draws: u64,
+}
+
+impl PollScheduler {
+    fn new() -> Self {
+        Self {
+            last_change: None,
+            random: RandomState::new(),
+            draws: 0,
+        }
+    }
+
+    fn next_delay(
+        &mut self,
+        config: &MonitorConfig,
+        base: Duration,
+        state: &ProcessMonitorState,
+        consecutive_failures: u32,
+    ) -> Duration {
+        let delay = if consecutive_failures > 0 {
+            // Backoff exponentiel : base, 2x, 4x... jusqu'au plafond
+            let factor = 2u32.saturating_pow(consecutive_failures.min(16) - 1);
+            base.saturating_mul(factor)
+                .min(Duration::from_secs(config.max_backoff_interval).max(base))
+        } else if !config.adaptive_polling {
+            base
+        } else if !state.is_active {
+            base.max(Duration::from_secs(config.idle_check_interval))
+        } else if self.recently_changed() || is_media_playing(state) {
+            base.min(Duration::from_millis(config.fast_check_interval_ms))
+        } else {
+            base
+        };
+
+        self.jitter(delay, config.jitter_ratio)
+    }
+
+    fn recently_changed(&self) -> bool {
+        self.last_change
+            .map(|changed| changed.elapsed() < RECENT_CHANGE_WINDOW)
+            .unwrap_or(false)
+    }
+
+    fn jitter(&mut self, delay: Duration, ratio: f64) -> Duration {
+        if ratio <= 0.0 {
+            return delay;
+        }
+
+        self.draws += 1;
+        let mut hasher = self.random.build_hasher();
+        hasher.write_u64(self.draws);
+        // Valeur pseudo-aléatoire dans [-1, 1]
+        let unit = hasher.finish() as f64 / u64::MAX as f64 * 2.0 - 1.0;
+        delay.mul_f64((1.0 + unit * ratio.min(1.0)).max(0.0))
+    }
+}
+
+/// Lecture média en cours (session système ou onglet audible)
+fn is_media_playing(state: &ProcessMonitorState) -> bool {
+    !state.audible_tabs.is_empty()
+        || state
+            .last_metadata
+            .as_ref()
+            .map(|metadata| {
+                metadata
+                    .media_sessions
+                    .iter()
+                    .any(|session| session.playback_state == Some(MediaPlaybackStatus::Playing))
+            })
+            .unwrap_or(false)
+}
+
 /// Moniteur de processus en temps réel
 pub struct RealtimeProcessMonitor {
     config: MonitorConfig,
     state: Arc<Mutex<ProcessMonitorState>>,
-    is_running: Arc<Mutex<bool>>,
+    health: Arc<Mutex<MonitorHealth>>,
+    /// Présent tant que le moniteur tourne ; annuler = envoyer `cancelled`
+    control: Mutex<Option<watch::Sender<MonitorControl>>>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+    /// Intervalle courant, conservé d'un redémarrage à l'autre
+    check_interval: Mutex<Duration>,
+    /// Snapshot partagé par toutes les lectures d'un même tick
+    snapshots: Arc<SnapshotCache>,
 }
 
 impl RealtimeProcessMonitor {
     pub fn new(config: MonitorConfig) -> Self {
+        let check_interval = Duration::from_secs(config.check_interval);
         Self {
             config,
             state: Arc::new(Mutex::new(ProcessMonitorState::default())),
-            is_running: Arc::new(Mutex::new(false)),
+            health: Arc::new(Mutex::new(MonitorHealth::default())),
+            control: Mutex::new(None),
+            tasks: Mutex::new(Vec::new()),
+            check_interval: Mutex::new(check_interval),
+            snapshots: Arc::new(SnapshotCache::new(DEFAULT_SNAPSHOT_TTL)),
         }
     }
 
-    /// Démarrer la surveillance
+    /// Démarrer la surveillance (sans effet si elle tourne déjà)
     pub async fn start(&self) -> Result<()> {
-        let mut is_running = self.is_running.lock().unwrap();
-        if *is_running {
+        if self.is_running() {
             return Ok(()); // Déjà en cours
         }
-        *is_running = true;
-        drop(is_running);
+        // Tâches d'une exécution précédente terminée d'elle-même
+        self.tasks.lock().unwrap().clear();
+
+        let (control_sender, _) = watch::channel(MonitorControl {
+            cancelled: false,
+            paused: false,
+            check_interval: *self.check_interval.lock().unwrap(),
+        });
 
         let config = self.config.clone();
         let state = Arc::clone(&self.state);
-        let is_running = Arc::clone(&self.is_running);
+        let health = Arc::clone(&self.health);
+        let snapshots = Arc::clone(&self.snapshots);
+        let mut tasks = Vec::new();
+
+        // Événements de premier plan : les changements courts entre deux ticks ne sont plus perdus
+        if config.watch_foreground && config.metadata_options.window_info {
+            match ForegroundWatcher::start() {
+                Ok((watcher, mut events)) => {
+                    let config = config.clone();
+                    let state = Arc::clone(&state);
+                    let mut control = control_sender.subscribe();
+                    tasks.push(tokio::spawn(async move {
+                        loop {
+                            tokio::select! {
+                                event = events.recv() => match event {
+                                    Some(event) => Self::handle_foreground_event(&config, &state, event),
+                                    None => break,
+                                },
+                                _ = cancelled(&mut control) => break,
+                            }
+                        }
+
+                        // L'arrêt du watcher attend la fin de son thread : hors du runtime
+                        let _ = tokio::task::spawn_blocking(move || drop(watcher)).await;
+                    }));
+                }
+                Err(e) => {
+                    debug_eprintln!("⚠️ Watcher de premier plan indisponible, polling seul: {}", e);
+                }
+            }
+        }
+
+        // Réveil anticipé de la boucle (démarrage/arrêt du processus cible)
+        let wake = Arc::new(Notify::new());
+
+        if config.process_events {
+            match ProcessEventSource::start(PROCESS_EVENT_POLL_INTERVAL) {
+                Ok((source, mut events)) => {
+                    let config = config.clone();
+                    let state = Arc::clone(&state);
+                    let wake = Arc::clone(&wake);
+                    let snapshots = Arc::clone(&snapshots);
+                    let mut control = control_sender.subscribe();
+                    tasks.push(tokio::spawn(async move {
+                        loop {
+                            tokio::select! {
+                                event = events.recv() => match event {
+                                    Some(event) => Self::handle_process_event(&config, &state, &snapshots, &wake, event),
+                                    None => break,
+                                },
+                                _ = cancelled(&mut control) => break,
+                            }
+                        }
+
+                        let _ = tokio::task::spawn_blocking(move || drop(source)).await;
+                    }));
+                }
+                Err(e) => {
+                    debug_eprintln!("⚠️ Événements de processus indisponibles, polling seul: {}", e);
+                }
+            }
+        }
 
         // Démarrer la boucle de surveillance
-        tokio::task::spawn_blocking(move || {
-            tokio::runtime::Handle::current().block_on(async move {
-                let mut interval = interval(Duration::from_secs(config.check_interval));
+        let mut control = control_sender.subscribe();
+        tasks.push(tokio::spawn(async move {
+            let mut last_check: Option<TokioInstant> = None;
+            let mut scheduler = PollScheduler::new();
+
+            loop {
+                let current = control.borrow_and_update().clone();
+                if current.cancelled {
+                    break;
+                }
+                if current.paused {
+                    // Attendre une reprise (ou l'annulation)
+                    if control.changed().await.is_err() {
+                        break;
+                    }
+                    continue;
+                }
 
-                loop {
-                    interval.tick().await;
+                // Première vérification immédiate, puis selon l'activité et les échecs récents
+                let next_check = match last_check {
+                    Some(last) => {
+                        let consecutive_failures = health.lock().unwrap().consecutive_failures;
+                        let delay = match state.try_lock() {
+                            Ok(state) => {
+                                scheduler.next_delay(&config, current.check_interval, &state, consecutive_failures)
+                            }
+                            Err(_) => current.check_interval,
+                        };
+                        health.lock().unwrap().next_check_delay_ms = delay.as_millis() as u64;
+                        last + delay
+                    }
+                    None => TokioInstant::now(),
+                };
 
-                    // Vérifier si on doit continuer
-                    {
-                        let running = is_running.lock().unwrap();
-                        if !*running {
+                tokio::select! {
+                    _ = tokio::time::sleep_until(next_check) => {}
+                    _ = wake.notified() => {}
+                    changed = control.changed() => {
+                        // Pause, annulation ou nouvel intervalle : réévaluer
+                        if changed.is_err() {
                             break;
                         }
+                        continue;
                     }
+                }
+                last_check = Some(TokioInstant::now());
 
-                    // Vérifier le processus avec timeout pour éviter les blocages
-                    let check_result = tokio::time::timeout(
-                        Duration::from_secs(5), // Timeout de 5 secondes
-                        Self::check_process(&config, &state)
-                    ).await;
+                // Vérifier le processus avec timeout pour éviter les blocages
+                let check_result = tokio::select! {
+                    result = tokio::time::timeout(CHECK_TIMEOUT, Self::check_process(&config, &state, &snapshots)) => result,
+                    _ = cancelled(&mut control) => break,
+                };
 
-        match check_result {
-            Ok(Ok(has_changes)) => {
-                if has_changes {
-                    debug_println!(
-                        "🔄 Changements détectés pour {}",
-                        config.executable_name
-                    );
+                if Self::record_check(&config, &health, check_result) {
+                    scheduler.last_change = Some(Instant::now());
                 }
             }
-            Ok(Err(e)) => {
-                debug_eprintln!("❌ Erreur lors de la vérification: {}", e);
-            }
-            Err(_) => {
-                debug_eprintln!("⏰ Timeout lors de la vérification de {}", config.executable_name);
-            }
-        }
-                }
-            })
-        });
+        }));
 
+        *self.tasks.lock().unwrap() = tasks;
+        *self.control.lock().unwrap() = Some(control_sender);
         Ok(())
     }
 
-    /// Arrêter la surveillance
-    pub fn stop(&self) {
-        let mut is_running = self.is_running.lock().unwrap();
-        *is_running = false;
+    /// Arrêter la surveillance et attendre la fin de toutes ses tâches
+    pub async fn stop(&self) {
+        self.cancel();
+
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap());
+        for task in tasks {
+            let _ = task.await;
+        }
+    }
+
+    /// Redémarrer la surveillance (l'état et la santé sont conservés)
+    pub async fn restart(&self) -> Result<()> {
+        self.stop().await;
+        self.start().await
+    }
+
+    /// Suspendre les vérifications sans arrêter les tâches
+    pub fn pause(&self) {
+        self.update_control(|control| control.paused = true);
+    }
+
+    pub fn resume(&self) {
+        self.update_control(|control| control.paused = false);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.control
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|control| control.borrow().paused)
+            .unwrap_or(false)
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.control.lock().unwrap().is_some()
+            && self.tasks.lock().unwrap().iter().any(|task| !task.is_finished())
+    }
+
+    /// Modifier l'intervalle de vérification, pris en compte immédiatement
+    pub fn set_check_interval(&self, check_interval: Duration) {
+        *self.check_interval.lock().unwrap() = check_interval;
+        self.update_control(|control| control.check_interval = check_interval);
+    }
+
+    pub fn check_interval(&self) -> Duration {
+        *self.check_interval.lock().unwrap()
+    }
+
+    /// Santé de la boucle (dernière réussite, échecs consécutifs...)
+    pub fn health(&self) -> MonitorHealth {
+        self.health.lock().unwrap().clone()
     }
 
     /// Obtenir l'état actuel
@@ -160,24 +585,112 @@ impl RealtimeProcessMonitor {
         }
     }
 
+    fn cancel(&self) {
+        if let Some(control) = self.control.lock().unwrap().take() {
+            control.send_modify(|control| control.cancelled = true);
+        }
+    }
+
+    fn update_control(&self, update: impl FnOnce(&mut MonitorControl)) {
+        if let Some(control) = self.control.lock().unwrap().as_ref() {
+            control.send_modify(update);
+        }
+    }
+
+    /// Mettre à jour la santé ; renvoie vrai si des changements ont été détectés
+    fn record_check(
+        config: &MonitorConfig,
+        health: &Mutex<MonitorHealth>,
+        check_result: std::result::Result<Result<bool>, tokio::time::error::Elapsed>,
+    ) -> bool {
+        let mut has_changes = false;
+        let error = match check_result {
+            Ok(Ok(changed)) => {
+                if changed {
+                    debug_println!(
+                        "🔄 Changements détectés pour {}",
+                        config.executable_name
+                    );
+                }
+                has_changes = changed;
+                None
+            }
+            Ok(Err(e)) => {
+                debug_eprintln!("❌ Erreur lors de la vérification: {}", e);
+                Some(e.to_string())
+            }
+            Err(_) => {
+                debug_eprintln!("⏰ Timeout lors de la vérification de {}", config.executable_name);
+                Some(format!("Timeout après {} s", CHECK_TIMEOUT.as_secs()))
+            }
+        };
+
+        let mut health = health.lock().unwrap();
+        health.total_checks += 1;
+        match error {
+            None => {
+                health.last_success = Some(current_millis());
+                health.consecutive_failures = 0;
+            }
+            Some(error) => {
+                health.last_failure = Some(current_millis());
+                health.last_error = Some(error);
+                health.consecutive_failures += 1;
+            }
+        }
+        has_changes
+    }
+
     /// Vérifier le processus et détecter les changements
     async fn check_process(
         config: &MonitorConfig,
         state: &Arc<Mutex<ProcessMonitorState>>,
+        snapshots: &Arc<SnapshotCache>,
     ) -> Result<bool> {
         let mut has_changes = false;
 
         debug_println!("🔍 Vérification du processus {}...", config.executable_name);
 
-        // Vérifier si le processus existe (approche synchrone)
-        let executable_name = config.executable_name.clone();
+        // Vérifier si le processus existe (le scanner délègue lui-même les appels bloquants)
+        let scanner = ProcessScanner::with_snapshot_cache(Arc::clone(snapshots));
         let options = config.metadata_options.clone();
-        let metadata_result = tokio::task::spawn_blocking(move || {
-            let scanner = ProcessScanner::new();
-            scanner.monitor_process_by_name(&executable_name, Some(options))
-        }).await;
+        let policy = config.selection_policy;
+        let metadata_result = match &config.target_query {
+            Some(query) => scanner.monitor_process_by_query(query, policy, Some(options)).await,
+            None => scanner.monitor_process_by_name_with_policy(&config.executable_name, policy, Some(options)).await,
+        };
+        // Une erreur de lecture n'est pas un arrêt : l'état est conservé et l'échec remonte à la santé
+        let metadata_result = metadata_result?.map(|mut metadata| {
+            if let Some(redactor) = &config.redactor {
+                redactor.redact_metadata(&mut metadata);
+            }
+            metadata
+        });
+
+        // Onglet actif (fenêtres, X11) et état de l'extension (fichiers) : appels bloquants
+        // exécutés sur le pool bloquant, avant de prendre le verrou de l'état
+        let browser_data = match &metadata_result {
+            Some(metadata) if config.metadata_options.window_info => {
+                let pid = metadata.pid;
+                let scanner = ProcessScanner::with_snapshot_cache(Arc::clone(snapshots));
+                let snapshots = Arc::clone(snapshots);
+                Some(
+                    tokio::task::spawn_blocking(move || {
+                        let active_tab = scanner.get_active_browser_tab(pid).ok().flatten();
+                        // Onglets enregistrés sous le PID du processus principal du navigateur
+                        let tab_state = snapshots.get().ok().and_then(|snapshot| {
+                            BrowserTabStore::new()
+                                .load_for_process(pid, |pid| snapshot.process(pid).map(|entry| entry.parent_pid))
+                        });
+                        (active_tab, tab_state)
+                    })
+                    .await?,
+                )
+            }
+            _ => None,
+        };
 
-        if let Ok(Ok(Some(metadata))) = metadata_result {
+        if let Some(metadata) = metadata_result {
             debug_println!(
                 "✅ Processus {} trouvé, PID: {}",
                 config.executable_name,
@@ -207,7 +720,13 @@ impl RealtimeProcessMonitor {
                 .map(|last| Self::has_metadata_changed(last, &metadata))
                 .unwrap_or(true);
 
-            if is_new_process || metadata_changed {
+            if is_new_process {
+                current_state.process_started_at = process_start_time(metadata.pid);
+            }
+            // Un processus inactif garde des débits nuls : rien à publier
+            let io_rates_changed = current_state.update_io_rates(metadata.pid, metadata.io_info.as_ref());
+
+            if is_new_process || metadata_changed || io_rates_changed {
                 current_state.last_metadata = Some(metadata.clone());
                 current_state.is_active = true;
                 current_state.last_update = Some(Instant::now());
@@ -220,16 +739,30 @@ impl RealtimeProcessMonitor {
             }
 
             // Vérifier l'onglet actif pour les navigateurs
-            if config.metadata_options.window_info {
-                let scanner = ProcessScanner::new();
-                if let Ok(Some(active_tab)) = scanner.get_active_browser_tab(metadata.pid) {
+            if let Some((active_tab, tab_state)) = browser_data {
+                if let Some(mut active_tab) = active_tab {
+                    // Analyse sur le titre brut, masquage avant comparaison et stockage
+                    let parser = BrowserTitleParser::with_categories(config.site_categories.clone());
+                    let mut parsed = parser.parse_window(&active_tab);
+                    if let Some(redactor) = &config.redactor {
+                        redactor.redact_window(&metadata.name, &mut active_tab);
+                        if let Some(parsed) = &mut parsed {
+                            redactor.redact_parsed_title(&metadata.name, parsed);
+                        }
+                    }
+
                     let tab_changed = current_state
                         .last_active_tab
                         .as_ref()
-                        .map(|last| last.window_title != active_tab.window_title)
+                        .map(|last| {
+                            last.window_title != active_tab.window_title
+                                || last.browser_tab.as_ref().map(|tab| &tab.url)
+                                    != active_tab.browser_tab.as_ref().map(|tab| &tab.url)
+                        })
                         .unwrap_or(true);
 
                     if tab_changed {
+                        current_state.last_active_tab_title = parsed;
                         current_state.last_active_tab = Some(active_tab.clone());
                         has_changes = true;
                         debug_println!(
@@ -238,9 +771,29 @@ impl RealtimeProcessMonitor {
                         );
                     }
                 }
+
+                // Onglets audibles rapportés par l'extension (même s'ils ne sont pas au premier plan)
+                if let Some(mut tab_state) = tab_state {
+                    if let Some(redactor) = &config.redactor {
+                        for tab in &mut tab_state.audible_tabs {
+                            redactor.redact_browser_tab(&metadata.name, tab);
+                        }
+                    }
+                    let audible_changed = current_state.audible_tabs.len() != tab_state.audible_tabs.len()
+                        || current_state
+                            .audible_tabs
+                            .iter()
+                            .zip(tab_state.audible_tabs.iter())
+                            .any(|(last, current)| last.tab_id != current.tab_id || last.url != current.url);
+
+                    if audible_changed {
+                        current_state.audible_tabs = tab_state.audible_tabs;
+                        has_changes = true;
+                    }
+                }
             }
         } else {
-            // Le processus n'existe plus ou timeout
+            // Le processus n'existe plus
             let mut current_state = match state.try_lock() {
                 Ok(guard) => guard,
                 Err(_) => {
@@ -250,16 +803,134 @@ impl RealtimeProcessMonitor {
             };
             
             if current_state.is_active {
+                // Fin détectée par polling : pas de code de sortie (déjà enregistrée si l'événement est arrivé)
+                if let Some(pid) = current_state.last_metadata.as_ref().map(|m| m.pid) {
+                    if current_state.last_exit.as_ref().map(|exit| exit.pid) != Some(pid) {
+                        let exited_at = current_millis();
+                        current_state.last_exit = Some(ProcessExitInfo {
+                            pid,
+                            exit_code: None,
+                            exited_at,
+                            lifetime_ms: current_state
+                                .process_started_at
+                                .map(|started| exited_at.saturating_sub(started)),
+                        });
+                    }
+                }
                 current_state.is_active = false;
+                current_state.io_rates = None;
                 current_state.last_update = Some(Instant::now());
                 has_changes = true;
-                debug_println!("⚠️ Processus {} arrêté ou timeout", config.executable_name);
+                debug_println!("⚠️ Processus {} arrêté", config.executable_name);
             }
         }
 
         Ok(has_changes)
     }
 
+    /// Réagir à un démarrage/arrêt de processus sans attendre le prochain tick
+    fn handle_process_event(
+        config: &MonitorConfig,
+        state: &Arc<Mutex<ProcessMonitorState>>,
+        snapshots: &SnapshotCache,
+        wake: &Notify,
+        event: ProcessEvent,
+    ) {
+        // La liste des processus a changé : le prochain tick doit reprendre un snapshot
+        snapshots.invalidate();
+
+        match event {
+            ProcessEvent::Started { pid, exe, .. } => {
+                let name = exe.rsplit(['/', '\\']).next().unwrap_or(&exe).to_lowercase();
+                let is_candidate = match &config.target_query {
+                    // Seuls le nom et le chemin sont connus ici : check_process tranche pour les autres champs
+                    Some(query) if !exe.is_empty() => {
+                        let path = Some(exe.as_str()).filter(|exe| exe.contains(['/', '\\']));
+                        query.may_match(&name, path, None)
+                    }
+                    Some(_) => true,
+                    None => name == config.executable_name.to_lowercase(),
+                };
+                if is_candidate {
+                    debug_println!("🚀 Processus {} démarré (PID: {})", config.executable_name, pid);
+                    wake.notify_one();
+                }
+            }
+            ProcessEvent::Exited { pid, exit_code, timestamp } => {
+                let mut current_state = match state.lock() {
+                    Ok(guard) => guard,
+                    Err(_) => return,
+                };
+
+                let is_tracked = current_state.is_active
+                    && current_state.last_metadata.as_ref().map(|m| m.pid) == Some(pid);
+                if !is_tracked {
+                    return;
+                }
+
+                current_state.is_active = false;
+                current_state.io_rates = None;
+                current_state.last_update = Some(Instant::now());
+                current_state.last_exit = Some(ProcessExitInfo {
+                    pid,
+                    exit_code,
+                    exited_at: timestamp,
+                    lifetime_ms: current_state
+                        .process_started_at
+                        .map(|started| timestamp.saturating_sub(started)),
+                });
+                debug_println!("⚠️ Processus {} terminé (code: {:?})", config.executable_name, exit_code);
+
+                // Une autre instance peut prendre le relais
+                wake.notify_one();
+            }
+        }
+    }
+
+    /// Appliquer un événement de premier plan à l'état
+    fn handle_foreground_event(
+        config: &MonitorConfig,
+        state: &Arc<Mutex<ProcessMonitorState>>,
+        mut event: ForegroundEvent,
+    ) {
+        let mut current_state = match state.lock() {
+            Ok(guard) => guard,
+            Err(_) => return,
+        };
+        let tracked_pid = current_state.last_metadata.as_ref().map(|m| m.pid);
+        let is_target = match &config.target_query {
+            Some(_) => tracked_pid == Some(event.window.process_id),
+            None => event.process_name == config.executable_name.to_lowercase(),
+        };
+
+        // Un changement de focus vers une autre application fait perdre le premier plan
+        if event.kind == ForegroundEventKind::FocusChanged {
+            current_state.is_foreground = is_target;
+        }
+        if !is_target {
+            return;
+        }
+
+        let parser = BrowserTitleParser::with_categories(config.site_categories.clone());
+        let mut parsed = parser.parse_window(&event.window);
+        if let Some(redactor) = &config.redactor {
+            redactor.redact_foreground_event(&mut event);
+            if let Some(parsed) = &mut parsed {
+                redactor.redact_parsed_title(&event.process_name, parsed);
+            }
+        }
+        if let Some(parsed) = parsed {
+            debug_println!("🔄 Onglet actif (événement): {}", event.window.window_title);
+            current_state.last_active_tab_title = Some(parsed);
+            current_state.last_active_tab = Some(event.window.clone());
+        }
+
+        current_state.last_update = Some(Instant::now());
+        current_state.foreground_events.push(event);
+        let overflow = current_state.foreground_events.len().saturating_sub(MAX_FOREGROUND_EVENTS);
+        current_state.foreground_events.drain(..overflow);
+    }
+
     /// Vérifier si les métadonnées ont changé
     fn has_metadata_changed(last: &ProcessMetadata, current: &ProcessMetadata) -> bool {
         // Comparer les champs importants
@@ -267,6 +938,7 @@ impl RealtimeProcessMonitor {
             || last.working_set_size != current.working_set_size
             || last.handle_count != current.handle_count
             || last.windows.len() != current.windows.len()
+            || last.io_info != current.io_info
         {
             return true;
         }
@@ -303,6 +975,25 @@ impl RealtimeProcessMonitor {
     }
 }
 
+impl Drop for RealtimeProcessMonitor {
+    fn drop(&mut self) {
+        // Les tâches s'arrêtent d'elles-mêmes à leur prochain point d'attente
+        self.cancel();
+    }
+}
+
+/// Se termine à l'annulation, ou si le moniteur a été libéré
+async fn cancelled(control: &mut watch::Receiver<MonitorControl>) {
+    let _ = control.wait_for(|control| control.cancelled).await;
+}
+
+fn current_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
 /// Fonction utilitaire pour créer un moniteur simple
 pub fn create_simple_monitor(executable_name: &str, check_interval: u64) -> RealtimeProcessMonitor {
     let mut config = MonitorConfig::default();
@@ -324,3 +1015,41 @@ pub fn create_simple_monitor(executable_name: &str, check_interval: u64) -> Real
 
     RealtimeProcessMonitor::new(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counters(read_bytes: u64) -> IoInfo {
+        IoInfo {
+            read_bytes,
+            ..IoInfo::default()
+        }
+    }
+
+    #[test]
+    fn io_rate_updates_are_reported_as_changes() {
+        let mut state = ProcessMonitorState::default();
+
+        // Premier échantillon : pas encore de débit
+        assert!(!state.update_io_rates(7, Some(&counters(0))));
+        assert!(state.io_rates.is_none());
+
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(state.update_io_rates(7, Some(&counters(4096))));
+        assert!(state.io_rates.as_ref().unwrap().read_bytes_per_sec > 0.0);
+
+        // Activité interrompue : le débit retombe à zéro, c'est un changement
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(state.update_io_rates(7, Some(&counters(4096))));
+        assert_eq!(state.io_rates.as_ref().unwrap().read_bytes_per_sec, 0.0);
+
+        // Toujours inactif : seul l'intervalle varie, rien à publier
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(!state.update_io_rates(7, Some(&counters(4096))));
+
+        // Compteurs indisponibles : les débits disparaissent
+        assert!(state.update_io_rates(7, None));
+        assert!(!state.update_io_rates(7, None));
+    }
+}
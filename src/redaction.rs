@@ -0,0 +1,788 @@
+use crate::foreground_watcher::ForegroundEvent;
+use crate::models::{
+    ApplicationInfo, BrowserTabInfo, ContainerInfo, MediaSessionInfo, NetworkConnection, ParsedTabTitle, ProcessInfo,
+    ProcessMetadata, ScanResult, WindowInfo,
+};
+use anyhow::Result;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Règles de masquage des données personnelles avant publication (télémétrie, fichiers de sortie)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RedactionConfig {
+    /// Expressions régulières remplacées dans tous les textes collectés
+    pub rules: Vec<RedactionRule>,
+    /// Mots-clés (insensibles à la casse) : un texte qui en contient un est masqué en entier
+    pub keywords: Vec<String>,
+    /// Remplacer les titres (fenêtres, onglets, médias) par leur empreinte SHA-256
+    pub hash_titles: bool,
+    /// Sel ajouté avant hachage, pour que les empreintes ne soient pas devinables
+    pub hash_salt: String,
+    /// Variables d'environnement supprimées, par motif de nom (`*` = n'importe quelle suite)
+    pub drop_env_patterns: Vec<String>,
+    /// Applications jamais masquées (outils internes de confiance)
+    pub allow_applications: Vec<String>,
+    /// Applications dont tous les textes sont masqués
+    pub deny_applications: Vec<String>,
+    /// Masquer entièrement les onglets de navigation privée
+    pub redact_incognito: bool,
+    /// Remplacer les adresses distantes des connexions réseau par leur empreinte (boucle locale conservée)
+    pub redact_remote_addresses: bool,
+    /// Remplacer les noms d'utilisateur propriétaires des processus par leur empreinte
+    pub redact_user_names: bool,
+    /// Texte de remplacement
+    pub replacement: String,
+}
+
+/// Remplacement par expression régulière (`replacement` peut utiliser les groupes `$1`, `$name`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RedactionRule {
+    pub name: String,
+    pub pattern: String,
+    pub replacement: Option<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        let rule = |name: &str, pattern: &str, replacement: Option<&str>| RedactionRule {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            replacement: replacement.map(|r| r.to_string()),
+        };
+
+        Self {
+            rules: vec![
+                rule("email", r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}", None),
+                // Paramètres d'URL et options de ligne de commande porteurs de secrets
+                rule(
+                    "secret_param",
+                    r"(?i)\b(token|access_token|api_key|apikey|password|passwd|pwd|secret|auth|session)([=:]\s*)[^\s&;]+",
+                    Some("$1$2[REDACTED]"),
+                ),
+                rule("bearer", r"(?i)\bbearer\s+[A-Za-z0-9._~+/=-]+", Some("Bearer [REDACTED]")),
+            ],
+            keywords: Vec::new(),
+            hash_titles: false,
+            hash_salt: String::new(),
+            drop_env_patterns: [
+                "*TOKEN*", "*SECRET*", "*PASSWORD*", "*PASSWD*", "*API_KEY*", "*APIKEY*", "*CREDENTIAL*",
+                "*PRIVATE_KEY*", "AWS_*", "AZURE_*", "GITHUB_*", "NPM_CONFIG_*",
+            ]
+            .iter()
+            .map(|p| p.to_string())
+            .collect(),
+            allow_applications: Vec::new(),
+            deny_applications: Vec::new(),
+            redact_incognito: true,
+            redact_remote_addresses: true,
+            redact_user_names: true,
+            replacement: "[REDACTED]".to_string(),
+        }
+    }
+}
+
+/// Nombre de valeurs masquées, par type de champ
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RedactionAudit {
+    pub total: u64,
+    pub by_field: BTreeMap<String, u64>,
+}
+
+impl RedactionAudit {
+    fn record(&mut self, field: &str) {
+        self.total += 1;
+        *self.by_field.entry(field.to_string()).or_default() += 1;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ApplicationPolicy {
+    Allow,
+    Deny,
+    Filter,
+}
+
+/// Pipeline de masquage compilé à partir d'une `RedactionConfig`
+pub struct Redactor {
+    config: RedactionConfig,
+    rules: Vec<(Regex, String)>,
+    keywords: Vec<String>,
+    env_patterns: Vec<Regex>,
+    audit: Mutex<RedactionAudit>,
+}
+
+impl Redactor {
+    /// Compiler les règles (l'erreur indique la clé fautive, ex: rules[2].pattern)
+    pub fn new(config: RedactionConfig) -> Result<Self> {
+        let mut rules = Vec::new();
+        for (index, rule) in config.rules.iter().enumerate() {
+            let regex = Regex::new(&rule.pattern)
+                .map_err(|e| anyhow::anyhow!("clé `rules[{}].pattern` ({}): {}", index, rule.name, e))?;
+            let replacement = rule.replacement.clone().unwrap_or_else(|| config.replacement.clone());
+            rules.push((regex, replacement));
+        }
+
+        let mut env_patterns = Vec::new();
+        for (index, pattern) in config.drop_env_patterns.iter().enumerate() {
+            let regex = RegexBuilder::new(&glob_to_regex(pattern))
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| anyhow::anyhow!("clé `drop_env_patterns[{}]`: {}", index, e))?;
+            env_patterns.push(regex);
+        }
+
+        let keywords = config
+            .keywords
+            .iter()
+            .filter(|keyword| !keyword.is_empty())
+            .map(|keyword| keyword.to_lowercase())
+            .collect();
+
+        Ok(Self {
+            config,
+            rules,
+            keywords,
+            env_patterns,
+            audit: Mutex::new(RedactionAudit::default()),
+        })
+    }
+
+    pub fn config(&self) -> &RedactionConfig {
+        &self.config
+    }
+
+    /// Compteurs cumulés depuis la création (ou la dernière remise à zéro)
+    pub fn audit(&self) -> RedactionAudit {
+        self.audit.lock().unwrap().clone()
+    }
+
+    pub fn reset_audit(&self) {
+        *self.audit.lock().unwrap() = RedactionAudit::default();
+    }
+
+    /// Masquer un texte libre (ligne de commande, chemin...) selon les mots-clés et les règles
+    pub fn redact_text(&self, field: &str, text: &mut String) {
+        if text.is_empty() {
+            return;
+        }
+
+        let lowercase = text.to_lowercase();
+        if self.keywords.iter().any(|keyword| lowercase.contains(keyword)) {
+            *text = self.config.replacement.clone();
+            self.record(field);
+            return;
+        }
+
+        let mut changed = false;
+        for (regex, replacement) in &self.rules {
+            if regex.is_match(text) {
+                *text = regex.replace_all(text, replacement.as_str()).into_owned();
+                changed = true;
+            }
+        }
+        if changed {
+            self.record(field);
+        }
+    }
+
+    /// Masquer un titre : empreinte si `hash_titles`, sinon mêmes règles qu'un texte libre
+    pub fn redact_title(&self, field: &str, title: &mut String) {
+        if title.is_empty() {
+            return;
+        }
+
+        if self.config.hash_titles {
+            *title = self.hash(title);
+            self.record(field);
+        } else {
+            self.redact_text(field, title);
+        }
+    }
+
+    /// La variable d'environnement doit-elle être supprimée ?
+    pub fn is_env_var_dropped(&self, name: &str) -> bool {
+        self.env_patterns.iter().any(|pattern| pattern.is_match(name))
+    }
+
+    pub fn redact_metadata(&self, metadata: &mut ProcessMetadata) {
+        let policy = self.policy_for(&metadata.name);
+        if policy == ApplicationPolicy::Allow {
+            return;
+        }
+
+        // Chaque champ est soit masqué ci-dessous, soit déclaré publiable : un champ ajouté
+        // à ProcessMetadata ne compile pas tant qu'il n'est pas classé ici
+        let ProcessMetadata {
+            // Publiables : identifiants, horodatages, compteurs et mesures
+            pid: _,
+            parent_pid: _,
+            name: _,
+            creation_time: _,
+            exit_time: _,
+            memory_info: _,
+            cpu_info: _,
+            io_info: _,
+            thread_count: _,
+            priority_class: _,
+            handle_count: _,
+            page_fault_count: _,
+            peak_working_set_size: _,
+            working_set_size: _,
+            quota_peak_paged_pool_usage: _,
+            quota_paged_pool_usage: _,
+            quota_peak_non_paged_pool_usage: _,
+            quota_non_paged_pool_usage: _,
+            pagefile_usage: _,
+            peak_pagefile_usage: _,
+            threads: _,
+            // Masqués
+            executable_path,
+            command_line,
+            command_line_args,
+            command_line_info,
+            working_directory,
+            executable_info,
+            window_title,
+            memory_map,
+            container_info,
+            owner_info,
+            windows,
+            modules,
+            media_sessions,
+            handles,
+            network_connections,
+            environment_variables,
+            raw_data,
+        } = metadata;
+
+        self.redact_optional_text(policy, "command_line", command_line);
+        for arg in command_line_args {
+            self.redact_text_with(policy, "command_line_arg", arg);
+        }
+        if let Some(info) = command_line_info {
+            self.redact_optional_text(policy, "command_line_script", &mut info.script);
+        }
+        self.redact_optional_text(policy, "working_directory", working_directory);
+        self.redact_optional_text(policy, "executable_path", executable_path);
+        if let Some(info) = executable_info {
+            // Version, empreinte et build-id décrivent le binaire, pas l'utilisateur
+            self.redact_text_with(policy, "executable_path", &mut info.path);
+        }
+        self.redact_optional_title(policy, "window_title", window_title);
+
+        if let Some(map) = memory_map {
+            for region in &mut map.regions {
+                self.redact_optional_text(policy, "memory_region_path", &mut region.path);
+            }
+        }
+        if let Some(container) = container_info {
+            self.redact_container(policy, container);
+        }
+        if let Some(owner) = owner_info {
+            // Identifiants numériques et SID conservés : ils ne sont pas nominatifs sans l'annuaire
+            if let Some(user_name) = &mut owner.user_name {
+                if policy == ApplicationPolicy::Deny {
+                    self.replace("user_name", user_name);
+                } else if self.config.redact_user_names {
+                    self.hash_field("user_name", user_name);
+                }
+            }
+        }
+
+        for window in windows {
+            self.redact_window_with(policy, window);
+        }
+        for module in modules {
+            self.redact_text_with(policy, "module_path", &mut module.module_path);
+        }
+        for session in media_sessions {
+            self.redact_media_session(policy, session);
+        }
+        for handle in handles {
+            self.redact_optional_text(policy, "handle_name", &mut handle.object_name);
+        }
+        for connection in network_connections {
+            self.redact_connection(policy, connection);
+        }
+
+        let before = environment_variables.len();
+        if policy == ApplicationPolicy::Deny {
+            environment_variables.clear();
+        } else {
+            environment_variables.retain(|name, _| !self.is_env_var_dropped(name));
+            for value in environment_variables.values_mut() {
+                self.redact_text("environment_value", value);
+            }
+        }
+        for _ in environment_variables.len()..before {
+            self.record("environment_variable");
+        }
+
+        for (key, value) in raw_data {
+            self.redact_json(policy, key, value);
+        }
+    }
+
+    pub fn redact_scan(&self, scan: &mut ScanResult) {
+        for application in &mut scan.applications {
+            self.redact_application(application);
+        }
+    }
+
+    pub fn redact_application(&self, application: &mut ApplicationInfo) {
+        let policy = self.policy_for(&application.main_process.name);
+        if policy != ApplicationPolicy::Allow {
+            if let Some(identity) = &mut application.identity {
+                // Nom affiché et éditeur viennent du binaire ; l'icône peut être un chemin
+                self.redact_optional_text(policy, "identity_icon", &mut identity.icon);
+            }
+            if let Some(container) = &mut application.container_info {
+                self.redact_container(policy, container);
+            }
+        }
+        self.redact_process_info(&mut application.main_process);
+    }
+
+    pub fn redact_process_info(&self, process: &mut ProcessInfo) {
+        let policy = self.policy_for(&process.name);
+        if policy != ApplicationPolicy::Allow {
+            self.redact_optional_title(policy, "window_title", &mut process.window_title);
+            self.redact_optional_text(policy, "executable_path", &mut process.executable_path);
+        }
+        for subprocess in &mut process.subprocesses {
+            self.redact_process_info(subprocess);
+        }
+    }
+
+    /// Fenêtre appartenant à l'application `app` (nom d'exécutable)
+    pub fn redact_window(&self, app: &str, window: &mut WindowInfo) {
+        let policy = self.policy_for(app);
+        if policy != ApplicationPolicy::Allow {
+            self.redact_window_with(policy, window);
+        }
+    }
+
+    pub fn redact_browser_tab(&self, app: &str, tab: &mut BrowserTabInfo) {
+        let policy = self.policy_for(app);
+        if policy != ApplicationPolicy::Allow {
+            self.redact_tab_with(policy, tab);
+        }
+    }
+
+    pub fn redact_parsed_title(&self, app: &str, parsed: &mut ParsedTabTitle) {
+        let policy = self.policy_for(app);
+        if policy == ApplicationPolicy::Allow {
+            return;
+        }
+        // Le site et la catégorie sont conservés : ils servent aux statistiques d'usage
+        self.redact_title_with(policy, "tab_title", &mut parsed.raw_title);
+        self.redact_title_with(policy, "tab_title", &mut parsed.page_title);
+    }
+
+    pub fn redact_foreground_event(&self, event: &mut ForegroundEvent) {
+        let app = event.process_name.clone();
+        self.redact_window(&app, &mut event.window);
+    }
+
+    fn redact_window_with(&self, policy: ApplicationPolicy, window: &mut WindowInfo) {
+        self.redact_title_with(policy, "window_title", &mut window.window_title);
+        if let Some(tab) = &mut window.browser_tab {
+            self.redact_tab_with(policy, tab);
+        }
+    }
+
+    fn redact_tab_with(&self, policy: ApplicationPolicy, tab: &mut BrowserTabInfo) {
+        if policy == ApplicationPolicy::Deny || (tab.is_incognito && self.config.redact_incognito) {
+            self.replace("tab_url", &mut tab.url);
+            self.replace("tab_title", &mut tab.title);
+            return;
+        }
+        self.redact_text("tab_url", &mut tab.url);
+        self.redact_title("tab_title", &mut tab.title);
+    }
+
+    fn redact_container(&self, policy: ApplicationPolicy, container: &mut ContainerInfo) {
+        // Le nom d'unité systemd peut reprendre celui d'un script ou d'un projet
+        self.redact_text_with(policy, "cgroup_path", &mut container.cgroup_path);
+        self.redact_optional_text(policy, "systemd_unit", &mut container.systemd_unit);
+    }
+
+    fn redact_connection(&self, policy: ApplicationPolicy, connection: &mut NetworkConnection) {
+        let Some(address) = &mut connection.remote_address else {
+            return;
+        };
+        if is_local_address(address) {
+            return;
+        }
+        if policy == ApplicationPolicy::Deny {
+            self.replace("remote_address", address);
+        } else if self.config.redact_remote_addresses {
+            // L'empreinte permet encore de compter les connexions vers un même hôte
+            self.hash_field("remote_address", address);
+        }
+    }
+
+    fn redact_media_session(&self, policy: ApplicationPolicy, session: &mut MediaSessionInfo) {
+        self.redact_optional_title(policy, "media_title", &mut session.title);
+        self.redact_optional_title(policy, "media_artist", &mut session.artist);
+        self.redact_optional_title(policy, "media_album", &mut session.album);
+    }
+
+    /// Propriétés brutes (JSON) : les clés qui décrivent un titre sont traitées comme des titres
+    fn redact_json(&self, policy: ApplicationPolicy, key: &str, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::String(text) => {
+                let key = key.to_lowercase();
+                if ["title", "artist", "album"].iter().any(|name| key.contains(name)) {
+                    self.redact_title_with(policy, "raw_title", text);
+                } else if policy == ApplicationPolicy::Deny {
+                    self.replace("raw_text", text);
+                } else {
+                    self.redact_text("raw_text", text);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    self.redact_json(policy, key, item);
+                }
+            }
+            serde_json::Value::Object(fields) => {
+                for (field, item) in fields {
+                    self.redact_json(policy, field, item);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn redact_optional_text(&self, policy: ApplicationPolicy, field: &str, text: &mut Option<String>) {
+        if let Some(text) = text {
+            self.redact_text_with(policy, field, text);
+        }
+    }
+
+    fn redact_text_with(&self, policy: ApplicationPolicy, field: &str, text: &mut String) {
+        if policy == ApplicationPolicy::Deny {
+            self.replace(field, text);
+        } else {
+            self.redact_text(field, text);
+        }
+    }
+
+    fn redact_optional_title(&self, policy: ApplicationPolicy, field: &str, title: &mut Option<String>) {
+        if let Some(title) = title {
+            self.redact_title_with(policy, field, title);
+        }
+    }
+
+    fn redact_title_with(&self, policy: ApplicationPolicy, field: &str, title: &mut String) {
+        if policy == ApplicationPolicy::Deny {
+            self.replace(field, title);
+        } else {
+            self.redact_title(field, title);
+        }
+    }
+
+    fn replace(&self, field: &str, text: &mut String) {
+        if !text.is_empty() && *text != self.config.replacement {
+            *text = self.config.replacement.clone();
+            self.record(field);
+        }
+    }
+
+    fn hash_field(&self, field: &str, text: &mut String) {
+        if !text.is_empty() && !text.starts_with("sha256:") {
+            *text = self.hash(text);
+            self.record(field);
+        }
+    }
+
+    fn hash(&self, text: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.config.hash_salt.as_bytes());
+        hasher.update(text.as_bytes());
+        let digest = hasher.finalize();
+        // 64 bits suffisent pour regrouper des titres identiques sans les révéler
+        let hex: String = digest.iter().take(8).map(|byte| format!("{:02x}", byte)).collect();
+        format!("sha256:{}", hex)
+    }
+
+    fn policy_for(&self, app: &str) -> ApplicationPolicy {
+        let matches = |list: &[String]| list.iter().any(|name| same_application(name, app));
+
+        if matches(&self.config.allow_applications) {
+            ApplicationPolicy::Allow
+        } else if matches(&self.config.deny_applications) {
+            ApplicationPolicy::Deny
+        } else {
+            ApplicationPolicy::Filter
+        }
+    }
+
+    fn record(&self, field: &str) {
+        self.audit.lock().unwrap().record(field);
+    }
+}
+
+/// Comparaison de noms d'applications insensible à la casse et à l'extension .exe
+fn same_application(configured: &str, app: &str) -> bool {
+    let normalize = |name: &str| {
+        let name = name.to_lowercase();
+        name.strip_suffix(".exe").map(|n| n.to_string()).unwrap_or(name)
+    };
+    !app.is_empty() && normalize(configured) == normalize(app)
+}
+
+/// Boucle locale ou adresse non spécifiée : rien à protéger
+fn is_local_address(address: &str) -> bool {
+    match address.parse::<std::net::IpAddr>() {
+        Ok(ip) => ip.is_loopback() || ip.is_unspecified(),
+        Err(_) => false,
+    }
+}
+
+/// Motif de nom (`*`, `?`) vers expression régulière ancrée
+fn glob_to_regex(pattern: &str) -> String {
+    let mut regex = String::from("^");
+    for c in pattern.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        ExecutableInfo, MemoryMap, MemoryRegion, MemoryUsage, ModuleInfo, OwnerInfo,
+    };
+
+    fn metadata(name: &str) -> ProcessMetadata {
+        ProcessMetadata {
+            pid: 42,
+            parent_pid: 1,
+            name: name.to_string(),
+            executable_path: Some("/opt/app/bin/app".to_string()),
+            command_line: Some("app --token=abc123 --user alice@example.com".to_string()),
+            command_line_args: vec!["--token=abc123".to_string()],
+            command_line_info: None,
+            working_directory: Some("/home/alice/secret-project".to_string()),
+            executable_info: Some(ExecutableInfo {
+                path: "/home/alice/secret-project/app".to_string(),
+                size: 0,
+                modified: None,
+                sha256: String::new(),
+                format: "elf".to_string(),
+                architecture: None,
+                version: None,
+                build_id: None,
+                soname: None,
+            }),
+            window_title: Some("Inbox - alice@example.com".to_string()),
+            creation_time: None,
+            exit_time: None,
+            memory_info: None,
+            memory_map: Some(MemoryMap {
+                regions: vec![MemoryRegion {
+                    start_address: 0x1000,
+                    end_address: 0x2000,
+                    permissions: "r--p".to_string(),
+                    kind: "file".to_string(),
+                    path: Some("/home/alice/secret-project/data.db".to_string()),
+                    usage: MemoryUsage::default(),
+                }],
+                ..MemoryMap::default()
+            }),
+            cpu_info: None,
+            io_info: None,
+            container_info: Some(ContainerInfo {
+                cgroup_path: "/user.slice/secret-project.scope".to_string(),
+                systemd_unit: Some("secret-project.scope".to_string()),
+                ..ContainerInfo::default()
+            }),
+            owner_info: Some(OwnerInfo {
+                user_id: Some("1000".to_string()),
+                user_name: Some("alice".to_string()),
+                ..OwnerInfo::default()
+            }),
+            thread_count: 1,
+            priority_class: None,
+            handle_count: 0,
+            page_fault_count: 0,
+            peak_working_set_size: 0,
+            working_set_size: 0,
+            quota_peak_paged_pool_usage: 0,
+            quota_paged_pool_usage: 0,
+            quota_peak_non_paged_pool_usage: 0,
+            quota_non_paged_pool_usage: 0,
+            pagefile_usage: 0,
+            peak_pagefile_usage: 0,
+            windows: Vec::new(),
+            threads: Vec::new(),
+            modules: vec![ModuleInfo {
+                module_name: "libsecret-project.so".to_string(),
+                module_path: "/home/alice/secret-project/libsecret-project.so".to_string(),
+                base_address: 0,
+                module_size: 0,
+                entry_point: 0,
+            }],
+            media_sessions: Vec::new(),
+            handles: Vec::new(),
+            network_connections: vec![
+                connection("203.0.113.7"),
+                connection("127.0.0.1"),
+            ],
+            environment_variables: [("GITHUB_TOKEN", "ghp_x"), ("LANG", "fr_FR.UTF-8")]
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            raw_data: Default::default(),
+        }
+    }
+
+    fn connection(remote: &str) -> NetworkConnection {
+        NetworkConnection {
+            protocol: "tcp".to_string(),
+            local_address: "192.168.1.10".to_string(),
+            local_port: 50000,
+            remote_address: Some(remote.to_string()),
+            remote_port: Some(443),
+            state: Some("ESTABLISHED".to_string()),
+            inode: None,
+            pid: 42,
+        }
+    }
+
+    fn redactor(config: RedactionConfig) -> Redactor {
+        Redactor::new(config).unwrap()
+    }
+
+    #[test]
+    fn filter_policy_applies_rules_and_hashes_identities() {
+        let redactor = redactor(RedactionConfig {
+            keywords: vec!["secret-project".to_string()],
+            ..RedactionConfig::default()
+        });
+        let mut metadata = metadata("app");
+        redactor.redact_metadata(&mut metadata);
+
+        assert_eq!(
+            metadata.command_line.as_deref(),
+            Some("app --token=[REDACTED] --user [REDACTED]")
+        );
+        assert_eq!(metadata.working_directory.as_deref(), Some("[REDACTED]"));
+        assert_eq!(metadata.executable_info.as_ref().unwrap().path, "[REDACTED]");
+        assert_eq!(
+            metadata.memory_map.as_ref().unwrap().regions[0].path.as_deref(),
+            Some("[REDACTED]")
+        );
+        assert_eq!(metadata.modules[0].module_path, "[REDACTED]");
+        let container = metadata.container_info.as_ref().unwrap();
+        assert_eq!(container.cgroup_path, "[REDACTED]");
+        assert_eq!(container.systemd_unit.as_deref(), Some("[REDACTED]"));
+
+        let owner = metadata.owner_info.as_ref().unwrap();
+        assert!(owner.user_name.as_deref().unwrap().starts_with("sha256:"));
+        assert_eq!(owner.user_id.as_deref(), Some("1000"));
+
+        let remote = metadata.network_connections[0].remote_address.as_deref().unwrap();
+        assert!(remote.starts_with("sha256:"));
+        assert_eq!(metadata.network_connections[1].remote_address.as_deref(), Some("127.0.0.1"));
+
+        assert!(!metadata.environment_variables.contains_key("GITHUB_TOKEN"));
+        assert!(metadata.environment_variables.contains_key("LANG"));
+
+        let audit = redactor.audit();
+        assert_eq!(audit.by_field.get("remote_address"), Some(&1));
+        assert_eq!(audit.by_field.get("user_name"), Some(&1));
+    }
+
+    #[test]
+    fn identity_hashing_can_be_disabled() {
+        let redactor = redactor(RedactionConfig {
+            redact_remote_addresses: false,
+            redact_user_names: false,
+            ..RedactionConfig::default()
+        });
+        let mut metadata = metadata("app");
+        redactor.redact_metadata(&mut metadata);
+
+        assert_eq!(metadata.network_connections[0].remote_address.as_deref(), Some("203.0.113.7"));
+        assert_eq!(metadata.owner_info.unwrap().user_name.as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn deny_and_allow_policies() {
+        let redactor = redactor(RedactionConfig {
+            allow_applications: vec!["trusted".to_string()],
+            deny_applications: vec!["app.exe".to_string()],
+            ..RedactionConfig::default()
+        });
+
+        let mut denied = metadata("APP.EXE");
+        redactor.redact_metadata(&mut denied);
+        assert_eq!(denied.executable_path.as_deref(), Some("[REDACTED]"));
+        assert_eq!(denied.modules[0].module_path, "[REDACTED]");
+        assert_eq!(denied.owner_info.unwrap().user_name.as_deref(), Some("[REDACTED]"));
+        assert_eq!(denied.network_connections[0].remote_address.as_deref(), Some("[REDACTED]"));
+        assert!(denied.environment_variables.is_empty());
+
+        let mut allowed = metadata("trusted");
+        redactor.redact_metadata(&mut allowed);
+        assert_eq!(allowed.command_line, metadata("trusted").command_line);
+    }
+
+    #[test]
+    fn scan_results_are_redacted() {
+        let redactor = redactor(RedactionConfig {
+            hash_titles: true,
+            keywords: vec!["secret-project".to_string()],
+            ..RedactionConfig::default()
+        });
+        let process = |pid: u32, title: Option<&str>, subprocesses: Vec<ProcessInfo>| ProcessInfo {
+            pid,
+            name: "app".to_string(),
+            window_title: title.map(|t| t.to_string()),
+            executable_path: Some("/home/alice/secret-project/app".to_string()),
+            subprocesses,
+            role: None,
+            utility_sub_type: None,
+        };
+        let mut scan = ScanResult {
+            applications: vec![ApplicationInfo {
+                main_process: process(1, Some("Inbox"), vec![process(2, Some("Draft"), Vec::new())]),
+                total_processes: 2,
+                identity: Some(crate::models::AppIdentity {
+                    display_name: "App".to_string(),
+                    vendor: None,
+                    icon: Some("/home/alice/secret-project/app.png".to_string()),
+                    app_id: None,
+                    source: "desktop_entry".to_string(),
+                }),
+                roles: Vec::new(),
+                container_info: None,
+            }],
+            scan_timestamp: String::new(),
+            total_applications: 1,
+        };
+        redactor.redact_scan(&mut scan);
+
+        let application = &scan.applications[0];
+        assert_eq!(application.identity.as_ref().unwrap().display_name, "App");
+        assert_eq!(application.identity.as_ref().unwrap().icon.as_deref(), Some("[REDACTED]"));
+        assert!(application.main_process.window_title.as_deref().unwrap().starts_with("sha256:"));
+        let subprocess = &application.main_process.subprocesses[0];
+        assert!(subprocess.window_title.as_deref().unwrap().starts_with("sha256:"));
+        assert_eq!(subprocess.executable_path.as_deref(), Some("[REDACTED]"));
+    }
+}
@@ -0,0 +1,281 @@
+use crate::models::{ProcessEntry, ThreadInfo, WindowInfo};
+use anyhow::Result;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Durée de validité par défaut d'un snapshot partagé
+pub const DEFAULT_SNAPSHOT_TTL: Duration = Duration::from_millis(500);
+
+/// Photographie du système (processus, threads, fenêtres) prise en une fois
+/// et relue par le scanner, les collecteurs et le moniteur
+#[derive(Debug, Clone)]
+pub struct SystemSnapshot {
+    pub processes: Vec<ProcessEntry>,
+    /// Threads de tous les processus (Windows uniquement : sous Linux ils sont lus à la demande par PID)
+    pub threads: Vec<ThreadInfo>,
+    pub windows: Vec<WindowInfo>,
+    pub captured_at: u64, // millisecondes depuis l'epoch
+    captured: Instant,
+}
+
+impl SystemSnapshot {
+    #[cfg(windows)]
+    pub fn capture() -> Result<Self> {
+        // Un seul snapshot Toolhelp pour les processus et les threads
+        let (processes, threads) = unsafe { windows_capture::processes_and_threads()? };
+        let windows = unsafe { windows_capture::windows() };
+        Ok(Self::new(processes, threads, windows))
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn capture() -> Result<Self> {
+        use crate::linux::x11::X11WindowBackend;
+
+        let processes = crate::ProcessScanner::new().list_process_entries()?;
+        // Hors session X11 (console, Wayland pur), aucune fenêtre n'est visible
+        let windows = match X11WindowBackend::shared() {
+            Some(backend) => backend.list_windows()?,
+            None => Vec::new(),
+        };
+        Ok(Self::new(processes, Vec::new(), windows))
+    }
+
+    pub(crate) fn new(processes: Vec<ProcessEntry>, threads: Vec<ThreadInfo>, windows: Vec<WindowInfo>) -> Self {
+        Self {
+            processes,
+            threads,
+            windows,
+            captured_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            captured: Instant::now(),
+        }
+    }
+
+    /// Copie limitée aux processus retenus, avec leurs seuls threads et fenêtres
+    pub fn filtered(&self, keep: impl Fn(&ProcessEntry) -> bool) -> Self {
+        let processes: Vec<ProcessEntry> = self.processes.iter().filter(|entry| keep(entry)).cloned().collect();
+        let pids: HashSet<u32> = processes.iter().map(|entry| entry.pid).collect();
+        Self {
+            threads: self.threads.iter().filter(|thread| pids.contains(&thread.process_id)).cloned().collect(),
+            windows: self.windows.iter().filter(|window| pids.contains(&window.process_id)).cloned().collect(),
+            processes,
+            captured_at: self.captured_at,
+            captured: self.captured,
+        }
+    }
+
+    pub fn age(&self) -> Duration {
+        self.captured.elapsed()
+    }
+
+    pub fn process(&self, pid: u32) -> Option<&ProcessEntry> {
+        self.processes.iter().find(|entry| entry.pid == pid)
+    }
+
+    pub fn process_name(&self, pid: u32) -> Option<&str> {
+        self.process(pid).map(|entry| entry.name.as_str())
+    }
+
+    /// Enfants directs d'un processus
+    pub fn children(&self, parent_pid: u32) -> impl Iterator<Item = &ProcessEntry> {
+        self.processes
+            .iter()
+            .filter(move |entry| entry.parent_pid == parent_pid && entry.pid != parent_pid)
+    }
+
+    pub fn threads_for(&self, pid: u32) -> Vec<ThreadInfo> {
+        self.threads
+            .iter()
+            .filter(|thread| thread.process_id == pid)
+            .cloned()
+            .collect()
+    }
+
+    pub fn windows_for(&self, pid: u32) -> Vec<WindowInfo> {
+        self.windows
+            .iter()
+            .filter(|window| window.process_id == pid)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Cache de snapshot avec durée de validité, partageable entre threads
+pub struct SnapshotCache {
+    ttl: Duration,
+    current: Mutex<Option<Arc<SystemSnapshot>>>,
+}
+
+static SHARED_CACHE: OnceLock<Arc<SnapshotCache>> = OnceLock::new();
+
+impl SnapshotCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            current: Mutex::new(None),
+        }
+    }
+
+    /// Cache commun au processus, utilisé par défaut par le scanner et les collecteurs
+    pub fn shared() -> Arc<Self> {
+        Arc::clone(SHARED_CACHE.get_or_init(|| Arc::new(Self::new(DEFAULT_SNAPSHOT_TTL))))
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Snapshot courant, repris si expiré
+    pub fn get(&self) -> Result<Arc<SystemSnapshot>> {
+        let mut current = self.current.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(snapshot) = current.as_ref().filter(|snapshot| snapshot.age() < self.ttl) {
+            return Ok(Arc::clone(snapshot));
+        }
+
+        let snapshot = Arc::new(SystemSnapshot::capture()?);
+        *current = Some(Arc::clone(&snapshot));
+        Ok(snapshot)
+    }
+
+    /// Forcer une nouvelle capture (processus inconnu du snapshot courant)
+    pub fn refresh(&self) -> Result<Arc<SystemSnapshot>> {
+        self.invalidate();
+        self.get()
+    }
+
+    /// Invalider le snapshot (démarrage ou arrêt de processus détecté)
+    pub fn invalidate(&self) {
+        *self.current.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    }
+}
+
+#[cfg(windows)]
+mod windows_capture {
+    use crate::metadata::process_metadata::window_info_from_hwnd;
+    use crate::models::{ProcessEntry, ThreadInfo, WindowInfo};
+    use anyhow::Result;
+    use std::mem;
+    use winapi::shared::minwindef::{BOOL, LPARAM};
+    use winapi::shared::windef::HWND;
+    use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+    use winapi::um::tlhelp32::{
+        CreateToolhelp32Snapshot, Process32First, Process32Next, Thread32First, Thread32Next,
+        PROCESSENTRY32, THREADENTRY32, TH32CS_SNAPPROCESS, TH32CS_SNAPTHREAD,
+    };
+    use winapi::um::winuser::EnumWindows;
+
+    pub unsafe fn processes_and_threads() -> Result<(Vec<ProcessEntry>, Vec<ThreadInfo>)> {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS | TH32CS_SNAPTHREAD, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return Err(anyhow::anyhow!("Impossible de créer le snapshot des processus"));
+        }
+
+        let mut processes = Vec::new();
+        let mut pe32: PROCESSENTRY32 = mem::zeroed();
+        pe32.dwSize = mem::size_of::<PROCESSENTRY32>() as u32;
+        if Process32First(snapshot, &mut pe32) != 0 {
+            loop {
+                let name = std::ffi::CStr::from_ptr(pe32.szExeFile.as_ptr())
+                    .to_string_lossy()
+                    .to_string();
+                processes.push(ProcessEntry {
+                    pid: pe32.th32ProcessID,
+                    parent_pid: pe32.th32ParentProcessID,
+                    name,
+                    start_time: None, // Non fourni par Toolhelp
+                });
+
+                if Process32Next(snapshot, &mut pe32) == 0 {
+                    break;
+                }
+            }
+        }
+
+        let mut threads = Vec::new();
+        let mut te32: THREADENTRY32 = mem::zeroed();
+        te32.dwSize = mem::size_of::<THREADENTRY32>() as u32;
+        if Thread32First(snapshot, &mut te32) != 0 {
+            loop {
+                threads.push(ThreadInfo {
+                    thread_id: te32.th32ThreadID,
+                    process_id: te32.th32OwnerProcessID,
+                    creation_time: None,
+                    exit_time: None,
+                    kernel_time: 0,
+                    user_time: 0,
+                    priority: 0,
+                    base_priority: te32.tpBasePri,
+                    context_switches: te32.dwFlags,
+                });
+
+                if Thread32Next(snapshot, &mut te32) == 0 {
+                    break;
+                }
+            }
+        }
+
+        CloseHandle(snapshot);
+        Ok((processes, threads))
+    }
+
+    pub unsafe fn windows() -> Vec<WindowInfo> {
+        let mut windows = Vec::new();
+        EnumWindows(Some(collect_window_proc), &mut windows as *mut Vec<WindowInfo> as LPARAM);
+        windows
+    }
+
+    unsafe extern "system" fn collect_window_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let windows = &mut *(lparam as *mut Vec<WindowInfo>);
+        windows.push(window_info_from_hwnd(hwnd));
+        1 // Continue enumeration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pid: u32, parent_pid: u32, name: &str) -> ProcessEntry {
+        ProcessEntry {
+            pid,
+            parent_pid,
+            name: name.to_string(),
+            start_time: None,
+        }
+    }
+
+    fn window(process_id: u32) -> WindowInfo {
+        WindowInfo {
+            hwnd: process_id as u64 * 16,
+            class_name: "Chrome_WidgetWin_1".to_string(),
+            window_title: format!("Fenêtre de {}", process_id),
+            process_id,
+            thread_id: 0,
+            is_visible: true,
+            window_rect: None,
+            browser_tab: None,
+        }
+    }
+
+    #[test]
+    fn filtered_snapshot_hides_other_processes_everywhere() {
+        // chrome (10) de l'utilisateur, dont un enfant (12) appartient à un autre utilisateur
+        let snapshot = SystemSnapshot::new(
+            vec![entry(1, 0, "init"), entry(10, 1, "chrome"), entry(11, 10, "chrome"), entry(12, 10, "chrome")],
+            Vec::new(),
+            vec![window(10), window(12)],
+        );
+        let filtered = snapshot.filtered(|entry| entry.pid == 10 || entry.pid == 11);
+
+        let children: Vec<u32> = filtered.children(10).map(|child| child.pid).collect();
+        assert_eq!(children, [11]);
+        assert!(filtered.process(12).is_none());
+        assert!(filtered.process(1).is_none());
+        assert_eq!(filtered.windows.len(), 1);
+        assert!(filtered.windows_for(12).is_empty());
+        assert_eq!(filtered.captured_at, snapshot.captured_at);
+    }
+}
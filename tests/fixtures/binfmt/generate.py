@@ -0,0 +1,220 @@
+#!/usr/bin/env python3
+"""Génère les binaires minimaux utilisés par les tests de src/binfmt.
+
+Les fichiers sont versionnés : ce script ne sert qu'à les régénérer (python3 generate.py).
+Chaque binaire ne contient que les structures lues par le parseur (en-têtes, segments,
+notes, section dynamique, répertoire de ressources), sans code exécutable.
+"""
+import os
+import struct
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+
+BUILD_ID = bytes(range(0x10, 0x24))  # 20 octets, comme un SHA-1 de ld
+SONAME = b"libfixture.so.1"
+
+PT_LOAD, PT_DYNAMIC, PT_NOTE = 1, 2, 4
+DT_NULL, DT_STRTAB, DT_SONAME = 0, 5, 14
+
+
+def align(value, boundary):
+    return (value + boundary - 1) // boundary * boundary
+
+
+def elf(is_64_bit, big_endian, machine, build_id=None, soname=None):
+    order = ">" if big_endian else "<"
+    word = "Q" if is_64_bit else "I"
+    base = 0x400000 if is_64_bit else 0x08048000
+    header_size = 64 if is_64_bit else 52
+    phentsize = 56 if is_64_bit else 32
+
+    segments = [PT_LOAD]
+    if build_id is not None:
+        segments.append(PT_NOTE)
+    if soname is not None:
+        segments.append(PT_DYNAMIC)
+    # En-tête complété à 64 octets : le parseur lit toujours 64 octets
+    body_offset = align(64 + phentsize * len(segments), 16)
+    body = bytearray()
+
+    note_offset = None
+    if build_id is not None:
+        note_offset = body_offset + len(body)
+        body += struct.pack(order + "III", 4, len(build_id), 3) + b"GNU\0" + build_id
+        body += b"\0" * (align(len(body), 8) - len(body))
+
+    dynamic_offset = dynamic_size = None
+    if soname is not None:
+        strtab_offset = body_offset + len(body)
+        body += b"\0" + soname + b"\0"
+        body += b"\0" * (align(len(body), 8) - len(body))
+        dynamic_offset = body_offset + len(body)
+        entries = [(DT_STRTAB, base + strtab_offset), (DT_SONAME, 1), (DT_NULL, 0)]
+        for tag, value in entries:
+            body += struct.pack(order + word + word, tag, value)
+        dynamic_size = body_offset + len(body) - dynamic_offset
+
+    file_size = body_offset + len(body)
+
+    def program_header(kind, offset, size):
+        # Notes alignées sur 4 octets (Elf_Nhdr), le reste sur la taille d'un mot
+        alignment = 4 if kind == PT_NOTE or not is_64_bit else 8
+        if is_64_bit:
+            return struct.pack(order + "IIQQQQQQ", kind, 4, offset, base + offset, base + offset, size, size, alignment)
+        return struct.pack(order + "IIIIIIII", kind, offset, base + offset, base + offset, size, size, 4, alignment)
+
+    headers = bytearray()
+    for kind in segments:
+        if kind == PT_LOAD:
+            headers += program_header(PT_LOAD, 0, file_size)
+        elif kind == PT_NOTE:
+            headers += program_header(PT_NOTE, note_offset, 16 + len(build_id))
+        else:
+            headers += program_header(PT_DYNAMIC, dynamic_offset, dynamic_size)
+
+    ident = b"\x7fELF" + bytes([2 if is_64_bit else 1, 2 if big_endian else 1, 1]) + b"\0" * 9
+    kind = 3 if soname is not None else 2  # ET_DYN ou ET_EXEC
+    if is_64_bit:
+        header = ident + struct.pack(
+            order + "HHIQQQIHHHHHH", kind, machine, 1, 0, 64, 0, 0, header_size, phentsize, len(segments), 0, 0, 0
+        )
+    else:
+        header = ident + struct.pack(
+            order + "HHIIIIIHHHHHH", kind, machine, 1, 0, 64, 0, 0, header_size, phentsize, len(segments), 0, 0, 0
+        )
+
+    data = bytearray(header)
+    data += b"\0" * (64 - len(data))
+    data += headers
+    data += b"\0" * (body_offset - len(data))
+    data += body
+    return bytes(data)
+
+
+def utf16(text):
+    return text.encode("utf-16-le") + b"\0\0"
+
+
+def version_block(key, value=b"", is_text=False, children=(), value_length=None):
+    data = bytearray(struct.pack("<HHH", 0, 0, 1 if is_text else 0))
+    data += utf16(key)
+    data += b"\0" * (align(len(data), 4) - len(data))
+    data += value
+    for child in children:
+        data += b"\0" * (align(len(data), 4) - len(data))
+        data += child
+    if value_length is None:
+        value_length = len(value) // 2 if is_text else len(value)
+    struct.pack_into("<HH", data, 0, len(data), value_length)
+    return bytes(data)
+
+
+def version_info(file_version, strings_by_language):
+    major, minor, patch, build = file_version
+    fixed = struct.pack(
+        "<13I",
+        0xFEEF04BD, 0x00010000,
+        (major << 16) | minor, (patch << 16) | build,
+        (major << 16) | minor, (patch << 16) | build,
+        0x3F, 0, 0x40004, 1, 0, 0, 0,
+    )
+    tables = [
+        version_block(language, children=[version_block(key, utf16(value), True) for key, value in strings], is_text=True)
+        for language, strings in strings_by_language
+    ]
+    string_file_info = version_block("StringFileInfo", children=tables, is_text=True)
+    return version_block("VS_VERSION_INFO", fixed, children=[string_file_info])
+
+
+def resource_directory(resources, section_rva):
+    """Répertoire .rsrc à trois niveaux (type > identifiant > langue), une entrée par type."""
+    directory = bytearray()
+    entries = []  # (position de l'entrée de données, données)
+
+    def table(count):
+        start = len(directory)
+        directory.extend(struct.pack("<IIHHHH", 0, 0, 0, 0, 0, count))
+        directory.extend(b"\0" * (8 * count))
+        return start
+
+    root = table(len(resources))
+    for index, (resource_type, resource_id, language, data) in enumerate(resources):
+        names = table(1)
+        struct.pack_into("<II", directory, root + 16 + index * 8, resource_type, 0x80000000 | names)
+        languages = table(1)
+        struct.pack_into("<II", directory, names + 16, resource_id, 0x80000000 | languages)
+        data_entry = len(directory)
+        directory.extend(b"\0" * 16)
+        struct.pack_into("<II", directory, languages + 16, language, data_entry)
+        entries.append((data_entry, data))
+
+    for data_entry, data in entries:
+        directory.extend(b"\0" * (align(len(directory), 8) - len(directory)))
+        struct.pack_into("<IIII", directory, data_entry, section_rva + len(directory), len(data), 0, 0)
+        directory.extend(data)
+    return bytes(directory)
+
+
+def pe(machine, is_pe32_plus, resources):
+    section_rva = 0x1000
+    raw_offset = 0x200
+    rsrc = resource_directory(resources, section_rva) if resources else b""
+
+    directories = 112 if is_pe32_plus else 96
+    optional = bytearray(directories + 16 * 8)
+    struct.pack_into("<H", optional, 0, 0x20B if is_pe32_plus else 0x10B)
+    struct.pack_into("<I", optional, directories - 4, 16)  # NumberOfRvaAndSizes
+    if rsrc:
+        struct.pack_into("<II", optional, directories + 2 * 8, section_rva, len(rsrc))
+
+    section = bytearray(40)
+    section[:8] = b".rsrc\0\0\0"
+    raw_size = align(max(len(rsrc), 1), 0x200)
+    struct.pack_into("<IIII", section, 8, len(rsrc), section_rva, raw_size, raw_offset)
+    struct.pack_into("<I", section, 36, 0x40000040)
+
+    dos = bytearray(64)
+    dos[:2] = b"MZ"
+    struct.pack_into("<I", dos, 0x3C, 64)
+    characteristics = 0x0022 if is_pe32_plus else 0x0102
+    file_header = struct.pack("<HHIIIHH", machine, 1, 0, 0, 0, len(optional), characteristics)
+
+    data = bytearray(dos + b"PE\0\0" + file_header + optional + section)
+    data += b"\0" * (raw_offset - len(data))
+    data += rsrc
+    data += b"\0" * (raw_offset + raw_size - len(data))
+    return bytes(data)
+
+
+def write(name, data):
+    with open(os.path.join(HERE, name), "wb") as file:
+        file.write(data)
+
+
+def main():
+    write("elf64_le_build_id.elf", elf(True, False, 62, build_id=BUILD_ID))
+    write("elf64_le_soname.elf", elf(True, False, 183, soname=SONAME))
+    write("elf32_le.elf", elf(False, False, 3, build_id=BUILD_ID, soname=SONAME))
+    write("elf64_be.elf", elf(True, True, 21, build_id=BUILD_ID, soname=SONAME))
+
+    strings = [
+        ("CompanyName", "Fixture Corp"),
+        ("FileDescription", "Fixture Application"),
+        ("FileVersion", "1.2.3"),
+        ("ProductName", "Fixture"),
+        ("ProductVersion", "1.2"),
+        ("OriginalFilename", "fixture.exe"),
+    ]
+    # Table française en premier : la table anglaise doit être préférée
+    french = [("040C04B0", [("ProductName", "Appli de test")]), ("040904B0", strings)]
+    version = version_info((1, 2, 3, 4), french)
+    icon = b"\x28\0\0\0" + b"\0" * 36  # En-tête BITMAPINFOHEADER seul, le contenu n'est pas décodé
+
+    write("pe32_version.exe", pe(0x014C, False, [(16, 1, 0x0409, version)]))
+    write("pe32_no_version.dll", pe(0x014C, False, [(3, 1, 0x0409, icon)]))
+    write("pe32plus_version.exe", pe(0x8664, True, [(3, 1, 0x0409, icon), (16, 1, 0x0409, version)]))
+    write("version_info.bin", version)
+
+
+if __name__ == "__main__":
+    main()
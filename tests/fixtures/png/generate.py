@@ -0,0 +1,170 @@
+#!/usr/bin/env python3
+"""Génère les PNG de conformité utilisés par les tests de src/icons/png.rs.
+
+Les fichiers sont versionnés : ce script ne sert qu'à les régénérer (python3 generate.py).
+Les flux sont compressés par zlib (codes dynamiques, fixes ou blocs stockés), indépendamment
+de l'encodeur du crate. Chaque <nom>.png est accompagné de <nom>.rgba : les pixels attendus
+en RGBA 8 bits, calculés ici d'après la spécification PNG.
+"""
+import os
+import struct
+import zlib
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+
+WIDTH, HEIGHT = 5, 6  # Largeur impaire : lignes à octet incomplet en profondeur < 8
+CHANNELS = {0: 1, 2: 3, 3: 1, 4: 2, 6: 4}
+PALETTE = [((index * 37) % 256, (index * 91) % 256, (255 - index * 13) % 256) for index in range(20)]
+TRANSPARENCY = [0, 128, 200]
+
+
+def sample(x, y, channel, depth):
+    return ((x * 7 + y * 13 + channel * 29) * 2654435761 >> 7) % (1 << depth)
+
+
+def palette_index(x, y, depth):
+    return (x + y * WIDTH) % min(1 << depth, len(PALETTE))
+
+
+def pixels(color_type, depth):
+    rows = []
+    for y in range(HEIGHT):
+        if color_type == 3:
+            rows.append([[palette_index(x, y, depth)] for x in range(WIDTH)])
+        else:
+            rows.append([[sample(x, y, c, depth) for c in range(CHANNELS[color_type])] for x in range(WIDTH)])
+    return rows
+
+
+def pack_row(row, depth):
+    if depth == 16:
+        return b"".join(struct.pack(">H", value) for pixel in row for value in pixel)
+    if depth == 8:
+        return bytes(value for pixel in row for value in pixel)
+    data = bytearray()
+    bits, count = 0, 0
+    for pixel in row:
+        for value in pixel:
+            bits = (bits << depth) | value
+            count += depth
+            if count == 8:
+                data.append(bits)
+                bits, count = 0, 0
+    if count:
+        data.append(bits << (8 - count))
+    return bytes(data)
+
+
+def paeth(left, up, up_left):
+    estimate = left + up - up_left
+    distances = (abs(estimate - left), abs(estimate - up), abs(estimate - up_left))
+    if distances[0] <= distances[1] and distances[0] <= distances[2]:
+        return left
+    return up if distances[1] <= distances[2] else up_left
+
+
+def filter_row(kind, row, previous, bpp):
+    output = bytearray([kind])
+    for index, byte in enumerate(row):
+        left = row[index - bpp] if index >= bpp else 0
+        up = previous[index] if previous else 0
+        up_left = previous[index - bpp] if previous and index >= bpp else 0
+        predictor = [0, left, up, (left + up) // 2, paeth(left, up, up_left)][kind]
+        output.append((byte - predictor) % 256)
+    return bytes(output)
+
+
+def expected_rgba(color_type, depth, rows):
+    output = bytearray()
+    for row in rows:
+        for pixel in row:
+            if color_type == 3:
+                index = pixel[0]
+                alpha = TRANSPARENCY[index] if index < len(TRANSPARENCY) else 255
+                output += bytes(PALETTE[index]) + bytes([alpha])
+                continue
+            if depth == 16:
+                values = [value >> 8 for value in pixel]
+            elif depth < 8:
+                values = [value * 255 // ((1 << depth) - 1) for value in pixel]
+            else:
+                values = list(pixel)
+            if color_type == 0:
+                output += bytes([values[0]] * 3 + [255])
+            elif color_type == 2:
+                output += bytes(values + [255])
+            elif color_type == 4:
+                output += bytes([values[0]] * 3 + [values[1]])
+            else:
+                output += bytes(values)
+    return bytes(output)
+
+
+def chunk(kind, body):
+    return struct.pack(">I", len(body)) + kind + body + struct.pack(">I", zlib.crc32(kind + body))
+
+
+def png(color_type, depth, compress, split_idat=False):
+    rows = pixels(color_type, depth)
+    bpp = max(1, CHANNELS[color_type] * depth // 8)
+    raw = bytearray()
+    previous = None
+    for y, row in enumerate(rows):
+        packed = pack_row(row, depth)
+        raw += filter_row(y % 5, packed, previous, bpp)  # Les cinq filtres, ligne par ligne
+        previous = packed
+
+    data = b"\x89PNG\r\n\x1a\n"
+    data += chunk(b"IHDR", struct.pack(">IIBBBBB", WIDTH, HEIGHT, depth, color_type, 0, 0, 0))
+    if color_type == 3:
+        data += chunk(b"PLTE", b"".join(bytes(color) for color in PALETTE))
+        data += chunk(b"tRNS", bytes(TRANSPARENCY))
+    data += chunk(b"tEXt", b"Comment\0fixture")
+    compressed = compress(bytes(raw))
+    if split_idat:
+        middle = len(compressed) // 2
+        data += chunk(b"IDAT", compressed[:middle]) + chunk(b"IDAT", compressed[middle:])
+    else:
+        data += chunk(b"IDAT", compressed)
+    data += chunk(b"IEND", b"")
+    return data, expected_rgba(color_type, depth, rows)
+
+
+def dynamic(raw):
+    return zlib.compress(raw, 9)
+
+
+def stored(raw):
+    return zlib.compress(raw, 0)
+
+
+def fixed(raw):
+    compressor = zlib.compressobj(9, zlib.DEFLATED, 15, 9, zlib.Z_FIXED)
+    return compressor.compress(raw) + compressor.flush()
+
+
+def write(name, result):
+    data, rgba = result
+    with open(os.path.join(HERE, name + ".png"), "wb") as file:
+        file.write(data)
+    with open(os.path.join(HERE, name + ".rgba"), "wb") as file:
+        file.write(rgba)
+
+
+def main():
+    formats = [
+        ("gray1", 0, 1), ("gray2", 0, 2), ("gray4", 0, 4), ("gray8", 0, 8), ("gray16", 0, 16),
+        ("rgb8", 2, 8), ("rgb16", 2, 16),
+        ("palette1", 3, 1), ("palette2", 3, 2), ("palette4", 3, 4), ("palette8", 3, 8),
+        ("graya8", 4, 8), ("graya16", 4, 16),
+        ("rgba8", 6, 8), ("rgba16", 6, 16),
+    ]
+    for name, color_type, depth in formats:
+        write(name, png(color_type, depth, dynamic))
+    write("rgba8_stored", png(6, 8, stored))
+    write("rgba8_fixed", png(6, 8, fixed))
+    write("rgba8_split_idat", png(6, 8, dynamic, split_idat=True))
+
+
+if __name__ == "__main__":
+    main()